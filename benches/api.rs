@@ -3,64 +3,64 @@ use enc_rust::kem::*;
 
 pub fn gen_key_benchmark_512(c: &mut Criterion) {
     c.bench_function("key_gen_bench_512", |b| {
-        b.iter(|| generate_keypair_512(None))
+        b.iter(generate_keypair_512_with_default_rng)
     });
 }
 
 pub fn gen_key_benchmark_768(c: &mut Criterion) {
     c.bench_function("key_gen_bench_768", |b| {
-        b.iter(|| generate_keypair_768(None))
+        b.iter(generate_keypair_768_with_default_rng)
     });
 }
 
 pub fn gen_key_benchmark_1024(c: &mut Criterion) {
     c.bench_function("key_gen_bench_1024", |b| {
-        b.iter(|| generate_keypair_1024(None))
+        b.iter(generate_keypair_1024_with_default_rng)
     });
 }
 
 pub fn encap_benchmark_512(c: &mut Criterion) {
-    let (pk, _) = generate_keypair_512(None).unwrap();
+    let (pk, _) = generate_keypair_512_with_default_rng().unwrap();
     c.bench_function("encap_benchmark_512", |b| {
-        b.iter(|| pk.encapsulate(None, None))
+        b.iter(|| pk.encapsulate_with_default_rng())
     });
 }
 
 pub fn encap_benchmark_768(c: &mut Criterion) {
-    let (pk, _) = generate_keypair_768(None).unwrap();
+    let (pk, _) = generate_keypair_768_with_default_rng().unwrap();
     c.bench_function("encap_benchmark_768", |b| {
-        b.iter(|| pk.encapsulate(None, None))
+        b.iter(|| pk.encapsulate_with_default_rng())
     });
 }
 
 pub fn encap_benchmark_1024(c: &mut Criterion) {
-    let (pk, _) = generate_keypair_1024(None).unwrap();
+    let (pk, _) = generate_keypair_1024_with_default_rng().unwrap();
     c.bench_function("encap_benchmark_1024", |b| {
-        b.iter(|| pk.encapsulate(None, None))
+        b.iter(|| pk.encapsulate_with_default_rng())
     });
 }
 
 pub fn decap_benchmark_512(c: &mut Criterion) {
-    let (pk, sk) = generate_keypair_512(None).unwrap();
-    let (ciphertext_obj, _) = pk.encapsulate(None, None).unwrap();
+    let (pk, sk) = generate_keypair_512_with_default_rng().unwrap();
+    let (ciphertext_obj, _) = pk.encapsulate_with_default_rng().unwrap();
     c.bench_function("decap_benchmark_512", |b| {
-        b.iter(|| sk.decapsulate(ciphertext_obj.as_bytes()))
+        b.iter(|| sk.decapsulate(&ciphertext_obj))
     });
 }
 
 pub fn decap_benchmark_768(c: &mut Criterion) {
-    let (pk, sk) = generate_keypair_768(None).unwrap();
-    let (ciphertext_obj, _) = pk.encapsulate(None, None).unwrap();
+    let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    let (ciphertext_obj, _) = pk.encapsulate_with_default_rng().unwrap();
     c.bench_function("decap_benchmark_768", |b| {
-        b.iter(|| sk.decapsulate(ciphertext_obj.as_bytes()))
+        b.iter(|| sk.decapsulate(&ciphertext_obj))
     });
 }
 
 pub fn decap_benchmark_1024(c: &mut Criterion) {
-    let (pk, sk) = generate_keypair_1024(None).unwrap();
-    let (ciphertext_obj, _) = pk.encapsulate(None, None).unwrap();
+    let (pk, sk) = generate_keypair_1024_with_default_rng().unwrap();
+    let (ciphertext_obj, _) = pk.encapsulate_with_default_rng().unwrap();
     c.bench_function("decap_benchmark_1024", |b| {
-        b.iter(|| sk.decapsulate(ciphertext_obj.as_bytes()))
+        b.iter(|| sk.decapsulate(&ciphertext_obj))
     });
 }
 
@@ -1,4 +1,4 @@
-use crate::params::{SecurityLevel, K};
+use crate::params::{SecurityLevel, K, N};
 use core::{
     array::TryFromSliceError,
     fmt::{Display, Formatter},
@@ -6,128 +6,156 @@ use core::{
 };
 use num_enum::TryFromPrimitiveError;
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum CrystalsError {
-    MismatchedSecurityLevels(SecurityLevel, SecurityLevel),
-    IncorrectBufferLength(usize, usize),
-    InvalidSeedLength(usize, usize),
-    InternalError(),
-    InvalidK(usize),
-    InvalidCiphertextLength(usize),
+/// The error type for all fallible operations in `enc_rust`.
+///
+/// Each variant has a fixed meaning, so errors can be matched on to decide how to respond, such
+/// as which alert to send in a protocol. New variants may be added in future releases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A key, ciphertext, seed, or buffer was of the wrong length for its parameter set.
+    InvalidLength {
+        /// The expected length in bytes
+        expected: usize,
+        /// The given length in bytes
+        actual: usize,
+    },
+    /// A key or ciphertext of unknown parameter set, such as one passed to
+    /// [`PublicKey::unpack`](crate::kem::PublicKey::unpack), is not the length of any supported
+    /// parameter set.
+    UnsupportedLength {
+        /// The given length in bytes
+        actual: usize,
+    },
+    /// The input was of the right length, but is not a valid encoding, such as a public key
+    /// failing the FIPS 203 modulus check, or a low order elliptic curve point.
+    InvalidEncoding,
+    /// Two values from different parameter sets were used together, such as decapsulating an
+    /// ML-KEM-512 ciphertext with an ML-KEM-768 private key. Parameter sets are given by their
+    /// name, i.e. 512, 768, or 1024.
+    ParameterMismatch {
+        /// The parameter set that was expected
+        expected: usize,
+        /// The parameter set that was given
+        actual: usize,
+    },
+    /// The random number generator failed.
+    RngFailure,
+    /// A well formed input failed a consistency check, see [`Validation`].
+    ValidationFailure(Validation),
+    /// An HPKE operation failed, see [`Hpke`].
+    HpkeFailure(Hpke),
+    /// An unexpected internal error, which should not occur for any input.
+    Internal,
+}
+
+/// The consistency check that failed, for [`Error::ValidationFailure`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Validation {
+    /// The public key hash stored in an expanded private key does not match the embedded public key.
+    PrivateKeyHash,
+    /// The private key does not decapsulate ciphertexts encapsulated to its own public key.
+    PairwiseConsistency,
+    /// A private key seed does not derive the given public key under any supported key derivation.
+    KeyDerivation,
+}
+
+/// The HPKE operation that failed, for [`Error::HpkeFailure`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Hpke {
+    /// The PSK mode was used without both a PSK and a PSK ID.
+    PskInputs,
+    /// A ciphertext failed to decrypt, as it was modified or sealed under a different key.
+    Open,
+    /// The context has sealed or opened the maximum number of messages.
+    MessageLimit,
+    /// The plaintext is longer than the AEAD supports.
+    MessageLength,
+}
+
+impl Error {
+    pub(crate) fn parameter_mismatch(expected: SecurityLevel, actual: SecurityLevel) -> Self {
+        let parameter_set = |sec_level: SecurityLevel| usize::from(sec_level.k()) * N;
+
+        Self::ParameterMismatch {
+            expected: parameter_set(expected),
+            actual: parameter_set(actual),
+        }
+    }
 }
 
-impl Display for CrystalsError {
+impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match *self {
-            Self::MismatchedSecurityLevels(sec_level_1, sec_level_2) => write!(f, "Mismatched security levels when attempting operation: {sec_level_1:#?} and {sec_level_2:#?}"),
-            Self::IncorrectBufferLength(buf_len, expected_buf_len) => write!(f, "Incorrect buffer length for (un)packing. Expected buffer length {expected_buf_len}, got length {buf_len}"),
-            Self::InvalidSeedLength(seed_len, expected_seed_len) => write!(f, "Invalid seed length, expected {expected_seed_len}, got {seed_len}"),
-            Self::InternalError() => write!(f, "Unexpected internal error"),
-            Self::InvalidK(k) => write!(f, "Recieved invalid k value, {k}, expected 2, 3, or 4"),
-            Self::InvalidCiphertextLength(ciphertext_len) => write!(f, "Invalid ciphertext length, expected 768, 1088, or 1568, got {ciphertext_len}"),
+            Self::InvalidLength { expected, actual } => write!(
+                f,
+                "Invalid length, expected {expected} bytes, got {actual} bytes"
+            ),
+            Self::UnsupportedLength { actual } => write!(
+                f,
+                "Unsupported length, {actual} bytes does not match any parameter set"
+            ),
+            Self::InvalidEncoding => write!(f, "Invalid encoding"),
+            Self::ParameterMismatch { expected, actual } => write!(
+                f,
+                "Mismatched parameter sets, expected ML-KEM-{expected}, got ML-KEM-{actual}"
+            ),
+            Self::RngFailure => write!(f, "Random number generator failed"),
+            Self::ValidationFailure(validation) => write!(f, "Validation failed: {validation}"),
+            Self::HpkeFailure(hpke) => write!(f, "HPKE failed: {hpke}"),
+            Self::Internal => write!(f, "Unexpected internal error"),
         }
     }
 }
 
-#[derive(Debug)]
-pub enum PackingError {
-    Crystals(CrystalsError),
-    TryFromPrimitive(TryFromPrimitiveError<K>),
-    TryFromInt(TryFromIntError),
-    TryFromSlice(TryFromSliceError),
-}
-
-impl From<CrystalsError> for PackingError {
-    fn from(error: CrystalsError) -> Self {
-        Self::Crystals(error)
-    }
-}
-
-impl From<TryFromPrimitiveError<K>> for PackingError {
-    fn from(error: TryFromPrimitiveError<K>) -> Self {
-        Self::TryFromPrimitive(error)
-    }
-}
-
-impl From<TryFromIntError> for PackingError {
-    fn from(error: TryFromIntError) -> Self {
-        Self::TryFromInt(error)
-    }
-}
-
-impl From<TryFromSliceError> for PackingError {
-    fn from(error: TryFromSliceError) -> Self {
-        Self::TryFromSlice(error)
-    }
-}
-
-#[derive(Debug)]
-pub enum KeyGenerationError {
-    Crystals(CrystalsError),
-    TryFromSlice(TryFromSliceError),
-    Packing(PackingError),
-    Rand(rand_core::Error),
-}
-
-impl From<CrystalsError> for KeyGenerationError {
-    fn from(error: CrystalsError) -> Self {
-        Self::Crystals(error)
-    }
-}
-
-impl From<TryFromSliceError> for KeyGenerationError {
-    fn from(error: TryFromSliceError) -> Self {
-        Self::TryFromSlice(error)
-    }
-}
-
-impl From<PackingError> for KeyGenerationError {
-    fn from(error: PackingError) -> Self {
-        Self::Packing(error)
+impl Display for Validation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::PrivateKeyHash => write!(f, "private key hash does not match the embedded public key"),
+            Self::PairwiseConsistency => write!(f, "private key does not decapsulate for its own public key"),
+            Self::KeyDerivation => write!(f, "private key seed does not derive the given public key under any supported key derivation"),
+        }
     }
 }
 
-impl From<rand_core::Error> for KeyGenerationError {
-    fn from(error: rand_core::Error) -> Self {
-        Self::Rand(error)
+impl Display for Hpke {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            Self::PskInputs => write!(f, "the PSK mode requires both a PSK and a PSK ID"),
+            Self::Open => write!(f, "ciphertext failed to decrypt"),
+            Self::MessageLimit => write!(f, "message limit of the context reached"),
+            Self::MessageLength => write!(f, "plaintext is longer than the AEAD supports"),
+        }
     }
 }
 
-#[derive(Debug)]
-pub enum EncryptionDecryptionError {
-    Crystals(CrystalsError),
-    KeyGenerationError(KeyGenerationError),
-    TryFromInt(TryFromIntError),
-    Packing(PackingError),
-    Rand(rand_core::Error),
-}
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
 
-impl From<CrystalsError> for EncryptionDecryptionError {
-    fn from(error: CrystalsError) -> Self {
-        Self::Crystals(error)
-    }
-}
+// Conversions only arise from internal invariants, as lengths and values are checked at the API boundary
 
-impl From<KeyGenerationError> for EncryptionDecryptionError {
-    fn from(error: KeyGenerationError) -> Self {
-        Self::KeyGenerationError(error)
+impl From<TryFromPrimitiveError<K>> for Error {
+    fn from(_: TryFromPrimitiveError<K>) -> Self {
+        Self::Internal
     }
 }
 
-impl From<TryFromIntError> for EncryptionDecryptionError {
-    fn from(error: TryFromIntError) -> Self {
-        Self::TryFromInt(error)
+impl From<TryFromIntError> for Error {
+    fn from(_: TryFromIntError) -> Self {
+        Self::Internal
     }
 }
 
-impl From<PackingError> for EncryptionDecryptionError {
-    fn from(error: PackingError) -> Self {
-        Self::Packing(error)
+impl From<TryFromSliceError> for Error {
+    fn from(_: TryFromSliceError) -> Self {
+        Self::Internal
     }
 }
 
-impl From<rand_core::Error> for EncryptionDecryptionError {
-    fn from(error: rand_core::Error) -> Self {
-        Self::Rand(error)
+impl From<rand_core::Error> for Error {
+    fn from(_: rand_core::Error) -> Self {
+        Self::RngFailure
     }
 }
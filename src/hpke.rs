@@ -0,0 +1,1176 @@
+//! Hybrid Public Key Encryption ([RFC 9180](https://www.rfc-editor.org/rfc/rfc9180)) with the
+//! ML-KEM KEMs of [draft-ietf-hpke-pq](https://datatracker.ietf.org/doc/draft-ietf-hpke-pq/).
+//!
+//! A [`Suite`] combines a [`Kem`], [`Kdf`] and [`Aead`]. The sender sets up a [`SenderContext`]
+//! with the recipient's ML-KEM public key, and sends the encapsulated
+//! [`Ciphertext`] alongside its sealed messages. The recipient sets up a
+//! [`ReceiverContext`] with its private key to open them, in the same order.
+//!
+//! The base and PSK modes are supported. The authenticated modes need a Diffie-Hellman KEM, and so
+//! are not available with ML-KEM.
+//!
+//! | KEM          | ID       | KDF         | ID       | AEAD               | ID       |
+//! |--------------|----------|-------------|----------|--------------------|----------|
+//! | `ML-KEM-512` | `0x0040` | HKDF-SHA256 | `0x0001` | AES-128-GCM        | `0x0001` |
+//! | `ML-KEM-768` | `0x0041` | HKDF-SHA384 | `0x0002` | AES-256-GCM        | `0x0002` |
+//! | `ML-KEM-1024`| `0x0042` | SHAKE128    | `0x0010` | ChaCha20-Poly1305  | `0x0003` |
+//! |              |          | SHAKE256    | `0x0011` |                    |          |
+//!
+//! # Example
+//! ```
+//! use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+//!
+//! let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+//! let (pk, sk) = generate_keypair_768_with_default_rng()?;
+//!
+//! // Sender
+//! let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, b"info")?;
+//! let mut ciphertext = [0u8; 5 + TAG_BYTES];
+//! sender.seal(b"aad", b"hello", &mut ciphertext)?;
+//!
+//! // Recipient
+//! let mut receiver = suite.setup_base_receiver(&enc, &sk, b"info")?;
+//! let mut plaintext = [0u8; 5];
+//! receiver.open(b"aad", &ciphertext, &mut plaintext)?;
+//!
+//! assert_eq!(&plaintext, b"hello");
+//!
+//! # Ok::<(), enc_rust::errors::Error>(())
+//! ```
+
+use crate::{
+    errors::{Error, Hpke},
+    kem::{key_pair_from_seed, Ciphertext, KeyDerivation, PrivateKey, PublicKey, SharedSecret},
+    params::{SecurityLevel, K, SYMBYTES},
+};
+use aes_gcm::{
+    aead::{AeadInPlace, KeyInit, Nonce, Tag},
+    Aes128Gcm, Aes256Gcm,
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use chacha20poly1305::ChaCha20Poly1305;
+use core::fmt::{Debug, Formatter};
+use hkdf::{SimpleHkdf, SimpleHkdfExtract};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use sha2::{
+    digest::{core_api::BlockSizeUser, Digest},
+    Sha256, Sha384,
+};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake128, Shake256,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Length of the authentication tag added to each sealed message, in bytes
+pub const TAG_BYTES: usize = 16;
+
+const NONCE_BYTES: usize = 12;
+// Largest `Nk`, for AES-256-GCM and ChaCha20-Poly1305
+const MAX_KEY_BYTES: usize = 32;
+// Largest `Nh`, for SHAKE256
+const MAX_HASH_BYTES: usize = 64;
+
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+const MODE_BASE: u8 = 0x00;
+const MODE_PSK: u8 = 0x01;
+
+/// An HPKE KEM identifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Kem {
+    /// `ML-KEM-512`, `0x0040`
+    MlKem512,
+    /// `ML-KEM-768`, `0x0041`
+    MlKem768,
+    /// `ML-KEM-1024`, `0x0042`
+    MlKem1024,
+}
+
+impl Kem {
+    /// Returns the KEM for an HPKE KEM identifier, or `None` if it is not supported
+    #[must_use]
+    pub const fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0x0040 => Some(Self::MlKem512),
+            0x0041 => Some(Self::MlKem768),
+            0x0042 => Some(Self::MlKem1024),
+            _ => None,
+        }
+    }
+
+    /// Returns the HPKE KEM identifier
+    #[must_use]
+    pub const fn id(self) -> u16 {
+        match self {
+            Self::MlKem512 => 0x0040,
+            Self::MlKem768 => 0x0041,
+            Self::MlKem1024 => 0x0042,
+        }
+    }
+
+    /// Length of the encapsulated key, `Nenc`, in bytes
+    #[must_use]
+    pub const fn enc_bytes(self) -> usize {
+        self.sec_level().ciphertext_bytes()
+    }
+
+    /// Length of an encoded public key, `Npk`, in bytes
+    #[must_use]
+    pub const fn public_key_bytes(self) -> usize {
+        self.sec_level().public_key_bytes()
+    }
+
+    const fn sec_level(self) -> SecurityLevel {
+        match self {
+            Self::MlKem512 => SecurityLevel::new(K::Two),
+            Self::MlKem768 => SecurityLevel::new(K::Three),
+            Self::MlKem1024 => SecurityLevel::new(K::Four),
+        }
+    }
+
+    const fn suite_id(self) -> [u8; 5] {
+        let id = self.id().to_be_bytes();
+        [b'K', b'E', b'M', id[0], id[1]]
+    }
+
+    /// Deterministically derives a keypair from the input keying material, as `DeriveKeyPair`.
+    ///
+    /// `ikm` is expanded to the 64 byte seed `d || z` using SHAKE256, and the keypair generated
+    /// from it as in FIPS 203. The returned [`PrivateKey`] holds the seed, which is the HPKE
+    /// encoding of the private key, see [`PrivateKey::to_seed`].
+    ///
+    /// # Inputs
+    /// - `ikm`: Input keying material of any length, which should have at least 32 bytes of
+    ///   entropy
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if key generation fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hpke::*;
+    /// let (pk, sk) = Kem::MlKem768.derive_key_pair(&[7u8; 64])?;
+    /// assert_eq!(Kem::MlKem768.derive_key_pair(&[7u8; 64])?.0, pk);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn derive_key_pair(self, ikm: &[u8]) -> Result<(PublicKey, PrivateKey), Error> {
+        let mut seed = [0u8; 2 * SYMBYTES];
+        labeled_derive::<Shake256>(&[ikm], &self.suite_id(), b"DeriveKeyPair", &[], &mut seed)?;
+
+        key_pair_from_seed(seed, self.sec_level(), KeyDerivation::Fips203)
+    }
+}
+
+/// An HPKE KDF identifier.
+///
+/// The HKDF KDFs use the two stage key schedule of RFC 9180, and the SHAKE KDFs the one stage key
+/// schedule of draft-ietf-hpke-pq.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Kdf {
+    /// HKDF-SHA256, `0x0001`
+    HkdfSha256,
+    /// HKDF-SHA384, `0x0002`
+    HkdfSha384,
+    /// SHAKE128, `0x0010`
+    Shake128,
+    /// SHAKE256, `0x0011`
+    Shake256,
+}
+
+impl Kdf {
+    /// Returns the KDF for an HPKE KDF identifier, or `None` if it is not supported
+    #[must_use]
+    pub const fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0x0001 => Some(Self::HkdfSha256),
+            0x0002 => Some(Self::HkdfSha384),
+            0x0010 => Some(Self::Shake128),
+            0x0011 => Some(Self::Shake256),
+            _ => None,
+        }
+    }
+
+    /// Returns the HPKE KDF identifier
+    #[must_use]
+    pub const fn id(self) -> u16 {
+        match self {
+            Self::HkdfSha256 => 0x0001,
+            Self::HkdfSha384 => 0x0002,
+            Self::Shake128 => 0x0010,
+            Self::Shake256 => 0x0011,
+        }
+    }
+
+    /// Length of the exporter secret, `Nh`, in bytes
+    #[must_use]
+    pub const fn hash_bytes(self) -> usize {
+        match self {
+            Self::HkdfSha256 | Self::Shake128 => 32,
+            Self::HkdfSha384 => 48,
+            Self::Shake256 => 64,
+        }
+    }
+}
+
+/// An HPKE AEAD identifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Aead {
+    /// AES-128-GCM, `0x0001`
+    Aes128Gcm,
+    /// AES-256-GCM, `0x0002`
+    Aes256Gcm,
+    /// ChaCha20-Poly1305, `0x0003`
+    ChaCha20Poly1305,
+}
+
+impl Aead {
+    /// Returns the AEAD for an HPKE AEAD identifier, or `None` if it is not supported
+    #[must_use]
+    pub const fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0x0001 => Some(Self::Aes128Gcm),
+            0x0002 => Some(Self::Aes256Gcm),
+            0x0003 => Some(Self::ChaCha20Poly1305),
+            _ => None,
+        }
+    }
+
+    /// Returns the HPKE AEAD identifier
+    #[must_use]
+    pub const fn id(self) -> u16 {
+        match self {
+            Self::Aes128Gcm => 0x0001,
+            Self::Aes256Gcm => 0x0002,
+            Self::ChaCha20Poly1305 => 0x0003,
+        }
+    }
+
+    /// Length of the key, `Nk`, in bytes
+    #[must_use]
+    pub const fn key_bytes(self) -> usize {
+        match self {
+            Self::Aes128Gcm => 16,
+            Self::Aes256Gcm | Self::ChaCha20Poly1305 => 32,
+        }
+    }
+}
+
+/// An HPKE ciphersuite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Suite {
+    kem: Kem,
+    kdf: Kdf,
+    aead: Aead,
+}
+
+impl Suite {
+    /// Creates a ciphersuite from its components
+    #[must_use]
+    pub const fn new(kem: Kem, kdf: Kdf, aead: Aead) -> Self {
+        Self { kem, kdf, aead }
+    }
+
+    /// Returns the ciphersuite for the given HPKE identifiers, or `None` if any are not supported
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hpke::*;
+    /// let suite = Suite::from_ids(0x0041, 0x0001, 0x0001).unwrap();
+    /// assert_eq!(suite, Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm));
+    ///
+    /// // DHKEM(X25519, HKDF-SHA256)
+    /// assert_eq!(Suite::from_ids(0x0020, 0x0001, 0x0001), None);
+    /// ```
+    #[must_use]
+    pub const fn from_ids(kem_id: u16, kdf_id: u16, aead_id: u16) -> Option<Self> {
+        match (
+            Kem::from_id(kem_id),
+            Kdf::from_id(kdf_id),
+            Aead::from_id(aead_id),
+        ) {
+            (Some(kem), Some(kdf), Some(aead)) => Some(Self::new(kem, kdf, aead)),
+            _ => None,
+        }
+    }
+
+    /// Returns the KEM of the ciphersuite
+    #[must_use]
+    pub const fn kem(self) -> Kem {
+        self.kem
+    }
+
+    /// Returns the KDF of the ciphersuite
+    #[must_use]
+    pub const fn kdf(self) -> Kdf {
+        self.kdf
+    }
+
+    /// Returns the AEAD of the ciphersuite
+    #[must_use]
+    pub const fn aead(self) -> Aead {
+        self.aead
+    }
+
+    const fn suite_id(self) -> [u8; 10] {
+        let kem = self.kem.id().to_be_bytes();
+        let kdf = self.kdf.id().to_be_bytes();
+        let aead = self.aead.id().to_be_bytes();
+
+        [
+            b'H', b'P', b'K', b'E', kem[0], kem[1], kdf[0], kdf[1], aead[0], aead[1],
+        ]
+    }
+
+    /// Sets up a [`SenderContext`] in the base mode, encapsulating to the recipient's public key
+    ///
+    /// # Inputs
+    /// - `pk_r`: The recipient's public key, of the ciphersuite's KEM
+    /// - `info`: Application supplied information, which must match the recipient's
+    /// - `rng`: RNG to be used during encapsulation. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Outputs
+    /// - [`Ciphertext`]: The encapsulated key, `enc`, to be sent to the recipient
+    /// - [`SenderContext`]
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - RNG fails
+    /// - `pk_r` is not of the ciphersuite's KEM
+    /// - `info` is longer than 65535 bytes with a SHAKE KDF
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_512_with_default_rng};
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// let suite = Suite::new(Kem::MlKem512, Kdf::Shake128, Aead::ChaCha20Poly1305);
+    /// let (pk, sk) = generate_keypair_512_with_default_rng()?;
+    ///
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (enc, sender) = suite.setup_base_sender(&pk, b"info", &mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn setup_base_sender<R: CryptoRng + RngCore + ?Sized>(
+        self,
+        pk_r: &PublicKey,
+        info: &[u8],
+        rng: &mut R,
+    ) -> Result<(Ciphertext, SenderContext), Error> {
+        self.setup_sender(pk_r, MODE_BASE, info, &[], &[], rng)
+    }
+
+    /// Sets up a [`SenderContext`] in the base mode, using
+    /// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source, see [`setup_base_sender`](Suite::setup_base_sender).
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - RNG fails
+    /// - `pk_r` is not of the ciphersuite's KEM
+    /// - `info` is longer than 65535 bytes with a SHAKE KDF
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_1024_with_default_rng};
+    /// let suite = Suite::new(Kem::MlKem1024, Kdf::HkdfSha384, Aead::Aes256Gcm);
+    /// let (pk, sk) = generate_keypair_1024_with_default_rng()?;
+    ///
+    /// let (enc, sender) = suite.setup_base_sender_with_default_rng(&pk, b"info")?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn setup_base_sender_with_default_rng(
+        self,
+        pk_r: &PublicKey,
+        info: &[u8],
+    ) -> Result<(Ciphertext, SenderContext), Error> {
+        self.setup_base_sender(pk_r, info, &mut ChaCha20Rng::from_entropy())
+    }
+
+    /// Sets up a [`SenderContext`] in the PSK mode, encapsulating to the recipient's public key
+    /// and authenticating with a pre-shared key
+    ///
+    /// # Inputs
+    /// - `pk_r`: The recipient's public key, of the ciphersuite's KEM
+    /// - `info`: Application supplied information, which must match the recipient's
+    /// - `psk`: The pre-shared key, which should have at least 32 bytes of entropy
+    /// - `psk_id`: The identifier of the pre-shared key
+    /// - `rng`: RNG to be used during encapsulation. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Outputs
+    /// - [`Ciphertext`]: The encapsulated key, `enc`, to be sent to the recipient
+    /// - [`SenderContext`]
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - RNG fails
+    /// - `pk_r` is not of the ciphersuite's KEM
+    /// - `psk` or `psk_id` is empty
+    /// - `info`, `psk` or `psk_id` is longer than 65535 bytes with a SHAKE KDF
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// let suite = Suite::new(Kem::MlKem768, Kdf::Shake256, Aead::Aes256Gcm);
+    /// let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    ///
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (enc, sender) = suite.setup_psk_sender(&pk, b"info", &[1u8; 32], b"psk id", &mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn setup_psk_sender<R: CryptoRng + RngCore + ?Sized>(
+        self,
+        pk_r: &PublicKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        rng: &mut R,
+    ) -> Result<(Ciphertext, SenderContext), Error> {
+        self.setup_sender(pk_r, MODE_PSK, info, psk, psk_id, rng)
+    }
+
+    /// Sets up a [`SenderContext`] in the PSK mode, using
+    /// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source, see [`setup_psk_sender`](Suite::setup_psk_sender).
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - RNG fails
+    /// - `pk_r` is not of the ciphersuite's KEM
+    /// - `psk` or `psk_id` is empty
+    /// - `info`, `psk` or `psk_id` is longer than 65535 bytes with a SHAKE KDF
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::ChaCha20Poly1305);
+    /// let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    ///
+    /// let (enc, sender) =
+    ///     suite.setup_psk_sender_with_default_rng(&pk, b"info", &[1u8; 32], b"psk id")?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn setup_psk_sender_with_default_rng(
+        self,
+        pk_r: &PublicKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<(Ciphertext, SenderContext), Error> {
+        self.setup_psk_sender(pk_r, info, psk, psk_id, &mut ChaCha20Rng::from_entropy())
+    }
+
+    /// Sets up a [`ReceiverContext`] in the base mode, decapsulating the sender's encapsulated key
+    ///
+    /// # Inputs
+    /// - `enc`: The encapsulated key from the sender
+    /// - `sk_r`: The recipient's private key, of the ciphersuite's KEM
+    /// - `info`: Application supplied information, which must match the sender's
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - `sk_r` or `enc` is not of the ciphersuite's KEM
+    /// - `info` is longer than 65535 bytes with a SHAKE KDF
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// # let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    /// # let (enc, _) = suite.setup_base_sender_with_default_rng(&pk, b"info")?;
+    /// let receiver = suite.setup_base_receiver(&enc, &sk, b"info")?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn setup_base_receiver(
+        self,
+        enc: &Ciphertext,
+        sk_r: &PrivateKey,
+        info: &[u8],
+    ) -> Result<ReceiverContext, Error> {
+        self.setup_receiver(enc, sk_r, MODE_BASE, info, &[], &[])
+    }
+
+    /// Sets up a [`ReceiverContext`] in the PSK mode, decapsulating the sender's encapsulated key
+    /// and authenticating with a pre-shared key
+    ///
+    /// # Inputs
+    /// - `enc`: The encapsulated key from the sender
+    /// - `sk_r`: The recipient's private key, of the ciphersuite's KEM
+    /// - `info`: Application supplied information, which must match the sender's
+    /// - `psk`: The pre-shared key
+    /// - `psk_id`: The identifier of the pre-shared key
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - `sk_r` or `enc` is not of the ciphersuite's KEM
+    /// - `psk` or `psk_id` is empty
+    /// - `info`, `psk` or `psk_id` is longer than 65535 bytes with a SHAKE KDF
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// # let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    /// # let (enc, _) =
+    /// #     suite.setup_psk_sender_with_default_rng(&pk, b"info", &[1u8; 32], b"psk id")?;
+    /// let receiver = suite.setup_psk_receiver(&enc, &sk, b"info", &[1u8; 32], b"psk id")?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn setup_psk_receiver(
+        self,
+        enc: &Ciphertext,
+        sk_r: &PrivateKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<ReceiverContext, Error> {
+        self.setup_receiver(enc, sk_r, MODE_PSK, info, psk, psk_id)
+    }
+
+    fn setup_sender<R: CryptoRng + RngCore + ?Sized>(
+        self,
+        pk_r: &PublicKey,
+        mode: u8,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        rng: &mut R,
+    ) -> Result<(Ciphertext, SenderContext), Error> {
+        let sec_level = self.kem.sec_level();
+        if pk_r.sec_level() != sec_level {
+            return Err(Error::parameter_mismatch(sec_level, pk_r.sec_level()));
+        }
+
+        let (enc, shared_secret) = pk_r.encapsulate(rng)?;
+        let context = self.key_schedule(mode, &shared_secret, info, psk, psk_id)?;
+
+        Ok((enc, SenderContext(context)))
+    }
+
+    fn setup_receiver(
+        self,
+        enc: &Ciphertext,
+        sk_r: &PrivateKey,
+        mode: u8,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<ReceiverContext, Error> {
+        let sec_level = self.kem.sec_level();
+        if sk_r.sec_level() != sec_level {
+            return Err(Error::parameter_mismatch(sec_level, sk_r.sec_level()));
+        }
+
+        let shared_secret = sk_r.decapsulate(enc)?;
+        let context = self.key_schedule(mode, &shared_secret, info, psk, psk_id)?;
+
+        Ok(ReceiverContext(context))
+    }
+
+    fn key_schedule(
+        self,
+        mode: u8,
+        shared_secret: &SharedSecret,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<Context, Error> {
+        key_schedule(
+            self.suite_id(),
+            self.kdf,
+            self.aead,
+            mode,
+            shared_secret.as_bytes(),
+            info,
+            psk,
+            psk_id,
+        )
+    }
+}
+
+/// The sender's encryption context, sealing messages to the recipient.
+///
+/// Messages are sealed under an incrementing nonce, and must be opened by the recipient in the
+/// same order. The keys are zeroised when dropped, and are redacted from the [`Debug`] output.
+pub struct SenderContext(pub(crate) Context);
+
+impl SenderContext {
+    /// Encrypts and authenticates a message, as `Seal`
+    ///
+    /// # Inputs
+    /// - `aad`: Additional data to be authenticated, which must match the recipient's
+    /// - `plaintext`: The message
+    /// - `ciphertext`: Buffer of length `plaintext.len() + TAG_BYTES` for the sealed message
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - `ciphertext` is not of length `plaintext.len() + TAG_BYTES`
+    /// - The context has sealed the maximum number of messages
+    /// - `plaintext` is longer than the AEAD supports
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// # let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    /// # let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, b"info")?;
+    /// let mut ciphertext = [0u8; 5 + TAG_BYTES];
+    /// sender.seal(b"aad", b"hello", &mut ciphertext)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn seal(
+        &mut self,
+        aad: &[u8],
+        plaintext: &[u8],
+        ciphertext: &mut [u8],
+    ) -> Result<(), Error> {
+        self.0.seal(aad, plaintext, ciphertext)
+    }
+
+    /// Encrypts and authenticates a message into a [`Vec`], see [`seal`](SenderContext::seal)
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The context has sealed the maximum number of messages
+    /// - `plaintext` is longer than the AEAD supports
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// # let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    /// # let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, b"info")?;
+    /// let ciphertext = sender.seal_to_vec(b"aad", b"hello")?;
+    /// assert_eq!(ciphertext.len(), 5 + TAG_BYTES);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn seal_to_vec(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut ciphertext = vec![0u8; plaintext.len() + TAG_BYTES];
+        self.seal(aad, plaintext, &mut ciphertext)?;
+
+        Ok(ciphertext)
+    }
+
+    /// Derives a secret from the context, as `Export`
+    ///
+    /// # Inputs
+    /// - `exporter_context`: Application supplied context for the secret
+    /// - `out`: Buffer for the exported secret
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if `out` is longer than 255 times
+    /// [`hash_bytes`](Kdf::hash_bytes) for an HKDF KDF, or 65535 bytes for a SHAKE KDF
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// # let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    /// # let (enc, sender) = suite.setup_base_sender_with_default_rng(&pk, b"info")?;
+    /// # let receiver = suite.setup_base_receiver(&enc, &sk, b"info")?;
+    /// let mut secret = [0u8; 32];
+    /// sender.export(b"context", &mut secret)?;
+    ///
+    /// let mut expected = [0u8; 32];
+    /// receiver.export(b"context", &mut expected)?;
+    /// assert_eq!(secret, expected);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn export(&self, exporter_context: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        self.0.export(exporter_context, out)
+    }
+}
+
+impl Debug for SenderContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SenderContext").finish_non_exhaustive()
+    }
+}
+
+impl ZeroizeOnDrop for SenderContext {}
+
+/// The recipient's encryption context, opening messages from the sender.
+///
+/// Messages are opened under an incrementing nonce, and so must be opened in the order they were
+/// sealed. The keys are zeroised when dropped, and are redacted from the [`Debug`] output.
+pub struct ReceiverContext(pub(crate) Context);
+
+impl ReceiverContext {
+    /// Decrypts and authenticates a message, as `Open`
+    ///
+    /// # Inputs
+    /// - `aad`: Additional data to be authenticated, which must match the sender's
+    /// - `ciphertext`: The sealed message
+    /// - `plaintext`: Buffer of length `ciphertext.len() - TAG_BYTES` for the message
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The ciphertext fails to decrypt, or is shorter than [`TAG_BYTES`]
+    /// - `plaintext` is not of length `ciphertext.len() - TAG_BYTES`
+    /// - The context has opened the maximum number of messages
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// # let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    /// # let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, b"info")?;
+    /// # let mut ciphertext = [0u8; 5 + TAG_BYTES];
+    /// # sender.seal(b"aad", b"hello", &mut ciphertext)?;
+    /// let mut receiver = suite.setup_base_receiver(&enc, &sk, b"info")?;
+    /// let mut plaintext = [0u8; 5];
+    /// receiver.open(b"aad", &ciphertext, &mut plaintext)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn open(
+        &mut self,
+        aad: &[u8],
+        ciphertext: &[u8],
+        plaintext: &mut [u8],
+    ) -> Result<(), Error> {
+        self.0.open(aad, ciphertext, plaintext)
+    }
+
+    /// Decrypts and authenticates a message into a [`Vec`], see [`open`](ReceiverContext::open)
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The ciphertext fails to decrypt, or is shorter than [`TAG_BYTES`]
+    /// - The context has opened the maximum number of messages
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::{hpke::*, kem::generate_keypair_768_with_default_rng};
+    /// # let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng()?;
+    /// # let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, b"info")?;
+    /// # let ciphertext = sender.seal_to_vec(b"aad", b"hello")?;
+    /// let mut receiver = suite.setup_base_receiver(&enc, &sk, b"info")?;
+    /// let plaintext = receiver.open_to_vec(b"aad", &ciphertext)?;
+    /// assert_eq!(plaintext, b"hello");
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn open_to_vec(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        let len = ciphertext
+            .len()
+            .checked_sub(TAG_BYTES)
+            .ok_or(Error::HpkeFailure(Hpke::Open))?;
+        let mut plaintext = vec![0u8; len];
+        self.open(aad, ciphertext, &mut plaintext)?;
+
+        Ok(plaintext)
+    }
+
+    /// Derives a secret from the context, as `Export`, see [`SenderContext::export`]
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if `out` is longer than 255 times
+    /// [`hash_bytes`](Kdf::hash_bytes) for an HKDF KDF, or 65535 bytes for a SHAKE KDF
+    pub fn export(&self, exporter_context: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        self.0.export(exporter_context, out)
+    }
+}
+
+impl Debug for ReceiverContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReceiverContext").finish_non_exhaustive()
+    }
+}
+
+impl ZeroizeOnDrop for ReceiverContext {}
+
+pub(crate) struct Context {
+    suite_id: [u8; 10],
+    kdf: Kdf,
+    aead: Aead,
+    pub(crate) key: [u8; MAX_KEY_BYTES],
+    pub(crate) base_nonce: [u8; NONCE_BYTES],
+    pub(crate) exporter_secret: [u8; MAX_HASH_BYTES],
+    pub(crate) seq: u64,
+}
+
+impl Context {
+    fn key(&self) -> &[u8] {
+        &self.key[..self.aead.key_bytes()]
+    }
+
+    pub(crate) fn exporter_secret(&self) -> &[u8] {
+        &self.exporter_secret[..self.kdf.hash_bytes()]
+    }
+
+    // base_nonce XOR I2OSP(seq, Nn)
+    pub(crate) fn nonce(&self) -> Result<[u8; NONCE_BYTES], Error> {
+        // The last sequence number is never used, so that it can always be incremented
+        if self.seq == u64::MAX {
+            return Err(Error::HpkeFailure(Hpke::MessageLimit));
+        }
+
+        let mut nonce = self.base_nonce;
+        for (byte, seq) in nonce[NONCE_BYTES - 8..]
+            .iter_mut()
+            .zip(self.seq.to_be_bytes())
+        {
+            *byte ^= seq;
+        }
+
+        Ok(nonce)
+    }
+
+    fn seal(&mut self, aad: &[u8], plaintext: &[u8], ciphertext: &mut [u8]) -> Result<(), Error> {
+        if ciphertext.len() != plaintext.len() + TAG_BYTES {
+            return Err(Error::InvalidLength {
+                expected: plaintext.len() + TAG_BYTES,
+                actual: ciphertext.len(),
+            });
+        }
+        let nonce = self.nonce()?;
+
+        let (buffer, tag) = ciphertext.split_at_mut(plaintext.len());
+        buffer.copy_from_slice(plaintext);
+        let result = match self.aead {
+            Aead::Aes128Gcm => seal_with::<Aes128Gcm>(self.key(), &nonce, aad, buffer, tag),
+            Aead::Aes256Gcm => seal_with::<Aes256Gcm>(self.key(), &nonce, aad, buffer, tag),
+            Aead::ChaCha20Poly1305 => {
+                seal_with::<ChaCha20Poly1305>(self.key(), &nonce, aad, buffer, tag)
+            }
+        };
+        if result.is_err() {
+            buffer.zeroize();
+        }
+        result?;
+
+        self.seq += 1;
+        Ok(())
+    }
+
+    fn open(&mut self, aad: &[u8], ciphertext: &[u8], plaintext: &mut [u8]) -> Result<(), Error> {
+        let len = ciphertext
+            .len()
+            .checked_sub(TAG_BYTES)
+            .ok_or(Error::HpkeFailure(Hpke::Open))?;
+        if plaintext.len() != len {
+            return Err(Error::InvalidLength {
+                expected: len,
+                actual: plaintext.len(),
+            });
+        }
+        let nonce = self.nonce()?;
+
+        let (sealed, tag) = ciphertext.split_at(len);
+        plaintext.copy_from_slice(sealed);
+        let result = match self.aead {
+            Aead::Aes128Gcm => open_with::<Aes128Gcm>(self.key(), &nonce, aad, plaintext, tag),
+            Aead::Aes256Gcm => open_with::<Aes256Gcm>(self.key(), &nonce, aad, plaintext, tag),
+            Aead::ChaCha20Poly1305 => {
+                open_with::<ChaCha20Poly1305>(self.key(), &nonce, aad, plaintext, tag)
+            }
+        };
+        if result.is_err() {
+            plaintext.zeroize();
+        }
+        result?;
+
+        self.seq += 1;
+        Ok(())
+    }
+
+    fn export(&self, exporter_context: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        let suite_id = &self.suite_id;
+        let exporter_secret = self.exporter_secret();
+
+        match self.kdf {
+            Kdf::HkdfSha256 => labeled_expand(
+                &prk::<Sha256>(exporter_secret)?,
+                suite_id,
+                b"sec",
+                &[exporter_context],
+                out,
+            ),
+            Kdf::HkdfSha384 => labeled_expand(
+                &prk::<Sha384>(exporter_secret)?,
+                suite_id,
+                b"sec",
+                &[exporter_context],
+                out,
+            ),
+            Kdf::Shake128 => labeled_derive::<Shake128>(
+                &[exporter_secret],
+                suite_id,
+                b"sec",
+                &[exporter_context],
+                out,
+            ),
+            Kdf::Shake256 => labeled_derive::<Shake256>(
+                &[exporter_secret],
+                suite_id,
+                b"sec",
+                &[exporter_context],
+                out,
+            ),
+        }
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.base_nonce.zeroize();
+        self.exporter_secret.zeroize();
+    }
+}
+
+fn seal_with<A: AeadInPlace + KeyInit>(
+    key: &[u8],
+    nonce: &[u8; NONCE_BYTES],
+    aad: &[u8],
+    buffer: &mut [u8],
+    tag: &mut [u8],
+) -> Result<(), Error> {
+    let cipher = A::new_from_slice(key).map_err(|_| Error::Internal)?;
+    let computed = cipher
+        .encrypt_in_place_detached(Nonce::<A>::from_slice(nonce), aad, buffer)
+        .map_err(|_| Error::HpkeFailure(Hpke::MessageLength))?;
+    tag.copy_from_slice(&computed);
+
+    Ok(())
+}
+
+fn open_with<A: AeadInPlace + KeyInit>(
+    key: &[u8],
+    nonce: &[u8; NONCE_BYTES],
+    aad: &[u8],
+    buffer: &mut [u8],
+    tag: &[u8],
+) -> Result<(), Error> {
+    let cipher = A::new_from_slice(key).map_err(|_| Error::Internal)?;
+    cipher
+        .decrypt_in_place_detached(
+            Nonce::<A>::from_slice(nonce),
+            aad,
+            buffer,
+            Tag::<A>::from_slice(tag),
+        )
+        .map_err(|_| Error::HpkeFailure(Hpke::Open))
+}
+
+// KeySchedule<ROLE>, for a ciphersuite given by its `suite_id`
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn key_schedule(
+    suite_id: [u8; 10],
+    kdf: Kdf,
+    aead: Aead,
+    mode: u8,
+    shared_secret: &[u8],
+    info: &[u8],
+    psk: &[u8],
+    psk_id: &[u8],
+) -> Result<Context, Error> {
+    // VerifyPSKInputs, where the PSK mode requires both and the base mode neither
+    if psk.is_empty() != psk_id.is_empty() || psk.is_empty() == (mode == MODE_PSK) {
+        return Err(Error::HpkeFailure(Hpke::PskInputs));
+    }
+
+    let mut context = Context {
+        suite_id,
+        kdf,
+        aead,
+        key: [0u8; MAX_KEY_BYTES],
+        base_nonce: [0u8; NONCE_BYTES],
+        exporter_secret: [0u8; MAX_HASH_BYTES],
+        seq: 0,
+    };
+    let secrets = Secrets {
+        suite_id: &suite_id,
+        mode,
+        shared_secret,
+        info,
+        psk,
+        psk_id,
+    };
+    let key = &mut context.key[..aead.key_bytes()];
+    let base_nonce = &mut context.base_nonce;
+    let exporter_secret = &mut context.exporter_secret[..kdf.hash_bytes()];
+
+    match kdf {
+        Kdf::HkdfSha256 => secrets.two_stage::<Sha256>(key, base_nonce, exporter_secret)?,
+        Kdf::HkdfSha384 => secrets.two_stage::<Sha384>(key, base_nonce, exporter_secret)?,
+        Kdf::Shake128 => secrets.one_stage::<Shake128>(key, base_nonce, exporter_secret)?,
+        Kdf::Shake256 => secrets.one_stage::<Shake256>(key, base_nonce, exporter_secret)?,
+    }
+
+    Ok(context)
+}
+
+struct Secrets<'a> {
+    suite_id: &'a [u8],
+    mode: u8,
+    shared_secret: &'a [u8],
+    info: &'a [u8],
+    psk: &'a [u8],
+    psk_id: &'a [u8],
+}
+
+impl Secrets<'_> {
+    // RFC 9180, with a key schedule context of mode || psk_id_hash || info_hash
+    fn two_stage<H: Digest + BlockSizeUser + Clone>(
+        &self,
+        key: &mut [u8],
+        base_nonce: &mut [u8],
+        exporter_secret: &mut [u8],
+    ) -> Result<(), Error> {
+        let (psk_id_hash, _) =
+            labeled_extract::<H>(self.suite_id, &[], b"psk_id_hash", self.psk_id);
+        let (info_hash, _) = labeled_extract::<H>(self.suite_id, &[], b"info_hash", self.info);
+        let ks_context: [&[u8]; 3] = [&[self.mode], &psk_id_hash, &info_hash];
+
+        let (_, secret) =
+            labeled_extract::<H>(self.suite_id, self.shared_secret, b"secret", self.psk);
+        labeled_expand(&secret, self.suite_id, b"key", &ks_context, key)?;
+        labeled_expand(
+            &secret,
+            self.suite_id,
+            b"base_nonce",
+            &ks_context,
+            base_nonce,
+        )?;
+        labeled_expand(&secret, self.suite_id, b"exp", &ks_context, exporter_secret)
+    }
+
+    // draft-ietf-hpke-pq, deriving key || base_nonce || exporter_secret in a single call
+    fn one_stage<H: Default + Update + ExtendableOutput>(
+        &self,
+        key: &mut [u8],
+        base_nonce: &mut [u8],
+        exporter_secret: &mut [u8],
+    ) -> Result<(), Error> {
+        let secrets: [&[u8]; 4] = [
+            &len_u16(self.psk)?,
+            self.psk,
+            &len_u16(self.shared_secret)?,
+            self.shared_secret,
+        ];
+        let context: [&[u8]; 5] = [
+            &[self.mode],
+            &len_u16(self.psk_id)?,
+            self.psk_id,
+            &len_u16(self.info)?,
+            self.info,
+        ];
+
+        let mut out = [0u8; MAX_KEY_BYTES + NONCE_BYTES + MAX_HASH_BYTES];
+        let len = key.len() + base_nonce.len() + exporter_secret.len();
+        labeled_derive::<H>(
+            &secrets,
+            self.suite_id,
+            b"secret",
+            &context,
+            &mut out[..len],
+        )?;
+
+        let (key_bytes, rest) = out[..len].split_at(key.len());
+        let (nonce_bytes, exporter_bytes) = rest.split_at(base_nonce.len());
+        key.copy_from_slice(key_bytes);
+        base_nonce.copy_from_slice(nonce_bytes);
+        exporter_secret.copy_from_slice(exporter_bytes);
+        out.zeroize();
+
+        Ok(())
+    }
+}
+
+// LabeledExtract(salt, label, ikm), returning the PRK and an HKDF instance keyed with it
+fn labeled_extract<H: Digest + BlockSizeUser + Clone>(
+    suite_id: &[u8],
+    salt: &[u8],
+    label: &[u8],
+    ikm: &[u8],
+) -> (hkdf::hmac::digest::Output<H>, SimpleHkdf<H>) {
+    let mut extract = SimpleHkdfExtract::<H>::new(Some(salt));
+    for part in [VERSION_LABEL, suite_id, label, ikm] {
+        extract.input_ikm(part);
+    }
+
+    extract.finalize()
+}
+
+// LabeledExpand(prk, label, info, L), where info is given in parts
+fn labeled_expand<H: Digest + BlockSizeUser + Clone>(
+    prk: &SimpleHkdf<H>,
+    suite_id: &[u8],
+    label: &[u8],
+    info: &[&[u8]],
+    out: &mut [u8],
+) -> Result<(), Error> {
+    let len = len_u16(out)?;
+    let mut parts: [&[u8]; 7] = [&len, VERSION_LABEL, suite_id, label, &[], &[], &[]];
+    parts[4..4 + info.len()].copy_from_slice(info);
+
+    prk.expand_multi_info(&parts[..4 + info.len()], out)
+        .map_err(|_| Error::InvalidLength {
+            expected: 255 * <H as Digest>::output_size(),
+            actual: out.len(),
+        })
+}
+
+fn prk<H: Digest + BlockSizeUser + Clone>(prk: &[u8]) -> Result<SimpleHkdf<H>, Error> {
+    SimpleHkdf::<H>::from_prk(prk).map_err(|_| Error::Internal)
+}
+
+// LabeledDerive(ikm, label, context, L), where ikm and context are given in parts
+fn labeled_derive<H: Default + Update + ExtendableOutput>(
+    ikm: &[&[u8]],
+    suite_id: &[u8],
+    label: &[u8],
+    context: &[&[u8]],
+    out: &mut [u8],
+) -> Result<(), Error> {
+    let mut hasher = H::default();
+    for part in ikm {
+        hasher.update(part);
+    }
+    hasher.update(VERSION_LABEL);
+    hasher.update(suite_id);
+    hasher.update(&len_u16(label)?);
+    hasher.update(label);
+    hasher.update(&len_u16(out)?);
+    for part in context {
+        hasher.update(part);
+    }
+    hasher.finalize_xof().read(out);
+
+    Ok(())
+}
+
+// I2OSP(len(bytes), 2)
+fn len_u16(bytes: &[u8]) -> Result<[u8; 2], Error> {
+    u16::try_from(bytes.len())
+        .map(u16::to_be_bytes)
+        .map_err(|_| Error::InvalidLength {
+            expected: u16::MAX.into(),
+            actual: bytes.len(),
+        })
+}
@@ -0,0 +1,154 @@
+//! Hybrid KEMs, combining ML-KEM with a classical elliptic curve Diffie-Hellman key exchange.
+//!
+//! A hybrid KEM remains secure as long as either of its components does, protecting against
+//! both a future quantum adversary and any undiscovered weakness in ML-KEM.
+//!
+//! - [`xwing`]: X-Wing, combining ML-KEM-768 and X25519
+//! - [`combiner`]: any ML-KEM parameter set combined with a [`ClassicalKem`](classical::ClassicalKem),
+//!   such as X25519 or X448, under a caller chosen label
+//! - `nist`: ML-KEM-768 with P-256 and ML-KEM-1024 with P-384, as in the `SecP256r1MLKEM768` and
+//!   `SecP384r1MLKEM1024` TLS groups
+//! - [`classical`]: the classical components
+
+pub mod classical;
+pub mod combiner;
+#[cfg(feature = "nist")]
+pub mod nist;
+pub mod xwing;
+
+use crate::errors::Error;
+#[cfg(feature = "nist")]
+use crate::params::SHAREDSECRETBYTES;
+#[cfg(feature = "nist")]
+use core::fmt::{Debug, Formatter};
+#[cfg(feature = "nist")]
+use subtle::{Choice, ConstantTimeEq};
+#[cfg(feature = "nist")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+// Largest concatenated shared secret, a P-384 and an ML-KEM shared secret
+#[cfg(feature = "nist")]
+const MAX_SHARED_SECRET: usize = 48 + SHAREDSECRETBYTES;
+
+/// A shared secret whose length depends on the KEM that produced it, such as the concatenation of
+/// the component shared secrets of a hybrid, or a single ML-KEM shared secret.
+///
+/// The secret is zeroised when dropped, and can only be accessed using the
+/// [`as_bytes`](SharedSecret::as_bytes) method. Equality is checked in constant time, and the
+/// secret is redacted from the [`Debug`] output.
+#[cfg(feature = "nist")]
+#[derive(Clone)]
+pub struct SharedSecret {
+    bytes: [u8; MAX_SHARED_SECRET],
+    len: usize,
+}
+
+// Each hybrid encoding has a single valid length
+pub(crate) const fn check_length(bytes: &[u8], expected: usize) -> Result<(), Error> {
+    if bytes.len() == expected {
+        Ok(())
+    } else {
+        Err(Error::InvalidLength {
+            expected,
+            actual: bytes.len(),
+        })
+    }
+}
+
+// Splits an encoding of length `expected` into its two components, the first of length `mid`
+pub(crate) const fn split_encoding(
+    bytes: &[u8],
+    expected: usize,
+    mid: usize,
+) -> Result<(&[u8], &[u8]), Error> {
+    match check_length(bytes, expected) {
+        Ok(()) => Ok(bytes.split_at(mid)),
+        Err(err) => Err(err),
+    }
+}
+
+// As `split_encoding`, for the output buffer when packing
+pub(crate) fn split_encoding_mut(
+    bytes: &mut [u8],
+    expected: usize,
+    mid: usize,
+) -> Result<(&mut [u8], &mut [u8]), Error> {
+    check_length(bytes, expected)?;
+
+    Ok(bytes.split_at_mut(mid))
+}
+
+#[cfg(feature = "nist")]
+impl SharedSecret {
+    #[cfg(feature = "tls")]
+    pub(crate) fn new(secret: &[u8]) -> Self {
+        let mut bytes = [0u8; MAX_SHARED_SECRET];
+        bytes[..secret.len()].copy_from_slice(secret);
+
+        Self {
+            bytes,
+            len: secret.len(),
+        }
+    }
+
+    // first || second, in the order given by the hybrid
+    pub(crate) fn concat(first: &[u8], second: &[u8]) -> Self {
+        let len = first.len() + second.len();
+        let mut bytes = [0u8; MAX_SHARED_SECRET];
+        bytes[..first.len()].copy_from_slice(first);
+        bytes[first.len()..len].copy_from_slice(second);
+
+        Self { bytes, len }
+    }
+
+    /// Returns a reference to the bytes of the shared secret
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (pk, _) = SecP384r1MlKem1024::generate_keypair_with_default_rng().unwrap();
+    /// let (_, shared_secret) = pk.encapsulate_with_default_rng()?;
+    /// let secret_bytes: &[u8] = shared_secret.as_bytes();
+    /// assert_eq!(secret_bytes.len(), 80);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+#[cfg(feature = "nist")]
+impl ConstantTimeEq for SharedSecret {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.as_bytes().ct_eq(other.as_bytes())
+    }
+}
+
+#[cfg(feature = "nist")]
+impl PartialEq for SharedSecret {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+#[cfg(feature = "nist")]
+impl Eq for SharedSecret {}
+
+#[cfg(feature = "nist")]
+impl Debug for SharedSecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedSecret").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "nist")]
+impl Drop for SharedSecret {
+    fn drop(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+
+#[cfg(feature = "nist")]
+impl ZeroizeOnDrop for SharedSecret {}
@@ -0,0 +1,568 @@
+//! Classical components of the hybrid KEMs, as Diffie-Hellman groups used as KEMs.
+//!
+//! A Diffie-Hellman group is used as a KEM by generating an ephemeral keypair on encapsulation,
+//! sending the ephemeral public key as the ciphertext, and using the Diffie-Hellman output as the
+//! shared secret. Outputs of low order points are rejected with [`Error::InvalidEncoding`].
+
+use crate::errors::Error;
+use core::{
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+};
+#[cfg(feature = "nist")]
+use p256::{
+    elliptic_curve::{
+        ecdh,
+        sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint},
+        AffinePoint, CurveArithmetic, FieldBytesSize, PublicKey as EcPublicKey, SecretKey,
+    },
+    NistP256,
+};
+#[cfg(feature = "nist")]
+use p384::NistP384;
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A classical KEM that can be combined with ML-KEM, see [`combiner`](super::combiner).
+///
+/// Keys, ciphertexts, and shared secrets are held as their encodings. Implemented by [`X25519`],
+/// `X448`, `P256`, and `P384`, and may be implemented for other groups.
+pub trait ClassicalKem: Copy + Debug + Eq {
+    /// Length of an encoded public key in bytes
+    const PUBLIC_KEY_BYTES: usize;
+    /// Length of an encoded private key in bytes
+    const PRIVATE_KEY_BYTES: usize;
+    /// Length of a ciphertext in bytes
+    const CIPHERTEXT_BYTES: usize;
+
+    /// Encoded public key
+    type PublicKey: Copy + Debug + Eq + AsRef<[u8]>;
+    /// Private key, which should be zeroised when dropped
+    type PrivateKey: AsRef<[u8]> + ZeroizeOnDrop;
+    /// Encoded ciphertext
+    type Ciphertext: Copy + Debug + Eq + AsRef<[u8]>;
+    /// Shared secret, which should be zeroised when dropped
+    type SharedSecret: AsRef<[u8]> + ZeroizeOnDrop;
+
+    /// Generates a new keypair
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::PrivateKey), Error>;
+
+    /// Returns the public key of a private key
+    fn public_key(sk: &Self::PrivateKey) -> Self::PublicKey;
+
+    /// Parses an encoded public key
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the public key is of the wrong length, or is not valid
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, Error>;
+
+    /// Parses an encoded private key
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the private key is of the wrong length, or is not valid
+    fn private_key_from_bytes(bytes: &[u8]) -> Result<Self::PrivateKey, Error>;
+
+    /// Parses a ciphertext
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the ciphertext is of the wrong length
+    fn ciphertext_from_bytes(bytes: &[u8]) -> Result<Self::Ciphertext, Error>;
+
+    /// Encapsulates a new shared secret to the public key
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails, or the public key is not valid
+    fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        pk: &Self::PublicKey,
+        rng: &mut R,
+    ) -> Result<(Self::Ciphertext, Self::SharedSecret), Error>;
+
+    /// Decapsulates a ciphertext into the shared secret
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the ciphertext is not valid
+    fn decapsulate(
+        sk: &Self::PrivateKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::SharedSecret, Error>;
+}
+
+/// Private scalar of the Diffie-Hellman group `G`, held as its `N` byte encoding.
+///
+/// The scalar is zeroised when dropped, and is redacted from the [`Debug`] output.
+#[derive(Clone)]
+pub struct PrivateKey<G, const N: usize> {
+    scalar: [u8; N],
+    group: PhantomData<G>,
+}
+
+impl<G, const N: usize> PrivateKey<G, N> {
+    const fn new(scalar: [u8; N]) -> Self {
+        Self {
+            scalar,
+            group: PhantomData,
+        }
+    }
+}
+
+impl<G, const N: usize> AsRef<[u8]> for PrivateKey<G, N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.scalar
+    }
+}
+
+impl<G, const N: usize> Debug for PrivateKey<G, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrivateKey").finish_non_exhaustive()
+    }
+}
+
+impl<G, const N: usize> Drop for PrivateKey<G, N> {
+    fn drop(&mut self) {
+        self.scalar.zeroize();
+    }
+}
+
+impl<G, const N: usize> ZeroizeOnDrop for PrivateKey<G, N> {}
+
+/// Shared secret of a Diffie-Hellman group, held as its `N` byte encoding.
+///
+/// The secret is zeroised when dropped, equality is checked in constant time, and the secret is
+/// redacted from the [`Debug`] output.
+#[derive(Clone)]
+pub struct SharedSecret<const N: usize>([u8; N]);
+
+impl<const N: usize> SharedSecret<N> {
+    /// Returns a reference to the bytes of the shared secret
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for SharedSecret<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> ConstantTimeEq for SharedSecret<N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<const N: usize> PartialEq for SharedSecret<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<const N: usize> Eq for SharedSecret<N> {}
+
+impl<const N: usize> Debug for SharedSecret<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SharedSecret").finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize> Drop for SharedSecret<N> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<const N: usize> ZeroizeOnDrop for SharedSecret<N> {}
+
+fn to_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], Error> {
+    bytes.try_into().map_err(|_| Error::InvalidLength {
+        expected: N,
+        actual: bytes.len(),
+    })
+}
+
+fn random_scalar<G, const N: usize, R: CryptoRng + RngCore + ?Sized>(
+    rng: &mut R,
+) -> Result<PrivateKey<G, N>, Error> {
+    let mut sk = PrivateKey::new([0u8; N]);
+    rng.try_fill_bytes(&mut sk.scalar)?;
+
+    Ok(sk)
+}
+
+/// X25519 from [RFC 7748](https://www.rfc-editor.org/rfc/rfc7748), with 32 byte keys,
+/// ciphertexts, and shared secrets.
+///
+/// An all zero shared secret, from a low order public key or ciphertext, is rejected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct X25519;
+
+impl X25519 {
+    fn diffie_hellman(sk: &PrivateKey<Self, 32>, pk: &[u8; 32]) -> Result<SharedSecret<32>, Error> {
+        let ss = SharedSecret(x25519_dalek::x25519(sk.scalar, *pk));
+        if ss.0.ct_eq(&[0u8; 32]).into() {
+            return Err(Error::InvalidEncoding);
+        }
+
+        Ok(ss)
+    }
+}
+
+impl ClassicalKem for X25519 {
+    const PUBLIC_KEY_BYTES: usize = 32;
+    const PRIVATE_KEY_BYTES: usize = 32;
+    const CIPHERTEXT_BYTES: usize = 32;
+
+    type PublicKey = [u8; 32];
+    type PrivateKey = PrivateKey<Self, 32>;
+    type Ciphertext = [u8; 32];
+    type SharedSecret = SharedSecret<32>;
+
+    fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::PrivateKey), Error> {
+        let sk = random_scalar(rng)?;
+
+        Ok((Self::public_key(&sk), sk))
+    }
+
+    fn public_key(sk: &Self::PrivateKey) -> Self::PublicKey {
+        x25519_dalek::x25519(sk.scalar, x25519_dalek::X25519_BASEPOINT_BYTES)
+    }
+
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, Error> {
+        to_array(bytes)
+    }
+
+    fn private_key_from_bytes(bytes: &[u8]) -> Result<Self::PrivateKey, Error> {
+        to_array(bytes).map(PrivateKey::new)
+    }
+
+    fn ciphertext_from_bytes(bytes: &[u8]) -> Result<Self::Ciphertext, Error> {
+        to_array(bytes)
+    }
+
+    fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        pk: &Self::PublicKey,
+        rng: &mut R,
+    ) -> Result<(Self::Ciphertext, Self::SharedSecret), Error> {
+        let (ciphertext, esk) = Self::generate_keypair(rng)?;
+
+        Ok((ciphertext, Self::diffie_hellman(&esk, pk)?))
+    }
+
+    fn decapsulate(
+        sk: &Self::PrivateKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::SharedSecret, Error> {
+        Self::diffie_hellman(sk, ciphertext)
+    }
+}
+
+/// X448 from [RFC 7748](https://www.rfc-editor.org/rfc/rfc7748), with 56 byte keys,
+/// ciphertexts, and shared secrets.
+///
+/// Low order public keys and ciphertexts are rejected.
+#[cfg(feature = "x448")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct X448;
+
+#[cfg(feature = "x448")]
+impl X448 {
+    // u = 5
+    const BASEPOINT: [u8; 56] = {
+        let mut basepoint = [0u8; 56];
+        basepoint[0] = 5;
+        basepoint
+    };
+
+    fn diffie_hellman(sk: &PrivateKey<Self, 56>, pk: &[u8; 56]) -> Result<SharedSecret<56>, Error> {
+        x448::x448(sk.scalar, *pk)
+            .map(SharedSecret)
+            .ok_or(Error::InvalidEncoding)
+    }
+}
+
+#[cfg(feature = "x448")]
+impl ClassicalKem for X448 {
+    const PUBLIC_KEY_BYTES: usize = 56;
+    const PRIVATE_KEY_BYTES: usize = 56;
+    const CIPHERTEXT_BYTES: usize = 56;
+
+    type PublicKey = [u8; 56];
+    type PrivateKey = PrivateKey<Self, 56>;
+    type Ciphertext = [u8; 56];
+    type SharedSecret = SharedSecret<56>;
+
+    fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::PrivateKey), Error> {
+        let sk = random_scalar(rng)?;
+
+        Ok((Self::public_key(&sk), sk))
+    }
+
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    fn public_key(sk: &Self::PrivateKey) -> Self::PublicKey {
+        // cannot fail, as the basepoint is not of low order
+        x448::x448(sk.scalar, Self::BASEPOINT).unwrap()
+    }
+
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, Error> {
+        let pk = to_array(bytes)?;
+        x448::PublicKey::from_bytes(&pk).ok_or(Error::InvalidEncoding)?;
+
+        Ok(pk)
+    }
+
+    fn private_key_from_bytes(bytes: &[u8]) -> Result<Self::PrivateKey, Error> {
+        to_array(bytes).map(PrivateKey::new)
+    }
+
+    fn ciphertext_from_bytes(bytes: &[u8]) -> Result<Self::Ciphertext, Error> {
+        Self::public_key_from_bytes(bytes)
+    }
+
+    fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        pk: &Self::PublicKey,
+        rng: &mut R,
+    ) -> Result<(Self::Ciphertext, Self::SharedSecret), Error> {
+        let (ciphertext, esk) = Self::generate_keypair(rng)?;
+
+        Ok((ciphertext, Self::diffie_hellman(&esk, pk)?))
+    }
+
+    fn decapsulate(
+        sk: &Self::PrivateKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::SharedSecret, Error> {
+        Self::diffie_hellman(sk, ciphertext)
+    }
+}
+
+// Uncompressed SEC1 point tag
+#[cfg(feature = "nist")]
+const UNCOMPRESSED: u8 = 0x04;
+
+// Rejection samples a scalar in [1, n) for the NIST curve `E`
+#[cfg(feature = "nist")]
+fn ec_random_scalar<E, G, const N: usize, R>(rng: &mut R) -> Result<PrivateKey<G, N>, Error>
+where
+    E: CurveArithmetic,
+    R: CryptoRng + RngCore + ?Sized,
+{
+    loop {
+        let sk = random_scalar(rng)?;
+        if SecretKey::<E>::from_slice(&sk.scalar).is_ok() {
+            return Ok(sk);
+        }
+    }
+}
+
+#[cfg(feature = "nist")]
+fn ec_secret_key<E: CurveArithmetic, G, const N: usize>(
+    sk: &PrivateKey<G, N>,
+) -> Result<SecretKey<E>, Error> {
+    SecretKey::from_slice(&sk.scalar).map_err(|_| Error::InvalidEncoding)
+}
+
+#[cfg(feature = "nist")]
+fn ec_private_key<E: CurveArithmetic, G, const N: usize>(
+    bytes: &[u8],
+) -> Result<PrivateKey<G, N>, Error> {
+    let sk = PrivateKey::new(to_array(bytes)?);
+    ec_secret_key::<E, G, N>(&sk)?;
+
+    Ok(sk)
+}
+
+#[cfg(feature = "nist")]
+#[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+fn ec_public_key<E, const M: usize>(sk: &SecretKey<E>) -> [u8; M]
+where
+    E: CurveArithmetic,
+    AffinePoint<E>: FromEncodedPoint<E> + ToEncodedPoint<E>,
+    FieldBytesSize<E>: ModulusSize,
+{
+    // cannot fail, as M is the uncompressed point length of E
+    sk.public_key()
+        .to_encoded_point(false)
+        .as_bytes()
+        .try_into()
+        .unwrap()
+}
+
+// Only uncompressed points on the curve are accepted
+#[cfg(feature = "nist")]
+fn ec_point<E, const M: usize>(bytes: &[u8]) -> Result<([u8; M], EcPublicKey<E>), Error>
+where
+    E: CurveArithmetic,
+    AffinePoint<E>: FromEncodedPoint<E> + ToEncodedPoint<E>,
+    FieldBytesSize<E>: ModulusSize,
+{
+    let encoded: [u8; M] = to_array(bytes)?;
+    if encoded[0] != UNCOMPRESSED {
+        return Err(Error::InvalidEncoding);
+    }
+    let point = EcPublicKey::from_sec1_bytes(&encoded).map_err(|_| Error::InvalidEncoding)?;
+
+    Ok((encoded, point))
+}
+
+// The shared secret is the x-coordinate of the shared point, as in SP 800-56A
+#[cfg(feature = "nist")]
+fn ec_diffie_hellman<E, const N: usize>(
+    sk: &SecretKey<E>,
+    pk: &EcPublicKey<E>,
+) -> Result<SharedSecret<N>, Error>
+where
+    E: CurveArithmetic,
+{
+    let shared_point = ecdh::diffie_hellman(sk.to_nonzero_scalar(), pk.as_affine());
+
+    Ok(SharedSecret(
+        shared_point.raw_secret_bytes().as_slice().try_into()?,
+    ))
+}
+
+/// ECDH over NIST P-256, with 32 byte private keys and shared secrets, and 65 byte uncompressed
+/// points as public keys and ciphertexts.
+///
+/// Compressed points, and points not on the curve, are rejected.
+#[cfg(feature = "nist")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct P256;
+
+#[cfg(feature = "nist")]
+impl ClassicalKem for P256 {
+    const PUBLIC_KEY_BYTES: usize = 65;
+    const PRIVATE_KEY_BYTES: usize = 32;
+    const CIPHERTEXT_BYTES: usize = 65;
+
+    type PublicKey = [u8; 65];
+    type PrivateKey = PrivateKey<Self, 32>;
+    type Ciphertext = [u8; 65];
+    type SharedSecret = SharedSecret<32>;
+
+    fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::PrivateKey), Error> {
+        let sk = ec_random_scalar::<NistP256, _, 32, _>(rng)?;
+
+        Ok((Self::public_key(&sk), sk))
+    }
+
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    fn public_key(sk: &Self::PrivateKey) -> Self::PublicKey {
+        // cannot fail, as the scalar is checked when the private key is created
+        ec_public_key(&ec_secret_key::<NistP256, _, 32>(sk).unwrap())
+    }
+
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, Error> {
+        ec_point::<NistP256, 65>(bytes).map(|(encoded, _)| encoded)
+    }
+
+    fn private_key_from_bytes(bytes: &[u8]) -> Result<Self::PrivateKey, Error> {
+        ec_private_key::<NistP256, _, 32>(bytes)
+    }
+
+    fn ciphertext_from_bytes(bytes: &[u8]) -> Result<Self::Ciphertext, Error> {
+        Self::public_key_from_bytes(bytes)
+    }
+
+    fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        pk: &Self::PublicKey,
+        rng: &mut R,
+    ) -> Result<(Self::Ciphertext, Self::SharedSecret), Error> {
+        let (_, pk) = ec_point::<NistP256, 65>(pk)?;
+        let (ciphertext, esk) = Self::generate_keypair(rng)?;
+
+        Ok((
+            ciphertext,
+            ec_diffie_hellman(&ec_secret_key::<NistP256, _, 32>(&esk)?, &pk)?,
+        ))
+    }
+
+    fn decapsulate(
+        sk: &Self::PrivateKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::SharedSecret, Error> {
+        let (_, ct) = ec_point::<NistP256, 65>(ciphertext)?;
+
+        ec_diffie_hellman(&ec_secret_key::<NistP256, _, 32>(sk)?, &ct)
+    }
+}
+
+/// ECDH over NIST P-384, with 48 byte private keys and shared secrets, and 97 byte uncompressed
+/// points as public keys and ciphertexts.
+///
+/// Compressed points, and points not on the curve, are rejected.
+#[cfg(feature = "nist")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct P384;
+
+#[cfg(feature = "nist")]
+impl ClassicalKem for P384 {
+    const PUBLIC_KEY_BYTES: usize = 97;
+    const PRIVATE_KEY_BYTES: usize = 48;
+    const CIPHERTEXT_BYTES: usize = 97;
+
+    type PublicKey = [u8; 97];
+    type PrivateKey = PrivateKey<Self, 48>;
+    type Ciphertext = [u8; 97];
+    type SharedSecret = SharedSecret<48>;
+
+    fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::PrivateKey), Error> {
+        let sk = ec_random_scalar::<NistP384, _, 48, _>(rng)?;
+
+        Ok((Self::public_key(&sk), sk))
+    }
+
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    fn public_key(sk: &Self::PrivateKey) -> Self::PublicKey {
+        // cannot fail, as the scalar is checked when the private key is created
+        ec_public_key(&ec_secret_key::<NistP384, _, 48>(sk).unwrap())
+    }
+
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey, Error> {
+        ec_point::<NistP384, 97>(bytes).map(|(encoded, _)| encoded)
+    }
+
+    fn private_key_from_bytes(bytes: &[u8]) -> Result<Self::PrivateKey, Error> {
+        ec_private_key::<NistP384, _, 48>(bytes)
+    }
+
+    fn ciphertext_from_bytes(bytes: &[u8]) -> Result<Self::Ciphertext, Error> {
+        Self::public_key_from_bytes(bytes)
+    }
+
+    fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        pk: &Self::PublicKey,
+        rng: &mut R,
+    ) -> Result<(Self::Ciphertext, Self::SharedSecret), Error> {
+        let (_, pk) = ec_point::<NistP384, 97>(pk)?;
+        let (ciphertext, esk) = Self::generate_keypair(rng)?;
+
+        Ok((
+            ciphertext,
+            ec_diffie_hellman(&ec_secret_key::<NistP384, _, 48>(&esk)?, &pk)?,
+        ))
+    }
+
+    fn decapsulate(
+        sk: &Self::PrivateKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::SharedSecret, Error> {
+        let (_, ct) = ec_point::<NistP384, 97>(ciphertext)?;
+
+        ec_diffie_hellman(&ec_secret_key::<NistP384, _, 48>(sk)?, &ct)
+    }
+}
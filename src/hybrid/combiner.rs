@@ -0,0 +1,575 @@
+//! Hybrid KEMs combining an ML-KEM parameter set with a [`ClassicalKem`], such as [`X25519`].
+//!
+//! The shared secret is derived from both component shared secrets, ciphertexts, and public keys,
+//! under a label chosen by the caller to identify the protocol, as in the universal combiner of
+//! the IETF hybrid KEM design drafts:
+//!
+//! ```text
+//! ss = SHA3-256(ss_M || ss_C || ct_M || ct_C || pk_M || pk_C || label)
+//! ```
+//!
+//! where `M` is the ML-KEM component and `C` the classical component. Keys and ciphertexts are
+//! encoded as the ML-KEM encoding followed by the classical encoding.
+//!
+//! # Example
+//! ```
+//! use enc_rust::hybrid::combiner::*;
+//!
+//! const LABEL: &[u8] = b"my-protocol v1";
+//!
+//! let (pk, sk) = MlKem768X25519::generate_keypair_with_default_rng()?;
+//!
+//! let (ciphertext, alice_secret) = pk.encapsulate_with_default_rng(LABEL)?;
+//! let bob_secret = sk.decapsulate(LABEL, &ciphertext)?;
+//!
+//! assert_eq!(alice_secret, bob_secret);
+//!
+//! # Ok::<(), enc_rust::errors::Error>(())
+//! ```
+
+#[cfg(feature = "x448")]
+pub use super::classical::X448;
+pub use super::classical::{ClassicalKem, X25519};
+pub use crate::kem::typed::{MlKem1024, MlKem512, MlKem768, ParameterSet};
+
+use super::{split_encoding, split_encoding_mut};
+use crate::{
+    errors::Error,
+    kem::{typed, PrivateKey as DynPrivateKey, PublicKey as DynPublicKey, SharedSecret},
+    params::SHAREDSECRETBYTES,
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::{
+    borrow::Borrow,
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+    mem::size_of,
+};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use sha3::{Digest, Sha3_256};
+use subtle::ConstantTimeEq;
+#[cfg(feature = "alloc")]
+use zeroize::Zeroizing;
+
+/// A hybrid of the ML-KEM parameter set `P` and the classical KEM `C`, used to generate keypairs.
+///
+/// # Example
+/// ```
+/// # use enc_rust::hybrid::combiner::*;
+/// let (pk, sk) = HybridKem::<MlKem1024, X25519>::generate_keypair_with_default_rng()?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HybridKem<P: ParameterSet, C: ClassicalKem> {
+    components: PhantomData<(P, C)>,
+}
+
+/// ML-KEM-512 with X25519
+pub type MlKem512X25519 = HybridKem<MlKem512, X25519>;
+/// ML-KEM-768 with X25519
+pub type MlKem768X25519 = HybridKem<MlKem768, X25519>;
+/// ML-KEM-1024 with X25519
+pub type MlKem1024X25519 = HybridKem<MlKem1024, X25519>;
+/// ML-KEM-512 with X448
+#[cfg(feature = "x448")]
+pub type MlKem512X448 = HybridKem<MlKem512, X448>;
+/// ML-KEM-768 with X448
+#[cfg(feature = "x448")]
+pub type MlKem768X448 = HybridKem<MlKem768, X448>;
+/// ML-KEM-1024 with X448
+#[cfg(feature = "x448")]
+pub type MlKem1024X448 = HybridKem<MlKem1024, X448>;
+
+/// Hybrid public key, the ML-KEM public key and the classical public key.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PublicKey<P: ParameterSet, C: ClassicalKem> {
+    pk_m: typed::PublicKey<P>,
+    pk_c: C::PublicKey,
+}
+
+/// Hybrid private key, the ML-KEM private key and the classical private key.
+///
+/// Both components are zeroised when dropped, and are redacted from the [`Debug`] output. The
+/// encoded component public keys are kept alongside them for the combiner, so decapsulation does
+/// not repack the ML-KEM public key.
+pub struct PrivateKey<P: ParameterSet, C: ClassicalKem> {
+    sk_m: typed::PrivateKey<P>,
+    sk_c: C::PrivateKey,
+    pk_m: P::PublicKeyBytes,
+    pk_c: C::PublicKey,
+}
+
+/// Hybrid ciphertext, the ML-KEM ciphertext and the classical ciphertext.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext<P: ParameterSet, C: ClassicalKem> {
+    ct_m: typed::Ciphertext<P>,
+    ct_c: C::Ciphertext,
+}
+
+type Keypair<P, C> = (PublicKey<P, C>, PrivateKey<P, C>);
+
+impl<P: ParameterSet, C: ClassicalKem> HybridKem<P, C> {
+    /// Generates a new hybrid keypair.
+    ///
+    /// # Inputs
+    /// - `rng`: RNG to be used when generating both component keypairs. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (pk, sk) = MlKem768X25519::generate_keypair(&mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<Keypair<P, C>, Error> {
+        let (pk_m, sk_m) = P::generate_keypair(rng)?;
+        let (pk_c, sk_c) = C::generate_keypair(rng)?;
+
+        let sk = PrivateKey {
+            sk_m,
+            sk_c,
+            pk_m: pk_m.to_bytes(),
+            pk_c,
+        };
+
+        Ok((PublicKey { pk_m, pk_c }, sk))
+    }
+
+    /// Generates a new hybrid keypair, using
+    /// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// let (pk, sk) = MlKem768X25519::generate_keypair_with_default_rng()?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn generate_keypair_with_default_rng() -> Result<Keypair<P, C>, Error> {
+        Self::generate_keypair(&mut ChaCha20Rng::from_entropy())
+    }
+}
+
+impl<P: ParameterSet, C: ClassicalKem> PublicKey<P, C> {
+    /// Length of the public key encoding in bytes
+    pub const BYTES: usize = size_of::<P::PublicKeyBytes>() + C::PUBLIC_KEY_BYTES;
+
+    /// Packs the public key into a given buffer, as the ML-KEM public key followed by the
+    /// classical public key
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the buffer is not of length [`BYTES`](Self::BYTES)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// let mut pk_bytes = [0u8; 1184 + 32];
+    /// pk.pack(&mut pk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), Error> {
+        let (m, c) = split_encoding_mut(bytes, Self::BYTES, size_of::<P::PublicKeyBytes>())?;
+        m.copy_from_slice(self.pk_m.to_bytes().as_ref());
+        c.copy_from_slice(self.pk_c.as_ref());
+
+        Ok(())
+    }
+
+    /// Packs the public key into an owned buffer
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// let pk_bytes = pk.to_vec();
+    /// assert_eq!(pk_bytes.len(), 1216);
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; Self::BYTES];
+        // cannot fail, as the buffer is always the correct length
+        self.pack(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    /// Unpacks a public key encoding
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The buffer is not of length [`BYTES`](Self::BYTES)
+    /// - Either component public key is not valid
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (new_pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// # let mut pk_bytes = [0u8; 1216];
+    /// # new_pk.pack(&mut pk_bytes)?;
+    /// let pk = PublicKey::<MlKem768, X25519>::unpack(&pk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn unpack(bytes: &[u8]) -> Result<Self, Error> {
+        let (m, c) = split_encoding(bytes, Self::BYTES, size_of::<P::PublicKeyBytes>())?;
+
+        Ok(Self {
+            pk_m: DynPublicKey::unpack(m)?.try_into()?,
+            pk_c: C::public_key_from_bytes(c)?,
+        })
+    }
+
+    /// Encapsulates a new shared secret to the public key, under the given label
+    ///
+    /// # Inputs
+    /// - `label`: Label identifying the protocol, which must match the label used to
+    ///   [`decapsulate`](PrivateKey::decapsulate)
+    /// - `rng`: RNG to be used during encapsulation. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Outputs
+    /// - [`Ciphertext`]
+    /// - [`SharedSecret`]
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - RNG fails
+    /// - The classical KEM rejects the public key, such as a low order point
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// # let (pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (ciphertext, shared_secret) = pk.encapsulate(b"my-protocol v1", &mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        &self,
+        label: &[u8],
+        rng: &mut R,
+    ) -> Result<(Ciphertext<P, C>, SharedSecret), Error> {
+        let (ct_m, ss_m) = self.pk_m.encapsulate(rng)?;
+        let (ct_c, ss_c) = C::encapsulate(&self.pk_c, rng)?;
+        let ciphertext = Ciphertext { ct_m, ct_c };
+
+        let shared_secret = combiner(
+            &ss_m,
+            &ss_c,
+            &ciphertext,
+            self.pk_m.to_bytes().as_ref(),
+            &self.pk_c,
+            label,
+        );
+
+        Ok((ciphertext, shared_secret))
+    }
+
+    /// Encapsulates a new shared secret to the public key, under the given label, using
+    /// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - RNG fails
+    /// - The classical KEM rejects the public key, such as a low order point
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng(b"my-protocol v1")?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate_with_default_rng(
+        &self,
+        label: &[u8],
+    ) -> Result<(Ciphertext<P, C>, SharedSecret), Error> {
+        self.encapsulate(label, &mut ChaCha20Rng::from_entropy())
+    }
+}
+
+impl<P: ParameterSet, C: ClassicalKem> PrivateKey<P, C> {
+    /// Length of the private key encoding in bytes
+    pub const BYTES: usize = size_of::<P::PrivateKeyBytes>() + C::PRIVATE_KEY_BYTES;
+
+    /// Returns the public key corresponding to the private key
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (_, sk) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// let pk = sk.get_public_key();
+    /// ```
+    #[must_use]
+    pub fn get_public_key(&self) -> PublicKey<P, C> {
+        PublicKey {
+            pk_m: self.sk_m.get_public_key(),
+            pk_c: self.pk_c,
+        }
+    }
+
+    /// Packs the private key into a given buffer, as the expanded ML-KEM private key followed by
+    /// the classical private key
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the buffer is not of length [`BYTES`](Self::BYTES)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (_, sk) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// let mut sk_bytes = [0u8; 2400 + 32];
+    /// sk.pack(&mut sk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), Error> {
+        let (m, c) = split_encoding_mut(bytes, Self::BYTES, size_of::<P::PrivateKeyBytes>())?;
+        Borrow::<DynPrivateKey>::borrow(&self.sk_m).to_expanded_bytes(m)?;
+        c.copy_from_slice(self.sk_c.as_ref());
+
+        Ok(())
+    }
+
+    /// Packs the private key into an owned buffer, which is zeroised when dropped
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (_, sk) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// let sk_bytes = sk.to_vec();
+    /// assert_eq!(sk_bytes.len(), 2432);
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(vec![0u8; Self::BYTES]);
+        // cannot fail, as the buffer is always the correct length
+        self.pack(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    /// Unpacks a private key encoding
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The buffer is not of length [`BYTES`](Self::BYTES)
+    /// - The ML-KEM private key fails the FIPS 203 decapsulation key checks, see
+    ///   [`kem::PrivateKey::from_expanded_bytes`](crate::kem::PrivateKey::from_expanded_bytes)
+    /// - The classical private key is not valid
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (_, new_sk) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// # let mut sk_bytes = [0u8; 2432];
+    /// # new_sk.pack(&mut sk_bytes)?;
+    /// let sk = PrivateKey::<MlKem768, X25519>::unpack(&sk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn unpack(bytes: &[u8]) -> Result<Self, Error> {
+        let (m, c) = split_encoding(bytes, Self::BYTES, size_of::<P::PrivateKeyBytes>())?;
+        let sk_m: typed::PrivateKey<P> = DynPrivateKey::from_expanded_bytes(m)?.try_into()?;
+        let sk_c = C::private_key_from_bytes(c)?;
+
+        Ok(Self {
+            pk_m: sk_m.get_public_key().to_bytes(),
+            pk_c: C::public_key(&sk_c),
+            sk_m,
+            sk_c,
+        })
+    }
+
+    /// Decapsulates a ciphertext into the shared secret, under the given label
+    ///
+    /// # Inputs
+    /// - `label`: Label identifying the protocol, which must match the label used to
+    ///   [`encapsulate`](PublicKey::encapsulate)
+    /// - `ciphertext`: The hybrid ciphertext
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the classical KEM rejects the ciphertext, such as a low order
+    /// point
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (pk, sk) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// # let (ciphertext, _) = pk.encapsulate_with_default_rng(b"my-protocol v1").unwrap();
+    /// let shared_secret = sk.decapsulate(b"my-protocol v1", &ciphertext)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn decapsulate(
+        &self,
+        label: &[u8],
+        ciphertext: &Ciphertext<P, C>,
+    ) -> Result<SharedSecret, Error> {
+        let ss_m = self.sk_m.decapsulate(&ciphertext.ct_m)?;
+        let ss_c = C::decapsulate(&self.sk_c, &ciphertext.ct_c)?;
+
+        Ok(combiner(
+            &ss_m,
+            &ss_c,
+            ciphertext,
+            self.pk_m.as_ref(),
+            &self.pk_c,
+            label,
+        ))
+    }
+}
+
+impl<P: ParameterSet, C: ClassicalKem> PartialEq for PrivateKey<P, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.sk_m == other.sk_m && bool::from(self.sk_c.as_ref().ct_eq(other.sk_c.as_ref()))
+    }
+}
+
+impl<P: ParameterSet, C: ClassicalKem> Eq for PrivateKey<P, C> {}
+
+impl<P: ParameterSet, C: ClassicalKem> Debug for PrivateKey<P, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrivateKey").finish_non_exhaustive()
+    }
+}
+
+impl<P: ParameterSet, C: ClassicalKem> Ciphertext<P, C> {
+    /// Length of the ciphertext encoding in bytes
+    pub const BYTES: usize = size_of::<P::CiphertextBytes>() + C::CIPHERTEXT_BYTES;
+
+    /// Packs the ciphertext into a given buffer, as the ML-KEM ciphertext followed by the
+    /// classical ciphertext
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the buffer is not of length [`BYTES`](Self::BYTES)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// # let (ciphertext, _) = pk.encapsulate_with_default_rng(b"my-protocol v1").unwrap();
+    /// let mut ct_bytes = [0u8; 1088 + 32];
+    /// ciphertext.pack(&mut ct_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), Error> {
+        let (m, c) = split_encoding_mut(bytes, Self::BYTES, size_of::<P::CiphertextBytes>())?;
+        m.copy_from_slice(self.ct_m.as_ref());
+        c.copy_from_slice(self.ct_c.as_ref());
+
+        Ok(())
+    }
+
+    /// Packs the ciphertext into an owned buffer
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// let (ciphertext, _) = pk.encapsulate_with_default_rng(b"my-protocol v1")?;
+    /// assert_eq!(ciphertext.to_vec().len(), 1120);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; Self::BYTES];
+        // cannot fail, as the buffer is always the correct length
+        self.pack(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    /// Parses a received ciphertext
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The ciphertext is not of length [`BYTES`](Self::BYTES)
+    /// - The classical ciphertext is not valid
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::combiner::*;
+    /// # let (pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+    /// # let (new_ciphertext, _) = pk.encapsulate_with_default_rng(b"my-protocol v1").unwrap();
+    /// # let mut bytes = [0u8; 1120];
+    /// # new_ciphertext.pack(&mut bytes)?;
+    /// let ciphertext = Ciphertext::<MlKem768, X25519>::from_bytes(&bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (m, c) = split_encoding(bytes, Self::BYTES, size_of::<P::CiphertextBytes>())?;
+
+        Ok(Self {
+            ct_m: m.try_into()?,
+            ct_c: C::ciphertext_from_bytes(c)?,
+        })
+    }
+}
+
+impl<P: ParameterSet, C: ClassicalKem> TryFrom<&[u8]> for Ciphertext<P, C> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
+// ss = SHA3-256(ss_M || ss_C || ct_M || ct_C || pk_M || pk_C || label)
+fn combiner<P: ParameterSet, C: ClassicalKem>(
+    ss_m: &SharedSecret,
+    ss_c: &C::SharedSecret,
+    ciphertext: &Ciphertext<P, C>,
+    pk_m: &[u8],
+    pk_c: &C::PublicKey,
+    label: &[u8],
+) -> SharedSecret {
+    let mut shared_secret = [0u8; SHAREDSECRETBYTES];
+    shared_secret.copy_from_slice(
+        &Sha3_256::new()
+            .chain_update(ss_m.as_bytes())
+            .chain_update(ss_c)
+            .chain_update(&ciphertext.ct_m)
+            .chain_update(ciphertext.ct_c)
+            .chain_update(pk_m)
+            .chain_update(pk_c)
+            .chain_update(label)
+            .finalize(),
+    );
+
+    SharedSecret(shared_secret)
+}
@@ -0,0 +1,506 @@
+//! Hybrids of ML-KEM with ECDH over the NIST curves, as in the `SecP256r1MLKEM768` and
+//! `SecP384r1MLKEM1024` TLS 1.3 groups of
+//! [draft-ietf-tls-ecdhe-mlkem](https://datatracker.ietf.org/doc/draft-ietf-tls-ecdhe-mlkem/).
+//!
+//! Keys and ciphertexts are encoded as the uncompressed ECDH point followed by the ML-KEM
+//! encoding, and the shared secret is the ECDH shared secret followed by the ML-KEM shared
+//! secret, without any further key derivation:
+//!
+//! | Hybrid                 | Public key     | Ciphertext     | Shared secret |
+//! |------------------------|----------------|----------------|---------------|
+//! | [`SecP256r1MlKem768`]  | 65 + 1184      | 65 + 1088      | 32 + 32       |
+//! | [`SecP384r1MlKem1024`] | 97 + 1568      | 97 + 1568      | 48 + 32       |
+//!
+//! # Example
+//! ```
+//! use enc_rust::hybrid::nist::*;
+//!
+//! let (pk, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng()?;
+//!
+//! let (ciphertext, alice_secret) = pk.encapsulate_with_default_rng()?;
+//! let bob_secret = sk.decapsulate(&ciphertext)?;
+//!
+//! assert_eq!(alice_secret, bob_secret);
+//! assert_eq!(alice_secret.as_bytes().len(), 64);
+//!
+//! # Ok::<(), enc_rust::errors::Error>(())
+//! ```
+
+pub use super::classical::{P256, P384};
+/// Hybrid shared secret, the ECDH shared secret followed by the ML-KEM shared secret: 64 bytes for
+/// [`SecP256r1MlKem768`] and 80 bytes for [`SecP384r1MlKem1024`]
+pub use super::SharedSecret;
+pub use crate::kem::typed::{MlKem1024, MlKem768, ParameterSet};
+
+use super::{classical::ClassicalKem, split_encoding, split_encoding_mut};
+use crate::{
+    errors::Error,
+    kem::{typed, PrivateKey as DynPrivateKey, PublicKey as DynPublicKey},
+};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::{
+    borrow::Borrow,
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+    mem::size_of,
+};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use subtle::ConstantTimeEq;
+#[cfg(feature = "alloc")]
+use zeroize::Zeroizing;
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::P256 {}
+    impl Sealed for super::P384 {}
+}
+
+/// A NIST curve, one of [`P256`] or [`P384`]
+pub trait Curve: private::Sealed + ClassicalKem {}
+
+impl Curve for P256 {}
+impl Curve for P384 {}
+
+/// A hybrid of ECDH over the NIST curve `C` and the ML-KEM parameter set `P`, used to generate
+/// keypairs.
+///
+/// # Example
+/// ```
+/// # use enc_rust::hybrid::nist::*;
+/// let (pk, sk) = SecP384r1MlKem1024::generate_keypair_with_default_rng()?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NistHybrid<C: Curve, P: ParameterSet> {
+    components: PhantomData<(C, P)>,
+}
+
+/// P-256 with ML-KEM-768, the `SecP256r1MLKEM768` TLS group
+pub type SecP256r1MlKem768 = NistHybrid<P256, MlKem768>;
+/// P-384 with ML-KEM-1024, the `SecP384r1MLKEM1024` TLS group
+pub type SecP384r1MlKem1024 = NistHybrid<P384, MlKem1024>;
+
+/// Hybrid public key, the uncompressed ECDH point and the ML-KEM public key.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PublicKey<C: Curve, P: ParameterSet> {
+    pk_c: C::PublicKey,
+    pk_m: typed::PublicKey<P>,
+}
+
+/// Hybrid private key, the ECDH private key and the ML-KEM private key.
+///
+/// Both components are zeroised when dropped, and are redacted from the [`Debug`] output.
+pub struct PrivateKey<C: Curve, P: ParameterSet> {
+    sk_c: C::PrivateKey,
+    pk_c: C::PublicKey,
+    sk_m: typed::PrivateKey<P>,
+}
+
+/// Hybrid ciphertext, the uncompressed ephemeral ECDH point and the ML-KEM ciphertext.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext<C: Curve, P: ParameterSet> {
+    ct_c: C::Ciphertext,
+    ct_m: typed::Ciphertext<P>,
+}
+
+type Keypair<C, P> = (PublicKey<C, P>, PrivateKey<C, P>);
+
+impl<C: Curve, P: ParameterSet> NistHybrid<C, P> {
+    /// Generates a new hybrid keypair.
+    ///
+    /// # Inputs
+    /// - `rng`: RNG to be used when generating both component keypairs. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (pk, sk) = SecP256r1MlKem768::generate_keypair(&mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<Keypair<C, P>, Error> {
+        let (pk_c, sk_c) = C::generate_keypair(rng)?;
+        let (pk_m, sk_m) = P::generate_keypair(rng)?;
+
+        Ok((PublicKey { pk_c, pk_m }, PrivateKey { sk_c, pk_c, sk_m }))
+    }
+
+    /// Generates a new hybrid keypair, using
+    /// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// let (pk, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng()?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn generate_keypair_with_default_rng() -> Result<Keypair<C, P>, Error> {
+        Self::generate_keypair(&mut ChaCha20Rng::from_entropy())
+    }
+}
+
+impl<C: Curve, P: ParameterSet> PublicKey<C, P> {
+    /// Length of the public key encoding in bytes
+    pub const BYTES: usize = C::PUBLIC_KEY_BYTES + size_of::<P::PublicKeyBytes>();
+
+    /// Packs the public key into a given buffer, as the uncompressed ECDH point followed by the
+    /// ML-KEM public key
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the buffer is not of length [`BYTES`](Self::BYTES)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let mut pk_bytes = [0u8; 65 + 1184];
+    /// pk.pack(&mut pk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), Error> {
+        let (c, m) = split_encoding_mut(bytes, Self::BYTES, C::PUBLIC_KEY_BYTES)?;
+        c.copy_from_slice(self.pk_c.as_ref());
+        m.copy_from_slice(self.pk_m.to_bytes().as_ref());
+
+        Ok(())
+    }
+
+    /// Packs the public key into an owned buffer
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let pk_bytes = pk.to_vec();
+    /// assert_eq!(pk_bytes.len(), 1249);
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; Self::BYTES];
+        // cannot fail, as the buffer is always the correct length
+        self.pack(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    /// Unpacks a public key encoding
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The buffer is not of length [`BYTES`](Self::BYTES)
+    /// - The ECDH point is compressed, or is not on the curve
+    /// - The ML-KEM public key fails the FIPS 203 modulus check
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (new_pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let mut pk_bytes = [0u8; 1249];
+    /// # new_pk.pack(&mut pk_bytes)?;
+    /// let pk = PublicKey::<P256, MlKem768>::unpack(&pk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn unpack(bytes: &[u8]) -> Result<Self, Error> {
+        let (c, m) = split_encoding(bytes, Self::BYTES, C::PUBLIC_KEY_BYTES)?;
+
+        Ok(Self {
+            pk_c: C::public_key_from_bytes(c)?,
+            pk_m: DynPublicKey::unpack(m)?.try_into()?,
+        })
+    }
+
+    /// Encapsulates a new shared secret to the public key
+    ///
+    /// # Inputs
+    /// - `rng`: RNG to be used during encapsulation. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Outputs
+    /// - [`Ciphertext`]
+    /// - [`SharedSecret`]
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// # let (pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (ciphertext, shared_secret) = pk.encapsulate(&mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Ciphertext<C, P>, SharedSecret), Error> {
+        let (ct_c, ss_c) = C::encapsulate(&self.pk_c, rng)?;
+        let (ct_m, ss_m) = self.pk_m.encapsulate(rng)?;
+
+        Ok((
+            Ciphertext { ct_c, ct_m },
+            SharedSecret::concat(ss_c.as_ref(), ss_m.as_bytes()),
+        ))
+    }
+
+    /// Encapsulates a new shared secret to the public key, using
+    /// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng()?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate_with_default_rng(&self) -> Result<(Ciphertext<C, P>, SharedSecret), Error> {
+        self.encapsulate(&mut ChaCha20Rng::from_entropy())
+    }
+}
+
+impl<C: Curve, P: ParameterSet> PrivateKey<C, P> {
+    /// Length of the private key encoding in bytes
+    pub const BYTES: usize = C::PRIVATE_KEY_BYTES + size_of::<P::PrivateKeyBytes>();
+
+    /// Returns the public key corresponding to the private key
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (_, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let pk = sk.get_public_key();
+    /// ```
+    #[must_use]
+    pub fn get_public_key(&self) -> PublicKey<C, P> {
+        PublicKey {
+            pk_c: self.pk_c,
+            pk_m: self.sk_m.get_public_key(),
+        }
+    }
+
+    /// Packs the private key into a given buffer, as the ECDH private scalar followed by the
+    /// expanded ML-KEM private key
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the buffer is not of length [`BYTES`](Self::BYTES)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (_, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let mut sk_bytes = [0u8; 32 + 2400];
+    /// sk.pack(&mut sk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), Error> {
+        let (c, m) = split_encoding_mut(bytes, Self::BYTES, C::PRIVATE_KEY_BYTES)?;
+        c.copy_from_slice(self.sk_c.as_ref());
+        Borrow::<DynPrivateKey>::borrow(&self.sk_m).to_expanded_bytes(m)?;
+
+        Ok(())
+    }
+
+    /// Packs the private key into an owned buffer, which is zeroised when dropped
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (_, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let sk_bytes = sk.to_vec();
+    /// assert_eq!(sk_bytes.len(), 2432);
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(vec![0u8; Self::BYTES]);
+        // cannot fail, as the buffer is always the correct length
+        self.pack(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    /// Unpacks a private key encoding
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The buffer is not of length [`BYTES`](Self::BYTES)
+    /// - The ECDH private scalar is zero, or not less than the curve order
+    /// - The ML-KEM private key fails the FIPS 203 decapsulation key checks, see
+    ///   [`kem::PrivateKey::from_expanded_bytes`](crate::kem::PrivateKey::from_expanded_bytes)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (_, new_sk) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let mut sk_bytes = [0u8; 2432];
+    /// # new_sk.pack(&mut sk_bytes)?;
+    /// let sk = PrivateKey::<P256, MlKem768>::unpack(&sk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn unpack(bytes: &[u8]) -> Result<Self, Error> {
+        let (c, m) = split_encoding(bytes, Self::BYTES, C::PRIVATE_KEY_BYTES)?;
+        let sk_c = C::private_key_from_bytes(c)?;
+
+        Ok(Self {
+            pk_c: C::public_key(&sk_c),
+            sk_c,
+            sk_m: DynPrivateKey::from_expanded_bytes(m)?.try_into()?,
+        })
+    }
+
+    /// Decapsulates a ciphertext into the shared secret
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if decapsulation fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (pk, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+    /// let shared_secret = sk.decapsulate(&ciphertext)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn decapsulate(&self, ciphertext: &Ciphertext<C, P>) -> Result<SharedSecret, Error> {
+        let ss_c = C::decapsulate(&self.sk_c, &ciphertext.ct_c)?;
+        let ss_m = self.sk_m.decapsulate(&ciphertext.ct_m)?;
+
+        Ok(SharedSecret::concat(ss_c.as_ref(), ss_m.as_bytes()))
+    }
+}
+
+impl<C: Curve, P: ParameterSet> PartialEq for PrivateKey<C, P> {
+    fn eq(&self, other: &Self) -> bool {
+        bool::from(self.sk_c.as_ref().ct_eq(other.sk_c.as_ref())) && self.sk_m == other.sk_m
+    }
+}
+
+impl<C: Curve, P: ParameterSet> Eq for PrivateKey<C, P> {}
+
+impl<C: Curve, P: ParameterSet> Debug for PrivateKey<C, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrivateKey").finish_non_exhaustive()
+    }
+}
+
+impl<C: Curve, P: ParameterSet> Ciphertext<C, P> {
+    /// Length of the ciphertext encoding in bytes
+    pub const BYTES: usize = C::CIPHERTEXT_BYTES + size_of::<P::CiphertextBytes>();
+
+    /// Packs the ciphertext into a given buffer, as the uncompressed ephemeral ECDH point followed
+    /// by the ML-KEM ciphertext
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the buffer is not of length [`BYTES`](Self::BYTES)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+    /// let mut ct_bytes = [0u8; 65 + 1088];
+    /// ciphertext.pack(&mut ct_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), Error> {
+        let (c, m) = split_encoding_mut(bytes, Self::BYTES, C::CIPHERTEXT_BYTES)?;
+        c.copy_from_slice(self.ct_c.as_ref());
+        m.copy_from_slice(self.ct_m.as_ref());
+
+        Ok(())
+    }
+
+    /// Packs the ciphertext into an owned buffer
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let (ciphertext, _) = pk.encapsulate_with_default_rng()?;
+    /// assert_eq!(ciphertext.to_vec().len(), 1153);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; Self::BYTES];
+        // cannot fail, as the buffer is always the correct length
+        self.pack(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    /// Parses a received ciphertext
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The ciphertext is not of length [`BYTES`](Self::BYTES)
+    /// - The ECDH point is compressed, or is not on the curve
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::nist::*;
+    /// # let (pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let (new_ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+    /// # let mut bytes = [0u8; 1153];
+    /// # new_ciphertext.pack(&mut bytes)?;
+    /// let ciphertext = Ciphertext::<P256, MlKem768>::from_bytes(&bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (c, m) = split_encoding(bytes, Self::BYTES, C::CIPHERTEXT_BYTES)?;
+
+        Ok(Self {
+            ct_c: C::ciphertext_from_bytes(c)?,
+            ct_m: m.try_into()?,
+        })
+    }
+}
+
+impl<C: Curve, P: ParameterSet> TryFrom<&[u8]> for Ciphertext<C, P> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
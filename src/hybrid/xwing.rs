@@ -0,0 +1,492 @@
+//! X-Wing, the hybrid of ML-KEM-768 and X25519 from
+//! [draft-connolly-cfrg-xwing-kem](https://datatracker.ietf.org/doc/draft-connolly-cfrg-xwing-kem/).
+//!
+//! Private keys are a 32 byte seed, which is expanded into an ML-KEM-768 keypair and an X25519
+//! keypair. The shared secret is derived from both component secrets using SHA3-256, bound to the
+//! X25519 ciphertext and public key.
+//!
+//! # Example
+//! ```
+//! use enc_rust::hybrid::xwing::*;
+//!
+//! let (pk, sk) = generate_keypair_with_default_rng()?;
+//!
+//! let (ciphertext, alice_secret) = pk.encapsulate_with_default_rng()?;
+//! let bob_secret = sk.decapsulate(&ciphertext)?;
+//!
+//! assert_eq!(alice_secret, bob_secret);
+//!
+//! # Ok::<(), enc_rust::errors::Error>(())
+//! ```
+
+use crate::{
+    errors::Error,
+    kem::{
+        generate_keypair_from_seed_768, Ciphertext as MlKemCiphertext,
+        PrivateKey as MlKemPrivateKey, PublicKey as MlKemPublicKey, SharedSecret,
+    },
+    params::{SecurityLevel, K, SHAREDSECRETBYTES, SYMBYTES},
+};
+use core::fmt::{Debug, Formatter};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Digest, Sha3_256, Shake256,
+};
+use subtle::{Choice, ConstantTimeEq};
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const ML_KEM_768: SecurityLevel = SecurityLevel::new(K::Three);
+const ML_KEM_PUBLIC_KEY_BYTES: usize = ML_KEM_768.public_key_bytes();
+const ML_KEM_CIPHERTEXT_BYTES: usize = ML_KEM_768.ciphertext_bytes();
+const X25519_BYTES: usize = 32;
+
+/// Size of an X-Wing private key seed in bytes
+pub const PRIVATE_KEY_BYTES: usize = 32;
+/// Size of an X-Wing public key in bytes, the ML-KEM-768 public key followed by the X25519 public key
+pub const PUBLIC_KEY_BYTES: usize = ML_KEM_PUBLIC_KEY_BYTES + X25519_BYTES;
+/// Size of an X-Wing ciphertext in bytes, the ML-KEM-768 ciphertext followed by the X25519 ciphertext
+pub const CIPHERTEXT_BYTES: usize = ML_KEM_CIPHERTEXT_BYTES + X25519_BYTES;
+/// Size of the randomness consumed by [`encapsulate_deterministic`](PublicKey::encapsulate_deterministic)
+pub const ENCAPSULATION_SEED_BYTES: usize = SYMBYTES + X25519_BYTES;
+
+// `\.//^\`
+const XWING_LABEL: &[u8; 6] = b"\\.//^\\";
+
+/// X-Wing private key, held as its 32 byte seed along with the expanded component keys.
+///
+/// The private key is zeroised when dropped, and is redacted from the [`Debug`] output.
+pub struct PrivateKey {
+    seed: [u8; PRIVATE_KEY_BYTES],
+    sk_m: MlKemPrivateKey,
+    sk_x: StaticSecret,
+    pk_x: X25519PublicKey,
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
+// sk_m and sk_x are wiped by their own drops
+impl ZeroizeOnDrop for PrivateKey {}
+
+impl Debug for PrivateKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrivateKey").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.seed.ct_eq(&other.seed).into()
+    }
+}
+
+impl Eq for PrivateKey {}
+
+/// X-Wing public key, the ML-KEM-768 public key and the X25519 public key.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PublicKey {
+    pk_m: MlKemPublicKey,
+    pk_x: X25519PublicKey,
+}
+
+/// X-Wing ciphertext, the ML-KEM-768 ciphertext followed by the X25519 ciphertext.
+///
+/// Equality is checked in constant time.
+#[derive(Clone, Debug)]
+pub struct Ciphertext {
+    bytes: [u8; CIPHERTEXT_BYTES],
+}
+
+/// Generates a new X-Wing keypair.
+///
+/// # Inputs
+/// - `rng`: RNG to be used when generating the seed. Must satisfy the
+///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+///
+/// # Outputs
+/// - [`PublicKey`]
+/// - [`PrivateKey`]
+///
+/// # Errors
+/// Will return an [`Error`] if RNG fails
+///
+/// # Example
+/// ```
+/// # use enc_rust::hybrid::xwing::*;
+/// # use rand_chacha::ChaCha20Rng;
+/// # use rand_core::SeedableRng;
+/// let mut rng = ChaCha20Rng::from_entropy();
+/// let (pk, sk) = generate_keypair(&mut rng)?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+    rng: &mut R,
+) -> Result<(PublicKey, PrivateKey), Error> {
+    let mut seed = [0u8; PRIVATE_KEY_BYTES];
+    rng.try_fill_bytes(&mut seed)?;
+
+    let sk = PrivateKey::from_seed(&seed);
+    seed.zeroize();
+
+    sk.map(|sk| (sk.get_public_key(), sk))
+}
+
+/// Generates a new X-Wing keypair, using the default RNG, `ChaCha20Rng` seeded from system
+/// entropy.
+///
+/// # Outputs
+/// - [`PublicKey`]
+/// - [`PrivateKey`]
+///
+/// # Errors
+/// Will return an [`Error`] if RNG fails
+///
+/// # Example
+/// ```
+/// # use enc_rust::hybrid::xwing::*;
+/// let (pk, sk) = generate_keypair_with_default_rng()?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn generate_keypair_with_default_rng() -> Result<(PublicKey, PrivateKey), Error> {
+    generate_keypair(&mut ChaCha20Rng::from_entropy())
+}
+
+impl PrivateKey {
+    /// Loads a private key from its 32 byte seed, expanding it into the component keys
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if expanding the seed fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (_, new_sk) = generate_keypair_with_default_rng().unwrap();
+    /// # let seed = *new_sk.as_bytes();
+    /// let sk = PrivateKey::from_seed(&seed)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn from_seed(seed: &[u8; PRIVATE_KEY_BYTES]) -> Result<Self, Error> {
+        // (d, z, sk_x) = SHAKE256(seed, 96)
+        let mut expanded = [0u8; 2 * SYMBYTES + X25519_BYTES];
+        let mut hasher = Shake256::default();
+        hasher.update(seed);
+        hasher.finalize_xof().read(&mut expanded);
+
+        let (d, rest) = expanded.split_at(SYMBYTES);
+        let (z, sk_x) = rest.split_at(SYMBYTES);
+        let keypair = generate_keypair_from_seed_768(d.try_into()?, z.try_into()?);
+        let sk_x = StaticSecret::from(<[u8; X25519_BYTES]>::try_from(sk_x)?);
+        expanded.zeroize();
+        let (_, sk_m) = keypair?;
+
+        let pk_x = X25519PublicKey::from(&sk_x);
+
+        Ok(Self {
+            seed: *seed,
+            sk_m,
+            sk_x,
+            pk_x,
+        })
+    }
+
+    /// Returns the 32 byte seed of the private key
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (_, sk) = generate_keypair_with_default_rng().unwrap();
+    /// let seed: &[u8; 32] = sk.as_bytes();
+    /// ```
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8; PRIVATE_KEY_BYTES] {
+        &self.seed
+    }
+
+    /// Returns the public key corresponding to the private key
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (_, sk) = generate_keypair_with_default_rng().unwrap();
+    /// let pk = sk.get_public_key();
+    /// ```
+    #[must_use]
+    pub fn get_public_key(&self) -> PublicKey {
+        PublicKey {
+            pk_m: self.sk_m.get_public_key(),
+            pk_x: self.pk_x,
+        }
+    }
+
+    /// Decapsulates a ciphertext into the shared secret
+    ///
+    /// As in the draft, an X25519 ciphertext giving the all zero Diffie-Hellman output is not
+    /// rejected. The shared secret then still depends on the ML-KEM-768 shared secret, which
+    /// implicitly rejects invalid ciphertexts.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if decapsulation fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (pk, sk) = generate_keypair_with_default_rng().unwrap();
+    /// # let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+    /// let shared_secret = sk.decapsulate(&ciphertext)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn decapsulate(&self, ciphertext: &Ciphertext) -> Result<SharedSecret, Error> {
+        let (ct_m, ct_x) = ciphertext.split()?;
+
+        let ss_m = self.sk_m.decapsulate(&ct_m)?;
+        let ss_x = self.sk_x.diffie_hellman(&ct_x);
+
+        Ok(combiner(&ss_m, ss_x.as_bytes(), &ct_x, &self.pk_x))
+    }
+}
+
+impl PublicKey {
+    /// Returns the public key encoding, the ML-KEM-768 public key followed by the X25519 public
+    /// key
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (pk, _) = generate_keypair_with_default_rng().unwrap();
+    /// let pk_bytes: [u8; 1216] = pk.to_bytes();
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; PUBLIC_KEY_BYTES] {
+        let mut bytes = [0u8; PUBLIC_KEY_BYTES];
+        let (m, x) = bytes.split_at_mut(ML_KEM_PUBLIC_KEY_BYTES);
+        // cannot fail, as the buffer is always the correct length for ML-KEM-768
+        self.pk_m.pack(m).unwrap();
+        x.copy_from_slice(self.pk_x.as_bytes());
+
+        bytes
+    }
+
+    /// Loads a public key from its encoding
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the ML-KEM-768 public key fails the FIPS 203 modulus check
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (new_pk, _) = generate_keypair_with_default_rng().unwrap();
+    /// # let pk_bytes = new_pk.to_bytes();
+    /// let pk = PublicKey::from_bytes(&pk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn from_bytes(bytes: &[u8; PUBLIC_KEY_BYTES]) -> Result<Self, Error> {
+        let (m, x) = bytes.split_at(ML_KEM_PUBLIC_KEY_BYTES);
+        let pk_m = MlKemPublicKey::unpack(m)?;
+        let pk_x = X25519PublicKey::from(<[u8; X25519_BYTES]>::try_from(x)?);
+
+        Ok(Self { pk_m, pk_x })
+    }
+
+    /// Encapsulates a new shared secret to the public key
+    ///
+    /// # Inputs
+    /// - `rng`: RNG to be used when generating the randomness. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Outputs
+    /// - [`Ciphertext`]
+    /// - [`SharedSecret`]
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # use rand_chacha::ChaCha20Rng;
+    /// # use rand_core::SeedableRng;
+    /// # let (pk, _) = generate_keypair_with_default_rng().unwrap();
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (ciphertext, shared_secret) = pk.encapsulate(&mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Ciphertext, SharedSecret), Error> {
+        let mut eseed = [0u8; ENCAPSULATION_SEED_BYTES];
+        rng.try_fill_bytes(&mut eseed)?;
+
+        let result = self.encapsulate_deterministic(&eseed);
+        eseed.zeroize();
+
+        result
+    }
+
+    /// Encapsulates a new shared secret to the public key, using the default RNG, `ChaCha20Rng`
+    /// seeded from system entropy
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (pk, _) = generate_keypair_with_default_rng().unwrap();
+    /// let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng()?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate_with_default_rng(&self) -> Result<(Ciphertext, SharedSecret), Error> {
+        self.encapsulate(&mut ChaCha20Rng::from_entropy())
+    }
+
+    /// Encapsulates a new shared secret using the given 64 bytes of randomness, the ML-KEM-768
+    /// message `m` followed by the X25519 ephemeral private key.
+    ///
+    /// This is intended for testing against known answer tests. The randomness must be uniformly
+    /// random and never reused, else the shared secret is not secret, so prefer
+    /// [`encapsulate`](PublicKey::encapsulate) in all other cases.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if encapsulation fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (pk, _) = generate_keypair_with_default_rng().unwrap();
+    /// let eseed = [0u8; 64];
+    /// let (ciphertext, shared_secret) = pk.encapsulate_deterministic(&eseed)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate_deterministic(
+        &self,
+        eseed: &[u8; ENCAPSULATION_SEED_BYTES],
+    ) -> Result<(Ciphertext, SharedSecret), Error> {
+        let (m, ek_x) = eseed.split_at(SYMBYTES);
+        let (ct_m, ss_m) = self.pk_m.encapsulate_deterministic(m.try_into()?)?;
+
+        let ek_x = StaticSecret::from(<[u8; X25519_BYTES]>::try_from(ek_x)?);
+        let ct_x = X25519PublicKey::from(&ek_x);
+        let ss_x = ek_x.diffie_hellman(&self.pk_x);
+
+        let mut bytes = [0u8; CIPHERTEXT_BYTES];
+        bytes[..ML_KEM_CIPHERTEXT_BYTES].copy_from_slice(ct_m.as_bytes());
+        bytes[ML_KEM_CIPHERTEXT_BYTES..].copy_from_slice(ct_x.as_bytes());
+
+        Ok((
+            Ciphertext { bytes },
+            combiner(&ss_m, ss_x.as_bytes(), &ct_x, &self.pk_x),
+        ))
+    }
+}
+
+impl Ciphertext {
+    /// Wraps a ciphertext encoding
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (pk, _) = generate_keypair_with_default_rng().unwrap();
+    /// # let (new_ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+    /// # let bytes = *new_ciphertext.as_bytes();
+    /// let ciphertext = Ciphertext::from_bytes(bytes);
+    /// ```
+    #[must_use]
+    pub const fn from_bytes(bytes: [u8; CIPHERTEXT_BYTES]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the ciphertext encoding
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::hybrid::xwing::*;
+    /// # let (pk, _) = generate_keypair_with_default_rng().unwrap();
+    /// # let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+    /// let bytes: &[u8; 1120] = ciphertext.as_bytes();
+    /// ```
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8; CIPHERTEXT_BYTES] {
+        &self.bytes
+    }
+
+    fn split(&self) -> Result<(MlKemCiphertext, X25519PublicKey), Error> {
+        let (m, x) = self.bytes.split_at(ML_KEM_CIPHERTEXT_BYTES);
+
+        Ok((
+            MlKemCiphertext::from_bytes(m)?,
+            X25519PublicKey::from(<[u8; X25519_BYTES]>::try_from(x)?),
+        ))
+    }
+}
+
+impl TryFrom<&[u8]> for Ciphertext {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes = bytes.try_into().map_err(|_| Error::InvalidLength {
+            expected: CIPHERTEXT_BYTES,
+            actual: bytes.len(),
+        })?;
+
+        Ok(Self { bytes })
+    }
+}
+
+impl AsRef<[u8]> for Ciphertext {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl ConstantTimeEq for Ciphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.bytes.ct_eq(&other.bytes)
+    }
+}
+
+impl PartialEq for Ciphertext {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Ciphertext {}
+
+// ss = SHA3-256(ss_M || ss_X || ct_X || pk_X || XWingLabel)
+fn combiner(
+    ss_m: &SharedSecret,
+    ss_x: &[u8; X25519_BYTES],
+    ct_x: &X25519PublicKey,
+    pk_x: &X25519PublicKey,
+) -> SharedSecret {
+    let mut shared_secret = [0u8; SHAREDSECRETBYTES];
+    shared_secret.copy_from_slice(
+        &Sha3_256::new()
+            .chain_update(ss_m.as_bytes())
+            .chain_update(ss_x)
+            .chain_update(ct_x.as_bytes())
+            .chain_update(pk_x.as_bytes())
+            .chain_update(XWING_LABEL)
+            .finalize(),
+    );
+
+    SharedSecret(shared_secret)
+}
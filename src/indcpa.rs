@@ -1,14 +1,16 @@
 use crate::{
-    errors::{CrystalsError, EncryptionDecryptionError, KeyGenerationError, PackingError},
+    errors::Error,
     matrix::Matrix,
-    params::{SecurityLevel, K, POLYBYTES, SYMBYTES},
+    params::{KeyDerivation, SecurityLevel, K, POLYBYTES, Q_I16, SYMBYTES},
     polynomials::{Montgomery, Normalised, Poly, Unreduced},
     vectors::PolyVec,
 };
 use sha3::{Digest, Sha3_512};
+use subtle::{Choice, ConstantTimeEq};
 use tinyvec::ArrayVec;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[derive(Clone, Copy, PartialEq, Debug, Eq)]
+#[derive(Clone, PartialEq, Debug, Eq)]
 pub struct PrivateKey {
     secret: PolyVec<Normalised>,
 }
@@ -20,44 +22,70 @@ pub struct PublicKey {
     a_t: Matrix<Montgomery>,
 }
 
+impl Zeroize for PrivateKey {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PrivateKey {}
+
+impl ConstantTimeEq for PrivateKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.secret.ct_eq(&other.secret)
+    }
+}
+
 impl PrivateKey {
     pub(crate) const fn sec_level(&self) -> SecurityLevel {
         self.secret.sec_level()
     }
 
     // buf should be of length indcpa_private_key_bytes
-    #[cfg(feature = "decap_key")]
-    pub(crate) fn pack(&self, buf: &mut [u8]) -> Result<(), PackingError> {
+    pub(crate) fn pack(&self, buf: &mut [u8]) -> Result<(), Error> {
         self.secret.pack(buf)
     }
 
     // buf should be of length indcpa_private_key_bytes
-    #[cfg(feature = "decap_key")]
-    pub(crate) fn unpack(buf: &[u8]) -> Result<Self, PackingError> {
-        let secret = PolyVec::unpack(buf)?.normalise();
+    pub(crate) fn unpack(buf: &[u8]) -> Result<Self, Error> {
+        let mut unpacked = PolyVec::unpack(buf)?;
+        let secret = unpacked.normalise();
+        unpacked.zeroize();
+
         Ok(Self { secret })
     }
 
-    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<[u8; SYMBYTES], EncryptionDecryptionError> {
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<[u8; SYMBYTES], Error> {
         let sec_level = self.sec_level();
         if ciphertext.len() == sec_level.indcpa_bytes() {
             let (u_bytes, v_bytes) = ciphertext.split_at(sec_level.poly_vec_compressed_bytes());
             let u = PolyVec::decompress(u_bytes)?.ntt();
             let v = Poly::decompress(v_bytes, &sec_level)?;
-            let inner_product = &self
+            let mut inner_product = self
                 .secret
                 .inner_product_pointwise(&u)
                 .barrett_reduce()
                 .inv_ntt();
-            let dif = v.sub(inner_product);
-            let m = dif.normalise();
+            let mut dif = v.sub(&inner_product);
+            let mut m = dif.normalise();
+            let message = m.write_msg();
 
-            Ok(m.write_msg()?)
+            inner_product.zeroize();
+            dif.zeroize();
+            m.zeroize();
+
+            Ok(message?)
         } else {
-            Err(
-                CrystalsError::IncorrectBufferLength(ciphertext.len(), sec_level.indcpa_bytes())
-                    .into(),
-            )
+            Err(Error::InvalidLength {
+                expected: sec_level.indcpa_bytes(),
+                actual: ciphertext.len(),
+            })
         }
     }
 }
@@ -69,7 +97,7 @@ impl PublicKey {
         // if self.noise.sec_level() == self.a_t.sec_level() {
         //     Ok(self.noise.sec_level())
         // } else {
-        //     Err(CrystalsError::MismatchedSecurityLevels(
+        //     Err(Error::MismatchedSecurityLevels(
         //         self.noise.sec_level(),
         //         self.a_t.sec_level(),
         //     ))
@@ -77,7 +105,7 @@ impl PublicKey {
     }
 
     // buf should be of length indcpa_public_key_bytes
-    pub(crate) fn pack(&self, buf: &mut [u8]) -> Result<(), PackingError> {
+    pub(crate) fn pack(&self, buf: &mut [u8]) -> Result<(), Error> {
         let k: usize = self.sec_level().k().into();
 
         let break_point: usize = POLYBYTES * k;
@@ -86,17 +114,30 @@ impl PublicKey {
             buf[break_point..].copy_from_slice(&self.rho[..]);
             Ok(())
         } else {
-            Err(CrystalsError::IncorrectBufferLength(buf.len(), break_point + SYMBYTES).into())
+            Err(Error::InvalidLength {
+                expected: break_point + SYMBYTES,
+                actual: buf.len(),
+            })
         }
     }
 
     // buf should be of length indcpa_public_key_bytes
-    pub(crate) fn unpack(buf: &[u8]) -> Result<Self, PackingError> {
+    pub(crate) fn unpack(buf: &[u8]) -> Result<Self, Error> {
         let k = K::try_from((buf.len() - SYMBYTES) / POLYBYTES)?;
         let k_value: usize = k.into();
         let break_point: usize = POLYBYTES * k_value;
 
-        let noise = PolyVec::unpack(&buf[..break_point])?.normalise();
+        let noise = PolyVec::unpack(&buf[..break_point])?;
+        // FIPS 203 7.2: ByteEncode12(ByteDecode12(ek)) == ek, i.e. every coefficient is below q
+        if noise
+            .polynomials()
+            .iter()
+            .flat_map(Poly::coeffs)
+            .any(|&coeff| coeff >= Q_I16)
+        {
+            return Err(Error::InvalidEncoding);
+        }
+        let noise = noise.normalise();
         let rho: [u8; SYMBYTES] = buf[break_point..].try_into()?;
 
         let a_t = Matrix::derive(&rho, true, k)?;
@@ -109,19 +150,19 @@ impl PublicKey {
         message: &[u8],              // length SYMBYTES
         seed: &[u8],                 // length SYMBYTES
         ciphertext_bytes: &mut [u8], // length indcpa_bytes()
-    ) -> Result<(), EncryptionDecryptionError> {
+    ) -> Result<(), Error> {
         let sec_level = self.sec_level();
         let k_value: usize = sec_level.k().into();
-        let msg_poly = Poly::read_msg(message)?;
+        let mut msg_poly = Poly::read_msg(message)?;
 
-        let rh = PolyVec::derive_noise(sec_level, seed, 0, sec_level.eta_1())
+        let mut rh = PolyVec::derive_noise(sec_level, seed, 0, sec_level.eta_1())
             .ntt()
             .barrett_reduce();
 
         #[allow(clippy::cast_possible_truncation)] // k_value will never be truncated
-        let error_1 = PolyVec::derive_noise(sec_level, seed, k_value as u8, sec_level.eta_2());
+        let mut error_1 = PolyVec::derive_noise(sec_level, seed, k_value as u8, sec_level.eta_2());
         #[allow(clippy::cast_possible_truncation)] // k_value will never be truncated
-        let error_2 = Poly::derive_noise(seed, (k_value as u8) * 2, sec_level.eta_2());
+        let mut error_2 = Poly::derive_noise(seed, (k_value as u8) * 2, sec_level.eta_2());
 
         //  u = A_t r + e_1
         let u = PolyVec::from(
@@ -151,17 +192,30 @@ impl PublicKey {
         u.compress(u_bytes)?;
         v.compress(v_bytes, &sec_level)?;
 
+        msg_poly.zeroize();
+        rh.zeroize();
+        error_1.zeroize();
+        error_2.zeroize();
+
         Ok(())
     }
 }
 
+// (rho, sigma) = G(d || k), as in FIPS 203 K-PKE.KeyGen.
+// The initial public draft omitted k, which is kept for loading legacy seeds.
 pub fn generate_indcpa_key_pair(
     seed: &[u8],
     sec_level: SecurityLevel,
-) -> Result<(PrivateKey, PublicKey), KeyGenerationError> {
+    derivation: KeyDerivation,
+) -> Result<(PrivateKey, PublicKey), Error> {
     let mut expanded_seed = [0u8; 2 * SYMBYTES];
     let mut hash = Sha3_512::new();
     hash.update(seed);
+    if derivation == KeyDerivation::Fips203 {
+        let k_value: usize = sec_level.k().into();
+        #[allow(clippy::cast_possible_truncation)] // k_value can only be 2, 3, 4
+        hash.update([k_value as u8]);
+    }
 
     expanded_seed.copy_from_slice(&hash.finalize());
 
@@ -170,15 +224,16 @@ pub fn generate_indcpa_key_pair(
 
     let sigma = &expanded_seed[32..]; // seed for noise
 
-    let secret = PolyVec::derive_noise(sec_level, sigma, 0, sec_level.eta_1())
+    let mut secret = PolyVec::derive_noise(sec_level, sigma, 0, sec_level.eta_1())
         .ntt()
         .normalise();
 
     let k_value: usize = sec_level.k().into();
     #[allow(clippy::cast_possible_truncation)] // k_value can only be 2, 3, 4
-    let error = PolyVec::derive_noise(sec_level, sigma, k_value as u8, sec_level.eta_1()).ntt();
+    let mut error = PolyVec::derive_noise(sec_level, sigma, k_value as u8, sec_level.eta_1()).ntt();
 
-    let noise_arr: ArrayVec<[Poly<Montgomery>; 4]> = a
+    // A * s on its own reveals the secret, so is wiped along with the noise
+    let mut noise_arr: ArrayVec<[Poly<Montgomery>; 4]> = a
         .vectors()
         .iter()
         .map(|row| row.inner_product_pointwise(&secret))
@@ -188,6 +243,12 @@ pub fn generate_indcpa_key_pair(
     let noise = PolyVec::from(noise_arr)?.add(&error)?.normalise();
 
     let a_t = a.transpose()?;
+    let private_key = PrivateKey { secret };
+
+    secret.zeroize();
+    error.zeroize();
+    noise_arr.iter_mut().for_each(Zeroize::zeroize);
+    expanded_seed.zeroize();
 
-    Ok((PrivateKey { secret }, PublicKey { rho, noise, a_t }))
+    Ok((private_key, PublicKey { rho, noise, a_t }))
 }
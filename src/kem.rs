@@ -1,44 +1,102 @@
+#[cfg(feature = "pkcs8")]
+mod pkcs8;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "kem")]
+mod traits;
+pub mod typed;
+
+pub use crate::params::KeyDerivation;
+#[cfg(feature = "pem")]
+pub use ::pkcs8::LineEnding;
+#[cfg(feature = "pkcs8")]
+pub use ::pkcs8::{DecodePrivateKey, DecodePublicKey};
+#[cfg(all(feature = "pkcs8", feature = "alloc"))]
+pub use ::pkcs8::{EncodePrivateKey, EncodePublicKey};
+#[cfg(feature = "serde")]
+pub use serialization::serde_private_key;
+
 use crate::{
-    errors::{CrystalsError, EncryptionDecryptionError, KeyGenerationError, PackingError},
+    errors::{Error, Validation},
     indcpa::{
         generate_indcpa_key_pair, PrivateKey as IndcpaPrivateKey, PublicKey as IndcpaPublicKey,
     },
     params::{SecurityLevel, K, MAX_CIPHERTEXT, SHAREDSECRETBYTES, SYMBYTES},
 };
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt::{Debug, Formatter};
 use rand_chacha::ChaCha20Rng;
 use rand_core::{CryptoRng, RngCore, SeedableRng};
 use sha3::{
     digest::{ExtendableOutput, Update, XofReader},
     Digest, Sha3_256, Sha3_512, Shake256,
 };
-use subtle::{ConditionallySelectable, ConstantTimeEq};
-use tinyvec::ArrayVec;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+#[cfg(feature = "alloc")]
+use zeroize::Zeroizing;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// `PrivateKey` struct that can only be generated via the [`generate_keypair_512`], [`generate_keypair_768`], or
 /// [`generate_keypair_1024`] functions and is used to [`decapsulate`](PrivateKey::decapsulate) a shared secret from a given ciphertext.
 ///
-/// Can be accessed in byte form by packing into a `u8` array using the [`pack`](PrivateKey::pack) method,
-/// and made available for use again using the [`unpack`](PrivateKey::unpack) method if `decap_key`
-/// feature flag is set, or [`unpack_512`](PrivateKey::unpack_512), [`unpack_768`](PrivateKey::unpack_768), or [`unpack_1024`](PrivateKey::unpack_1024).
+/// A private key can be held in two forms: the 64 byte seed `d || z`, and the expanded FIPS 203
+/// decapsulation key. Keys from [`generate_keypair_768`] and friends hold both. Keys loaded with
+/// [`from_seed_768`](PrivateKey::from_seed_768) and friends hold only the seed, and can be expanded once
+/// using [`expand`](PrivateKey::expand) before repeated use, else every [`decapsulate`](PrivateKey::decapsulate)
+/// re-derives the key from the seed. Keys loaded with [`from_expanded_bytes`](PrivateKey::from_expanded_bytes)
+/// hold only the expanded key, as the seed cannot be recovered from it.
 ///
-/// If using `decap_key` feature, the array used to pack must be of the correct length for the given security level, see
-/// [`pack`](PrivateKey::pack) for more.
-#[derive(Debug, Eq, PartialEq)]
+/// The expanded key can always be written out with [`to_expanded_bytes`](PrivateKey::to_expanded_bytes), while
+/// [`to_seed`](PrivateKey::to_seed) returns `None` if the seed is not known.
 pub struct PrivateKey {
-    #[cfg(not(feature = "decap_key"))]
-    key: PrivateSeed,
-    #[cfg(feature = "decap_key")]
-    key: PrivateKeyInner,
+    seed: Option<PrivateSeed>,
+    expanded: Option<PrivateKeyInner>,
     sec_level: SecurityLevel,
 }
 
-#[cfg(not(feature = "decap_key"))]
-#[derive(Debug, Eq, PartialEq)]
+// Seeds are compared when both keys have one, otherwise the expanded keys are compared, in
+// constant time either way
+impl PartialEq for PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        if self.sec_level != other.sec_level {
+            return false;
+        }
+        if let (Some(seed), Some(other_seed)) = (&self.seed, &other.seed) {
+            return seed.ct_eq(other_seed).into();
+        }
+
+        matches!(
+            self.with_expanded(|inner| {
+                other.with_expanded(|other_inner| bool::from(inner.ct_eq(other_inner)))
+            }),
+            Ok(Ok(true))
+        )
+    }
+}
+
+impl Eq for PrivateKey {}
+
+#[derive(Debug)]
 struct PrivateSeed {
     seed: [u8; 2 * SYMBYTES],
+    derivation: KeyDerivation,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+impl Drop for PrivateSeed {
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
+// The derivation is not secret, so is compared normally
+impl ConstantTimeEq for PrivateSeed {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.seed.ct_eq(&other.seed) & Choice::from(u8::from(self.derivation == other.derivation))
+    }
+}
+
+#[derive(Debug)]
 struct PrivateKeyInner {
     sk: IndcpaPrivateKey,
     pk: IndcpaPublicKey,
@@ -46,6 +104,34 @@ struct PrivateKeyInner {
     z: [u8; SYMBYTES],
 }
 
+// sk is wiped by its own drop
+impl Drop for PrivateKeyInner {
+    fn drop(&mut self) {
+        self.z.zeroize();
+    }
+}
+
+// The public key and its hash are not secret, so are compared normally
+impl ConstantTimeEq for PrivateKeyInner {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.sk.ct_eq(&other.sk)
+            & self.z.ct_eq(&other.z)
+            & Choice::from(u8::from(self.pk == other.pk && self.h_pk == other.h_pk))
+    }
+}
+
+// The secret parts of the key are wiped by the drops of PrivateSeed and PrivateKeyInner
+impl ZeroizeOnDrop for PrivateKey {}
+
+impl Debug for PrivateKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PrivateKey")
+            .field("sec_level", &self.sec_level)
+            .field("key", &"[REDACTED]")
+            .finish_non_exhaustive()
+    }
+}
+
 /// `PublicKey` struct that can only be generated via the [`generate_keypair_512`], [`generate_keypair_768`], or [`generate_keypair_1024`] functions or from the
 /// corresponding [`PrivateKey`] struct using the [`get_public_key`](PrivateKey::get_public_key)
 /// method and is used to [`encapsulate`](PublicKey::encapsulate) a shared secret.
@@ -59,32 +145,202 @@ pub struct PublicKey {
     h_pk: [u8; SYMBYTES],
 }
 
-/// `Ciphertext` struct that can only be generated by [`encapsulate`](PublicKey::encapsulate).
+/// `Ciphertext` struct that is generated by [`encapsulate`](PublicKey::encapsulate), or parsed from
+/// received bytes using [`from_bytes`](Ciphertext::from_bytes), and is used to
+/// [`decapsulate`](PrivateKey::decapsulate) a shared secret.
 ///
-/// Should be converted to bytes using the [`as_bytes`](Ciphertext::as_bytes) method to be transmitted and decapsulated.
+/// Should be converted to bytes using the [`as_bytes`](Ciphertext::as_bytes) method to be transmitted.
+/// Equality is checked in constant time.
+#[derive(Clone)]
 pub struct Ciphertext {
     bytes: [u8; MAX_CIPHERTEXT], // max ciphertext_bytes()
-    len: usize,
+    sec_level: SecurityLevel,
 }
 
 impl Ciphertext {
+    pub(crate) const fn sec_level(&self) -> SecurityLevel {
+        self.sec_level
+    }
+
+    /// Parses a received ciphertext, checking that it is of a valid length for one of the
+    /// ML-KEM parameter sets.
+    ///
+    /// # Inputs
+    /// - `bytes`: The ciphertext, of length:
+    ///
+    /// | Security Level | Length |
+    /// |----------------|--------|
+    /// | 512            | 768    |
+    /// | 768            | 1088   |
+    /// | 1024           | 1568   |
+    ///
+    /// # Outputs
+    /// - [`Ciphertext`] object
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the ciphertext is not of a valid length
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// # let (new_ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+    /// # let bytes = new_ciphertext.as_bytes();
+    /// let ciphertext = Ciphertext::from_bytes(bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let sec_level = [K::Two, K::Three, K::Four]
+            .into_iter()
+            .map(SecurityLevel::new)
+            .find(|sec_level| sec_level.ciphertext_bytes() == bytes.len())
+            .ok_or(Error::UnsupportedLength {
+                actual: bytes.len(),
+            })?;
+
+        let mut ciphertext = [0u8; MAX_CIPHERTEXT];
+        ciphertext[..bytes.len()].copy_from_slice(bytes);
+
+        Ok(Self {
+            bytes: ciphertext,
+            sec_level,
+        })
+    }
+
     /// Returns a byte slice of the ciphertext
     ///
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (pk, sk) = generate_keypair_768(None).unwrap();
-    /// let (ciphertext_obj, shared_secret) = pk.encapsulate(None, None)?;
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let (ciphertext_obj, shared_secret) = pk.encapsulate_with_default_rng()?;
     /// let ciphertext = ciphertext_obj.as_bytes();
     ///
-    /// # Ok::<(), enc_rust::errors::EncryptionDecryptionError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
     #[must_use]
     pub fn as_bytes(&self) -> &[u8] {
-        &self.bytes[..self.len]
+        &self.bytes[..self.sec_level.ciphertext_bytes()]
+    }
+
+    /// Returns the ciphertext as an owned buffer
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let (ciphertext_obj, shared_secret) = pk.encapsulate_with_default_rng()?;
+    /// let ciphertext = ciphertext_obj.to_vec();
+    /// assert_eq!(ciphertext.len(), 1088);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+}
+
+impl TryFrom<&[u8]> for Ciphertext {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl AsRef<[u8]> for Ciphertext {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl ConstantTimeEq for Ciphertext {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.as_bytes().ct_eq(other.as_bytes())
+    }
+}
+
+impl PartialEq for Ciphertext {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
     }
 }
 
+impl Eq for Ciphertext {}
+
+impl Debug for Ciphertext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Ciphertext")
+            .field("sec_level", &self.sec_level)
+            .field("bytes", &self.as_bytes())
+            .finish()
+    }
+}
+
+/// `SharedSecret` struct holding the 32 byte shared secret produced by
+/// [`encapsulate`](PublicKey::encapsulate) and [`decapsulate`](PrivateKey::decapsulate).
+///
+/// The secret is zeroised when dropped, and can only be accessed using the
+/// [`as_bytes`](SharedSecret::as_bytes) method. Equality is checked in constant time, and the
+/// secret is redacted from the [`Debug`] output.
+#[derive(Clone)]
+pub struct SharedSecret(pub(crate) [u8; SHAREDSECRETBYTES]);
+
+impl SharedSecret {
+    /// Returns a reference to the bytes of the shared secret
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let (_, shared_secret) = pk.encapsulate_with_default_rng()?;
+    /// let secret_bytes: &[u8; 32] = shared_secret.as_bytes();
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[must_use]
+    pub const fn as_bytes(&self) -> &[u8; SHAREDSECRETBYTES] {
+        &self.0
+    }
+}
+
+impl ConstantTimeEq for SharedSecret {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for SharedSecret {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SharedSecret {}
+
+impl Debug for SharedSecret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SharedSecret([REDACTED])")
+    }
+}
+
+impl Zeroize for SharedSecret {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SharedSecret {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SharedSecret {}
+
 fn sha3_256_from(input: &[u8]) -> [u8; SYMBYTES] {
     let mut hash = Sha3_256::new();
     Digest::update(&mut hash, input);
@@ -93,22 +349,28 @@ fn sha3_256_from(input: &[u8]) -> [u8; SYMBYTES] {
     output
 }
 
-fn sha3_512_from(input: &[u8]) -> ([u8; SHAREDSECRETBYTES], [u8; SYMBYTES]) {
+// Inputs are hashed in order, rather than concatenated into a buffer that would need wiping.
+fn sha3_512_from(inputs: &[&[u8]]) -> ([u8; SHAREDSECRETBYTES], [u8; SYMBYTES]) {
     let mut hash = Sha3_512::new();
-    Digest::update(&mut hash, input);
-    let output = hash.finalize();
+    for input in inputs {
+        Digest::update(&mut hash, input);
+    }
+    let mut output = hash.finalize();
 
     let mut o1 = [0u8; SHAREDSECRETBYTES];
     let mut o2 = [0u8; SYMBYTES];
 
     o1.copy_from_slice(&output[..SHAREDSECRETBYTES]);
     o2.copy_from_slice(&output[SHAREDSECRETBYTES..]);
+    output[..].zeroize();
     (o1, o2)
 }
 
-fn shake256_from(input: &[u8]) -> [u8; SHAREDSECRETBYTES] {
+fn shake256_from(inputs: &[&[u8]]) -> [u8; SHAREDSECRETBYTES] {
     let mut hash = Shake256::default();
-    hash.update(input);
+    for input in inputs {
+        hash.update(input);
+    }
     let mut output = [0u8; SHAREDSECRETBYTES];
     hash.finalize_xof().read(&mut output);
     output
@@ -116,10 +378,11 @@ fn shake256_from(input: &[u8]) -> [u8; SHAREDSECRETBYTES] {
 
 // derived new keypair deterministically from a given 64 (2 * 32) byte seed.
 fn new_key_from_seed(
-    seed: [u8; 2 * SYMBYTES],
+    mut seed: [u8; 2 * SYMBYTES],
     sec_level: SecurityLevel,
-) -> Result<(PublicKey, PrivateKeyInner), KeyGenerationError> {
-    let (sk, pk) = generate_indcpa_key_pair(&seed[..SYMBYTES], sec_level)?;
+    derivation: KeyDerivation,
+) -> Result<(PublicKey, PrivateKeyInner), Error> {
+    let (sk, pk) = generate_indcpa_key_pair(&seed[..SYMBYTES], sec_level, derivation)?;
 
     let z: [u8; SYMBYTES] = seed[SYMBYTES..].try_into()?;
 
@@ -127,145 +390,343 @@ fn new_key_from_seed(
     pk.pack(&mut packed_pk[..sec_level.indcpa_public_key_bytes()])?;
 
     let h_pk: [u8; SYMBYTES] = sha3_256_from(&packed_pk[..sec_level.indcpa_public_key_bytes()]);
+    seed.zeroize();
 
     Ok((PublicKey { pk, h_pk }, PrivateKeyInner { sk, pk, h_pk, z }))
 }
 
-/// Acceptable RNG to be used in encapsulation and key generation must have the
-/// [`RngCore`](https://docs.rs/rand_core/latest/rand_core/trait.RngCore.html) and
-/// [`CryptoRng`](https://docs.rs/rand_core/latest/rand_core/trait.CryptoRng.html) traits.
-pub trait AcceptableRng: RngCore + CryptoRng {}
+pub(crate) fn generate_key_pair<R: CryptoRng + RngCore + ?Sized>(
+    rng: &mut R,
+    k: K,
+) -> Result<(PublicKey, PrivateKey), Error> {
+    let mut seed = [0u8; 2 * SYMBYTES];
+    rng.try_fill_bytes(&mut seed)?;
+
+    let sec_level = SecurityLevel::new(k);
+
+    let key_pair = key_pair_from_seed(seed, sec_level, KeyDerivation::Fips203);
+    seed.zeroize();
 
-pub(crate) fn generate_key_pair(
-    rng: Option<&mut dyn AcceptableRng>,
+    key_pair
+}
+
+pub(crate) fn generate_key_pair_from_seed(
+    d: &[u8; SYMBYTES],
+    z: &[u8; SYMBYTES],
     k: K,
-) -> Result<(PublicKey, PrivateKey), KeyGenerationError> {
+) -> Result<(PublicKey, PrivateKey), Error> {
     let mut seed = [0u8; 2 * SYMBYTES];
+    seed[..SYMBYTES].copy_from_slice(d);
+    seed[SYMBYTES..].copy_from_slice(z);
 
-    if let Some(rng) = rng {
-        rng.try_fill_bytes(&mut seed)?;
-    } else {
-        let mut chacha = ChaCha20Rng::from_entropy();
-        chacha.try_fill_bytes(&mut seed)?;
-    };
+    let key_pair = key_pair_from_seed(seed, SecurityLevel::new(k), KeyDerivation::Fips203);
+    seed.zeroize();
 
-    let sec_level = SecurityLevel::new(k);
+    key_pair
+}
 
-    let (pk, _sk_inner) = new_key_from_seed(seed, sec_level)?;
+// ML-KEM.KeyGen_internal for KeyDerivation::Fips203, where seed is d || z.
+pub(crate) fn key_pair_from_seed(
+    mut seed: [u8; 2 * SYMBYTES],
+    sec_level: SecurityLevel,
+    derivation: KeyDerivation,
+) -> Result<(PublicKey, PrivateKey), Error> {
+    let (pk, sk_inner) = new_key_from_seed(seed, sec_level, derivation)?;
 
-    Ok((
-        pk,
-        PrivateKey {
-            #[cfg(not(feature = "decap_key"))]
-            key: PrivateSeed { seed },
-            #[cfg(feature = "decap_key")]
-            #[allow(clippy::used_underscore_binding)]
-            key: _sk_inner,
-            sec_level,
-        },
-    ))
+    let sk = PrivateKey {
+        seed: Some(PrivateSeed { seed, derivation }),
+        expanded: Some(sk_inner),
+        sec_level,
+    };
+    seed.zeroize();
+
+    Ok((pk, sk))
 }
 
 /// Generates a new keypair for the 512 Security Parameters.
 ///
 /// # Inputs
-/// - `rng`: (Optional) RNG to be used when generating the keypair. Must satisfy the
-///   [`RngCore`](https://docs.rs/rand_core/latest/rand_core/trait.RngCore.html) and
-///   [`CryptoRng`](https://docs.rs/rand_core/latest/rand_core/trait.CryptoRng.html) traits.
-///   If RNG is not present, then
-///   [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
-///   will be used.
+/// - `rng`: RNG to be used when generating the keypair. Must satisfy the
+///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
 ///
 /// # Outputs
 /// - [`PublicKey`] object
 /// - [`PrivateKey`] object
 ///
 /// # Errors
-/// Will return a [`KeyGenerationError`] if:
-/// - Given invalid K value
+/// Will return an [`Error`] if:
 /// - RNG fails
 ///
 /// # Example
 /// ```
 /// # use enc_rust::kem::*;
-/// let (pk, sk) = generate_keypair_512(None)?;
+/// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
 ///
-/// # Ok::<(), enc_rust::errors::KeyGenerationError>(())
+/// let mut rng = ChaCha20Rng::from_entropy();
+/// let (pk, sk) = generate_keypair_512(&mut rng)?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
 /// ```
-pub fn generate_keypair_512(
-    rng: Option<&mut dyn AcceptableRng>,
-) -> Result<(PublicKey, PrivateKey), KeyGenerationError> {
+pub fn generate_keypair_512<R: CryptoRng + RngCore + ?Sized>(
+    rng: &mut R,
+) -> Result<(PublicKey, PrivateKey), Error> {
     generate_key_pair(rng, K::Two)
 }
 
+/// Generates a new keypair for the 512 Security Parameters, using
+/// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+/// seeded from the system entropy source.
+///
+/// # Outputs
+/// - [`PublicKey`] object
+/// - [`PrivateKey`] object
+///
+/// # Errors
+/// Will return an [`Error`] if:
+/// - RNG fails
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::*;
+/// let (pk, sk) = generate_keypair_512_with_default_rng()?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn generate_keypair_512_with_default_rng() -> Result<(PublicKey, PrivateKey), Error> {
+    generate_key_pair(&mut ChaCha20Rng::from_entropy(), K::Two)
+}
+
 /// Generates a new keypair for the 768 Security Parameters.
 ///
 /// # Inputs
-/// - `rng`: (Optional) RNG to be used when generating the keypair. Must satisfy the
-///   [`RngCore`](https://docs.rs/rand_core/latest/rand_core/trait.RngCore.html) and
-///   [`CryptoRng`](https://docs.rs/rand_core/latest/rand_core/trait.CryptoRng.html) traits.
-///   If RNG is not present, then
-///   [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
-///   will be used.
+/// - `rng`: RNG to be used when generating the keypair. Must satisfy the
+///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
 ///
 /// # Outputs
 /// - [`PublicKey`] object
 /// - [`PrivateKey`] object
 ///
 /// # Errors
-/// Will return a [`KeyGenerationError`] if:
-/// - Given invalid K value
+/// Will return an [`Error`] if:
 /// - RNG fails
 ///
 /// # Example
 /// ```
 /// # use enc_rust::kem::*;
-/// let (pk, sk) = generate_keypair_768(None)?;
+/// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+///
+/// let mut rng = ChaCha20Rng::from_entropy();
+/// let (pk, sk) = generate_keypair_768(&mut rng)?;
 ///
-/// # Ok::<(), enc_rust::errors::KeyGenerationError>(())
+/// # Ok::<(), enc_rust::errors::Error>(())
 /// ```
-pub fn generate_keypair_768(
-    rng: Option<&mut dyn AcceptableRng>,
-) -> Result<(PublicKey, PrivateKey), KeyGenerationError> {
+pub fn generate_keypair_768<R: CryptoRng + RngCore + ?Sized>(
+    rng: &mut R,
+) -> Result<(PublicKey, PrivateKey), Error> {
     generate_key_pair(rng, K::Three)
 }
 
+/// Generates a new keypair for the 768 Security Parameters, using
+/// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+/// seeded from the system entropy source.
+///
+/// # Outputs
+/// - [`PublicKey`] object
+/// - [`PrivateKey`] object
+///
+/// # Errors
+/// Will return an [`Error`] if:
+/// - RNG fails
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::*;
+/// let (pk, sk) = generate_keypair_768_with_default_rng()?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn generate_keypair_768_with_default_rng() -> Result<(PublicKey, PrivateKey), Error> {
+    generate_key_pair(&mut ChaCha20Rng::from_entropy(), K::Three)
+}
+
 /// Generates a new keypair for the 1024 Security Parameters.
 ///
 /// # Inputs
-/// - `rng`: (Optional) RNG to be used when generating the keypair. Must satisfy the
-///   [`RngCore`](https://docs.rs/rand_core/latest/rand_core/trait.RngCore.html) and
-///   [`CryptoRng`](https://docs.rs/rand_core/latest/rand_core/trait.CryptoRng.html) traits.
-///   If RNG is not present, then
-///   [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
-///   will be used.
+/// - `rng`: RNG to be used when generating the keypair. Must satisfy the
+///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
 ///
 /// # Outputs
 /// - [`PublicKey`] object
 /// - [`PrivateKey`] object
 ///
 /// # Errors
-/// Will return a [`KeyGenerationError`] if:
-/// - Given invalid K value
+/// Will return an [`Error`] if:
 /// - RNG fails
 ///
 /// # Example
 /// ```
 /// # use enc_rust::kem::*;
-/// let (pk, sk) = generate_keypair_1024(None)?;
+/// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+///
+/// let mut rng = ChaCha20Rng::from_entropy();
+/// let (pk, sk) = generate_keypair_1024(&mut rng)?;
 ///
-/// # Ok::<(), enc_rust::errors::KeyGenerationError>(())
+/// # Ok::<(), enc_rust::errors::Error>(())
 /// ```
-pub fn generate_keypair_1024(
-    rng: Option<&mut dyn AcceptableRng>,
-) -> Result<(PublicKey, PrivateKey), KeyGenerationError> {
+pub fn generate_keypair_1024<R: CryptoRng + RngCore + ?Sized>(
+    rng: &mut R,
+) -> Result<(PublicKey, PrivateKey), Error> {
     generate_key_pair(rng, K::Four)
 }
 
+/// Generates a new keypair for the 1024 Security Parameters, using
+/// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+/// seeded from the system entropy source.
+///
+/// # Outputs
+/// - [`PublicKey`] object
+/// - [`PrivateKey`] object
+///
+/// # Errors
+/// Will return an [`Error`] if:
+/// - RNG fails
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::*;
+/// let (pk, sk) = generate_keypair_1024_with_default_rng()?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn generate_keypair_1024_with_default_rng() -> Result<(PublicKey, PrivateKey), Error> {
+    generate_key_pair(&mut ChaCha20Rng::from_entropy(), K::Four)
+}
+
+/// Deterministically derives a keypair for the 512 Security Parameters from the seeds `d` and `z`,
+/// as in FIPS 203 `ML-KEM.KeyGen_internal`. The same `d` and `z` will always produce the same keypair.
+///
+/// `d` and `z` must be generated by an approved RNG and kept secret, as they are the private key.
+/// The returned [`PrivateKey`] holds them as its seed, `d || z`, see [`PrivateKey::to_seed`].
+///
+/// # Inputs
+/// - `d`: 32 byte seed for the underlying K-PKE keypair
+/// - `z`: 32 byte implicit rejection value
+///
+/// # Outputs
+/// - [`PublicKey`] object
+/// - [`PrivateKey`] object
+///
+/// # Errors
+/// Will return an [`Error`] if key generation fails
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::*;
+/// let (d, z) = ([1u8; 32], [2u8; 32]);
+/// let (pk, sk) = generate_keypair_from_seed_512(&d, &z)?;
+/// assert_eq!(generate_keypair_from_seed_512(&d, &z)?.0, pk);
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn generate_keypair_from_seed_512(
+    d: &[u8; SYMBYTES],
+    z: &[u8; SYMBYTES],
+) -> Result<(PublicKey, PrivateKey), Error> {
+    generate_key_pair_from_seed(d, z, K::Two)
+}
+
+/// Deterministically derives a keypair for the 768 Security Parameters from the seeds `d` and `z`,
+/// as in FIPS 203 `ML-KEM.KeyGen_internal`. The same `d` and `z` will always produce the same keypair.
+///
+/// `d` and `z` must be generated by an approved RNG and kept secret, as they are the private key.
+/// The returned [`PrivateKey`] holds them as its seed, `d || z`, see [`PrivateKey::to_seed`].
+///
+/// # Inputs
+/// - `d`: 32 byte seed for the underlying K-PKE keypair
+/// - `z`: 32 byte implicit rejection value
+///
+/// # Outputs
+/// - [`PublicKey`] object
+/// - [`PrivateKey`] object
+///
+/// # Errors
+/// Will return an [`Error`] if key generation fails
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::*;
+/// let (d, z) = ([1u8; 32], [2u8; 32]);
+/// let (pk, sk) = generate_keypair_from_seed_768(&d, &z)?;
+/// assert_eq!(generate_keypair_from_seed_768(&d, &z)?.0, pk);
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn generate_keypair_from_seed_768(
+    d: &[u8; SYMBYTES],
+    z: &[u8; SYMBYTES],
+) -> Result<(PublicKey, PrivateKey), Error> {
+    generate_key_pair_from_seed(d, z, K::Three)
+}
+
+/// Deterministically derives a keypair for the 1024 Security Parameters from the seeds `d` and `z`,
+/// as in FIPS 203 `ML-KEM.KeyGen_internal`. The same `d` and `z` will always produce the same keypair.
+///
+/// `d` and `z` must be generated by an approved RNG and kept secret, as they are the private key.
+/// The returned [`PrivateKey`] holds them as its seed, `d || z`, see [`PrivateKey::to_seed`].
+///
+/// # Inputs
+/// - `d`: 32 byte seed for the underlying K-PKE keypair
+/// - `z`: 32 byte implicit rejection value
+///
+/// # Outputs
+/// - [`PublicKey`] object
+/// - [`PrivateKey`] object
+///
+/// # Errors
+/// Will return an [`Error`] if key generation fails
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::*;
+/// let (d, z) = ([1u8; 32], [2u8; 32]);
+/// let (pk, sk) = generate_keypair_from_seed_1024(&d, &z)?;
+/// assert_eq!(generate_keypair_from_seed_1024(&d, &z)?.0, pk);
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn generate_keypair_from_seed_1024(
+    d: &[u8; SYMBYTES],
+    z: &[u8; SYMBYTES],
+) -> Result<(PublicKey, PrivateKey), Error> {
+    generate_key_pair_from_seed(d, z, K::Four)
+}
+
 impl PrivateKey {
-    #[cfg(feature = "decap_key")]
     pub(crate) const fn sec_level(&self) -> SecurityLevel {
-        self.key.sk.sec_level()
+        self.sec_level
+    }
+
+    // Always succeeds for keys that hold a seed, and at least one of the seed or expanded key is held
+    fn derive_expanded(&self) -> Result<PrivateKeyInner, Error> {
+        let Some(seed) = &self.seed else {
+            return Err(Error::Internal);
+        };
+        let (_, inner) = new_key_from_seed(seed.seed, self.sec_level, seed.derivation)?;
+
+        Ok(inner)
+    }
+
+    // Runs f on the cached expanded key, or on a temporary one derived from the seed
+    fn with_expanded<T>(&self, f: impl FnOnce(&PrivateKeyInner) -> T) -> Result<T, Error> {
+        if let Some(inner) = &self.expanded {
+            return Ok(f(inner));
+        }
+        let inner = self.derive_expanded()?;
+
+        Ok(f(&inner))
     }
 
     /// Returns the corresponding public key for a given private key
@@ -273,52 +734,154 @@ impl PrivateKey {
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// let (_, sk) = generate_keypair_768(None)?;
+    /// let (_, sk) = generate_keypair_768_with_default_rng()?;
     /// let pk = sk.get_public_key();
     ///
-    /// # Ok::<(), enc_rust::errors::KeyGenerationError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
     #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
     #[must_use]
     pub fn get_public_key(&self) -> PublicKey {
-        #[cfg(not(feature = "decap_key"))]
-        {
-            let (pk, _) = new_key_from_seed(self.key.seed, self.sec_level).unwrap();
+        self.with_expanded(|inner| PublicKey {
+            pk: inner.pk,
+            h_pk: inner.h_pk,
+        })
+        .unwrap()
+    }
 
-            pk
+    /// Expands the private key seed, caching the expanded key so that later calls to
+    /// [`decapsulate`](PrivateKey::decapsulate) and [`get_public_key`](PrivateKey::get_public_key)
+    /// do not need to re-derive it. The seed is kept, so [`to_seed`](PrivateKey::to_seed) is unaffected.
+    ///
+    /// Does nothing if the key is already expanded.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if expanding the seed fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// # let (pk, new_sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// # let seed = new_sk.to_seed().unwrap();
+    /// let mut sk = PrivateKey::from_seed_768(seed);
+    /// sk.expand()?;
+    /// assert!(sk.is_expanded());
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn expand(&mut self) -> Result<(), Error> {
+        if self.expanded.is_none() {
+            self.expanded = Some(self.derive_expanded()?);
         }
-        #[cfg(feature = "decap_key")]
-        {
-            PublicKey {
-                pk: self.key.pk,
-                h_pk: self.key.h_pk,
+
+        Ok(())
+    }
+
+    /// Returns whether the expanded key is held, see [`expand`](PrivateKey::expand)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// let (_, sk) = generate_keypair_768_with_default_rng()?;
+    /// assert!(sk.is_expanded());
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[must_use]
+    pub const fn is_expanded(&self) -> bool {
+        self.expanded.is_some()
+    }
+
+    /// Returns the [`KeyDerivation`] used to expand the private key seed, or `None` if the key
+    /// was loaded from its expanded form and the seed is not known
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// let (_, sk) = generate_keypair_768_with_default_rng()?;
+    /// assert_eq!(sk.derivation(), Some(KeyDerivation::Fips203));
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[must_use]
+    pub const fn derivation(&self) -> Option<KeyDerivation> {
+        match &self.seed {
+            Some(seed) => Some(seed.derivation),
+            None => None,
+        }
+    }
+
+    /// Loads a 64 byte private key seed, detecting which [`KeyDerivation`] produced the given
+    /// corresponding public key.
+    ///
+    /// Seeds packed by earlier releases were expanded using the FIPS 203 initial public draft,
+    /// and so will not match their public key when loaded with [`from_seed_768`](PrivateKey::from_seed_768) and friends.
+    /// The returned key will decapsulate ciphertexts for `pk` under either derivation, and its
+    /// [`derivation`](PrivateKey::derivation) can be checked to decide whether the key should be rotated.
+    ///
+    /// # Inputs
+    /// - `seed`: The 64 byte private key seed
+    /// - `pk`: The [`PublicKey`] that was originally generated alongside `seed`
+    ///
+    /// # Outputs
+    /// - [`PrivateKey`] object
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if `seed` does not derive `pk` under any supported
+    /// [`KeyDerivation`]
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// # let (pk, new_sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// # let seed = new_sk.to_seed().unwrap();
+    /// let sk = PrivateKey::migrate_seed(seed, &pk)?;
+    /// assert_eq!(sk.derivation(), Some(KeyDerivation::Fips203));
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn migrate_seed(seed: [u8; 2 * SYMBYTES], pk: &PublicKey) -> Result<Self, Error> {
+        let sec_level = pk.sec_level();
+
+        for derivation in [KeyDerivation::Fips203, KeyDerivation::Fips203Ipd] {
+            let (derived_pk, sk) = key_pair_from_seed(seed, sec_level, derivation)?;
+            if derived_pk.h_pk.ct_eq(&pk.h_pk).into() {
+                return Ok(sk);
             }
         }
+
+        Err(Error::ValidationFailure(Validation::KeyDerivation))
     }
 
-    /// Packs the private key as bytes and returns it as a 64 byte array
+    /// Returns the 64 byte private key seed `d || z`, or `None` if:
+    /// - The key was loaded from its expanded form using
+    ///   [`from_expanded_bytes`](PrivateKey::from_expanded_bytes)
+    /// - The seed uses the legacy [`Fips203Ipd`](KeyDerivation::Fips203Ipd) derivation, see
+    ///   [`migrate_seed`](PrivateKey::migrate_seed). Seeds are always loaded with
+    ///   [`Fips203`](KeyDerivation::Fips203), which would give a different keypair, so such keys
+    ///   can only be exported in their expanded form.
     ///
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// let (_, sk) = generate_keypair_768(None).unwrap();
-    /// #[cfg(feature = "decap_key")]
-    /// {
-    ///     let mut sk_bytes = [0u8; 2400];
-    ///     sk.pack(&mut sk_bytes)?;
-    /// }
-    /// #[cfg(not(feature = "decap_key"))]
-    /// let sk_bytes = sk.pack();
+    /// let (_, sk) = generate_keypair_768_with_default_rng()?;
+    /// let seed = sk.to_seed().unwrap();
     ///
-    /// # Ok::<(), enc_rust::errors::PackingError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
     #[must_use]
-    #[cfg(not(feature = "decap_key"))]
-    pub const fn pack(&self) -> [u8; 2 * SYMBYTES] {
-        self.key.seed
+    pub const fn to_seed(&self) -> Option<[u8; 2 * SYMBYTES]> {
+        match &self.seed {
+            Some(PrivateSeed {
+                seed,
+                derivation: KeyDerivation::Fips203,
+            }) => Some(*seed),
+            _ => None,
+        }
     }
 
-    /// Packs private key into a given buffer
+    /// Packs the expanded private key into a given buffer, deriving it from the seed if it is not
+    /// held. This is the FIPS 203 decapsulation key format `dk`.
     ///
     /// # Inputs
     /// - `bytes`: Buffer for the private key to be packed into. For corresponding
@@ -331,49 +894,67 @@ impl PrivateKey {
     /// | 1024           | 3168   |
     ///
     /// # Errors
-    /// Will return a [`PackingError`] if the buffer is of the wrong length
+    /// Will return an [`Error`] if the buffer is of the wrong length
     ///
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// let (_, sk) = generate_keypair_768(None).unwrap();
-    /// #[cfg(feature = "decap_key")]
-    /// {
-    ///     let mut sk_bytes = [0u8; 2400];
-    ///     sk.pack(&mut sk_bytes)?;
-    /// }
-    /// #[cfg(not(feature = "decap_key"))]
-    /// let sk_bytes = sk.pack();
+    /// let (_, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let mut sk_bytes = [0u8; 2400];
+    /// sk.to_expanded_bytes(&mut sk_bytes)?;
     ///
-    /// # Ok::<(), enc_rust::errors::PackingError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
-    #[cfg(feature = "decap_key")]
-    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), PackingError> {
-        let sec_level = self.sec_level();
+    pub fn to_expanded_bytes(&self, bytes: &mut [u8]) -> Result<(), Error> {
+        let sec_level = self.sec_level;
 
         if bytes.len() != sec_level.private_key_bytes() {
-            return Err(CrystalsError::IncorrectBufferLength(
-                bytes.len(),
-                sec_level.private_key_bytes(),
-            )
-            .into());
+            return Err(Error::InvalidLength {
+                expected: sec_level.private_key_bytes(),
+                actual: bytes.len(),
+            });
         }
 
         let (sk_bytes, rest) = bytes.split_at_mut(sec_level.indcpa_private_key_bytes());
         let (pk_bytes, rest) = rest.split_at_mut(sec_level.indcpa_public_key_bytes());
         let (h_pk_bytes, z_bytes) = rest.split_at_mut(SYMBYTES);
-        self.key.sk.pack(sk_bytes)?;
-        self.key.pk.pack(pk_bytes)?;
-        h_pk_bytes.copy_from_slice(&self.key.h_pk);
-        z_bytes.copy_from_slice(&self.key.z);
+        self.with_expanded(|inner| {
+            inner.sk.pack(sk_bytes)?;
+            inner.pk.pack(pk_bytes)?;
+            h_pk_bytes.copy_from_slice(&inner.h_pk);
+            z_bytes.copy_from_slice(&inner.z);
 
-        Ok(())
+            Ok(())
+        })
+        .map_err(|_| Error::Internal)?
+    }
+
+    /// Returns the expanded private key as an owned buffer, which is wiped when dropped, see
+    /// [`to_expanded_bytes`](PrivateKey::to_expanded_bytes)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// let (_, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let sk_bytes = sk.to_vec();
+    /// assert_eq!(sk_bytes.len(), 2400);
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(vec![0u8; self.sec_level.private_key_bytes()]);
+        // cannot fail, as the buffer is always the correct length
+        self.to_expanded_bytes(&mut bytes).unwrap();
+
+        bytes
     }
 
-    /// Unpacks a buffer of bytes into a [`PrivateKey`]
+    /// Loads a 64 byte ML-KEM-512 private key seed `d || z` into a [`PrivateKey`]. The key is
+    /// expanded lazily, see [`expand`](PrivateKey::expand).
     ///
     /// # Inputs
-    /// - `bytes`: Buffer for the private key to be extracted from
+    /// - `seed`: The 64 byte private key seed
     ///
     /// # Outputs
     /// - [`PrivateKey`] object
@@ -381,33 +962,22 @@ impl PrivateKey {
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (pk, new_sk) = generate_keypair_768(None).unwrap();
-    /// # #[cfg(feature = "decap_key")]
-    /// # {
-    /// #     let mut sk_bytes = [0u8; 2400];
-    /// #     new_sk.pack(&mut sk_bytes)?;
-    /// # }
-    /// # #[cfg(not(feature = "decap_key"))]
-    /// # let sk_bytes = new_sk.pack();
+    /// # let (pk, new_sk) = generate_keypair_512_with_default_rng().unwrap();
+    /// # let seed = new_sk.to_seed().unwrap();
+    /// let sk = PrivateKey::from_seed_512(seed);
     ///
-    /// # #[cfg(not(feature = "decap_key"))]
-    /// let sk = PrivateKey::unpack_512(sk_bytes);
-    ///
-    /// # Ok::<(), enc_rust::errors::PackingError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
     #[must_use]
-    #[cfg(not(feature = "decap_key"))]
-    pub const fn unpack_512(bytes: [u8; 2 * SYMBYTES]) -> Self {
-        Self {
-            key: PrivateSeed { seed: bytes },
-            sec_level: SecurityLevel::new(K::Two),
-        }
+    pub const fn from_seed_512(seed: [u8; 2 * SYMBYTES]) -> Self {
+        Self::from_seed(seed, SecurityLevel::new(K::Two))
     }
 
-    /// Unpacks a buffer of bytes into a [`PrivateKey`]
+    /// Loads a 64 byte ML-KEM-768 private key seed `d || z` into a [`PrivateKey`]. The key is
+    /// expanded lazily, see [`expand`](PrivateKey::expand).
     ///
     /// # Inputs
-    /// - `bytes`: Buffer for the private key to be extracted from
+    /// - `seed`: The 64 byte private key seed
     ///
     /// # Outputs
     /// - [`PrivateKey`] object
@@ -415,33 +985,22 @@ impl PrivateKey {
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (pk, new_sk) = generate_keypair_768(None).unwrap();
-    /// # #[cfg(feature = "decap_key")]
-    /// # {
-    /// #     let mut sk_bytes = [0u8; 2400];
-    /// #     new_sk.pack(&mut sk_bytes)?;
-    /// # }
-    /// # #[cfg(not(feature = "decap_key"))]
-    /// # let sk_bytes = new_sk.pack();
-    ///
-    /// # #[cfg(not(feature = "decap_key"))]
-    /// let sk = PrivateKey::unpack_768(sk_bytes);
+    /// # let (pk, new_sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// # let seed = new_sk.to_seed().unwrap();
+    /// let sk = PrivateKey::from_seed_768(seed);
     ///
-    /// # Ok::<(), enc_rust::errors::PackingError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
     #[must_use]
-    #[cfg(not(feature = "decap_key"))]
-    pub const fn unpack_768(bytes: [u8; 2 * SYMBYTES]) -> Self {
-        Self {
-            key: PrivateSeed { seed: bytes },
-            sec_level: SecurityLevel::new(K::Three),
-        }
+    pub const fn from_seed_768(seed: [u8; 2 * SYMBYTES]) -> Self {
+        Self::from_seed(seed, SecurityLevel::new(K::Three))
     }
 
-    /// Unpacks a buffer of bytes into a [`PrivateKey`]
+    /// Loads a 64 byte ML-KEM-1024 private key seed `d || z` into a [`PrivateKey`]. The key is
+    /// expanded lazily, see [`expand`](PrivateKey::expand).
     ///
     /// # Inputs
-    /// - `bytes`: Buffer for the private key to be extracted from
+    /// - `seed`: The 64 byte private key seed
     ///
     /// # Outputs
     /// - [`PrivateKey`] object
@@ -449,62 +1008,66 @@ impl PrivateKey {
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (pk, new_sk) = generate_keypair_768(None).unwrap();
-    /// # #[cfg(feature = "decap_key")]
-    /// # {
-    /// #     let mut sk_bytes = [0u8; 2400];
-    /// #     new_sk.pack(&mut sk_bytes)?;
-    /// # }
-    /// # #[cfg(not(feature = "decap_key"))]
-    /// # let sk_bytes = new_sk.pack();
-    ///
-    /// # #[cfg(not(feature = "decap_key"))]
-    /// let sk = PrivateKey::unpack_1024(sk_bytes);
+    /// # let (pk, new_sk) = generate_keypair_1024_with_default_rng().unwrap();
+    /// # let seed = new_sk.to_seed().unwrap();
+    /// let sk = PrivateKey::from_seed_1024(seed);
     ///
-    /// # Ok::<(), enc_rust::errors::PackingError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
     #[must_use]
-    #[cfg(not(feature = "decap_key"))]
-    pub const fn unpack_1024(bytes: [u8; 2 * SYMBYTES]) -> Self {
+    pub const fn from_seed_1024(seed: [u8; 2 * SYMBYTES]) -> Self {
+        Self::from_seed(seed, SecurityLevel::new(K::Four))
+    }
+
+    pub(crate) const fn from_seed(seed: [u8; 2 * SYMBYTES], sec_level: SecurityLevel) -> Self {
         Self {
-            key: PrivateSeed { seed: bytes },
-            sec_level: SecurityLevel::new(K::Four),
+            seed: Some(PrivateSeed {
+                seed,
+                derivation: KeyDerivation::Fips203,
+            }),
+            expanded: None,
+            sec_level,
         }
     }
 
-    /// Unpacks a buffer of bytes into a [`PrivateKey`]
+    /// Loads an expanded FIPS 203 decapsulation key into a [`PrivateKey`]. The security level is
+    /// determined by the length of `bytes`. The seed cannot be recovered from the expanded key, so
+    /// [`to_seed`](PrivateKey::to_seed) of the returned key is `None`.
     ///
     /// # Inputs
-    /// - `bytes`: Buffer for the private key to be extracted from
+    /// - `bytes`: Buffer for the private key to be extracted from, of length 1632, 2400, or 3168
     ///
     /// # Outputs
     /// - [`PrivateKey`] object
     ///
     /// # Errors
-    /// Will return a [`PackingError`] if the buffer is of the wrong length
+    /// Will return an [`Error`] if:
+    /// - The buffer is of the wrong length
+    /// - The embedded public key fails the FIPS 203 modulus check
+    /// - The embedded public key hash does not match the embedded public key
+    ///
+    /// These are the input checks from FIPS 203 section 7.3. The optional pairwise consistency
+    /// check can be run afterwards with [`check_pairwise_consistency`](PrivateKey::check_pairwise_consistency).
     ///
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (pk, new_sk) = generate_keypair_768(None).unwrap();
-    /// # #[cfg(feature = "decap_key")]
-    /// # {
-    /// #     let mut sk_bytes = [0u8; 2400];
-    /// #     new_sk.pack(&mut sk_bytes)?;
-    ///
-    ///       let sk = PrivateKey::unpack(&sk_bytes)?;
-    /// # }
-    ///
-    /// # Ok::<(), enc_rust::errors::PackingError>(())
-    /// ```
-    #[cfg(feature = "decap_key")]
-    pub fn unpack(bytes: &[u8]) -> Result<Self, PackingError> {
-        let sec_level = match bytes.len() {
-            1632 => SecurityLevel::new(K::Two),
-            2400 => SecurityLevel::new(K::Three),
-            3168 => SecurityLevel::new(K::Four),
-            _ => return Err(CrystalsError::IncorrectBufferLength(bytes.len(), 3168).into()),
-        };
+    /// # let (pk, new_sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// # let mut sk_bytes = [0u8; 2400];
+    /// # new_sk.to_expanded_bytes(&mut sk_bytes)?;
+    /// let sk = PrivateKey::from_expanded_bytes(&sk_bytes)?;
+    /// assert_eq!(sk.to_seed(), None);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn from_expanded_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let sec_level = [K::Two, K::Three, K::Four]
+            .into_iter()
+            .map(SecurityLevel::new)
+            .find(|sec_level| sec_level.private_key_bytes() == bytes.len())
+            .ok_or(Error::UnsupportedLength {
+                actual: bytes.len(),
+            })?;
         let (sk_bytes, rest) = bytes.split_at(sec_level.indcpa_private_key_bytes());
         let (pk_bytes, rest) = rest.split_at(sec_level.indcpa_public_key_bytes());
         let (h_pk_bytes, z_bytes) = rest.split_at(SYMBYTES);
@@ -513,83 +1076,118 @@ impl PrivateKey {
         let pk = IndcpaPublicKey::unpack(pk_bytes)?;
         let mut h_pk = [0u8; SYMBYTES];
         h_pk.copy_from_slice(h_pk_bytes);
+        if !bool::from(sha3_256_from(pk_bytes).ct_eq(&h_pk)) {
+            return Err(Error::ValidationFailure(Validation::PrivateKeyHash));
+        }
         let mut z = [0u8; SYMBYTES];
         z.copy_from_slice(z_bytes);
 
         Ok(Self {
-            key: PrivateKeyInner { sk, pk, h_pk, z },
+            seed: None,
+            expanded: Some(PrivateKeyInner { sk, pk, h_pk, z }),
             sec_level,
         })
     }
 
-    /// Decapsulates a ciphertext (given as a byte slice) into the shared secret
+    /// Checks that the private key decapsulates ciphertexts encapsulated to its own public key.
+    ///
+    /// This is the optional pairwise consistency check from FIPS 203 section 7.3, and is useful
+    /// when importing private keys from an untrusted source, as [`from_expanded_bytes`](PrivateKey::from_expanded_bytes)
+    /// does not check that the secret and public parts of the key correspond.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The decapsulated shared secret does not match the encapsulated one
+    /// - RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// let (_, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// sk.check_pairwise_consistency()?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn check_pairwise_consistency(&self) -> Result<(), Error> {
+        let (ciphertext, shared_secret) = self.get_public_key().encapsulate_with_default_rng()?;
+        let decap_secret = self.decapsulate(&ciphertext)?;
+
+        if bool::from(shared_secret.ct_eq(&decap_secret)) {
+            Ok(())
+        } else {
+            Err(Error::ValidationFailure(Validation::PairwiseConsistency))
+        }
+    }
+
+    /// Decapsulates a ciphertext into the shared secret
     ///
     /// # Inputs
-    /// - `ciphertext`: Byte slice containing the ciphertext to be decapsulated
+    /// - `ciphertext`: The [`Ciphertext`] to be decapsulated
     ///
     /// # Outputs
-    /// - `[u8; 32]`: The shared secret, a 32 byte array
+    /// - [`SharedSecret`] object
     ///
     /// # Errors
-    /// Will return an [`EncryptionDecryptionError`] if:
-    /// - Given invalid ciphertext length
+    /// Will return an [`Error`] if:
+    /// - The ciphertext is for a different security level to the private key
     ///
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (pk, sk) = generate_keypair_768(None).unwrap();
-    /// # let (ciphertext_obj, secret) = pk.encapsulate(None, None).unwrap();
-    /// # let ciphertext = ciphertext_obj.as_bytes();
-    /// let shared_secret = sk.decapsulate(ciphertext)?;
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// # let (ciphertext, secret) = pk.encapsulate_with_default_rng().unwrap();
+    /// let shared_secret = sk.decapsulate(&ciphertext)?;
     ///
-    /// # Ok::<(), enc_rust::errors::EncryptionDecryptionError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
-    pub fn decapsulate(
-        &self,
-        ciphertext: &[u8],
-    ) -> Result<[u8; SHAREDSECRETBYTES], EncryptionDecryptionError> {
-        let valid_bytes = [
-            SecurityLevel::new(K::Two).ciphertext_bytes(),
-            SecurityLevel::new(K::Three).ciphertext_bytes(),
-            SecurityLevel::new(K::Four).ciphertext_bytes(),
-        ];
-
-        let sec_level = match ciphertext.len() {
-            len if len == valid_bytes[0] => {
-                Ok::<SecurityLevel, CrystalsError>(SecurityLevel::new(K::Two))
-            }
-            len if len == valid_bytes[1] => {
-                Ok::<SecurityLevel, CrystalsError>(SecurityLevel::new(K::Three))
-            }
-            len if len == valid_bytes[2] => {
-                Ok::<SecurityLevel, CrystalsError>(SecurityLevel::new(K::Four))
-            }
-            _ => Err(CrystalsError::InvalidCiphertextLength(ciphertext.len())),
-        }?;
+    pub fn decapsulate(&self, ciphertext: &Ciphertext) -> Result<SharedSecret, Error> {
+        let sec_level = ciphertext.sec_level();
+        if sec_level != self.sec_level {
+            return Err(Error::parameter_mismatch(self.sec_level, sec_level));
+        }
 
-        #[cfg(not(feature = "decap_key"))]
-        let (_, inner) = new_key_from_seed(self.key.seed, sec_level)?;
-        #[cfg(feature = "decap_key")]
-        let inner = &self.key;
+        self.decapsulate_bytes(ciphertext.as_bytes())
+    }
 
-        let m = inner.sk.decrypt(ciphertext)?;
+    /// Decapsulates a ciphertext encoding already known to be
+    /// `self.sec_level.ciphertext_bytes()` long, without copying it into a [`Ciphertext`]
+    pub(crate) fn decapsulate_bytes(&self, ciphertext: &[u8]) -> Result<SharedSecret, Error> {
+        let sec_level = self.sec_level;
 
-        let (k, r) = sha3_512_from(&[m, inner.h_pk].concat());
+        let derived;
+        let inner = if let Some(inner) = &self.expanded {
+            inner
+        } else {
+            derived = self.derive_expanded()?;
+            &derived
+        };
 
-        let k_bar = shake256_from(&[&inner.z, ciphertext].concat());
+        let mut m = inner.sk.decrypt(ciphertext)?;
+
+        let (mut k, mut r) = sha3_512_from(&[&m, &inner.h_pk]);
+
+        let mut k_bar = shake256_from(&[&inner.z, ciphertext]);
 
         let mut ct = [0u8; MAX_CIPHERTEXT]; // max indcpa_bytes()
-        inner
+        let encrypted = inner
             .pk
-            .encrypt(&m, &r, &mut ct[..sec_level.indcpa_bytes()])?;
+            .encrypt(&m, &r, &mut ct[..sec_level.indcpa_bytes()]);
+
+        let equal = ct[..sec_level.indcpa_bytes()].ct_eq(ciphertext);
 
-        let equal = ct.ct_eq(ciphertext);
+        // Implicit rejection: K' if the re-encryption matches, otherwise K_bar.
+        let mut shared_secret = SharedSecret([0u8; SHAREDSECRETBYTES]);
+        for ((secret, x), y) in shared_secret.0.iter_mut().zip(k_bar.iter()).zip(k.iter()) {
+            *secret = u8::conditional_select(x, y, equal);
+        }
+
+        m.zeroize();
+        k.zeroize();
+        r.zeroize();
+        k_bar.zeroize();
+        encrypted?;
 
-        Ok(k.iter()
-            .zip(k_bar.iter())
-            .map(|(x, y)| u8::conditional_select(x, y, equal))
-            .collect::<ArrayVec<[u8; SHAREDSECRETBYTES]>>()
-            .into_inner())
+        Ok(shared_secret)
     }
 }
 
@@ -611,24 +1209,23 @@ impl PublicKey {
     /// | 1024           | 1568   |
     ///
     /// # Errors
-    /// Will return a [`PackingError`] if the buffer is of the wrong length
+    /// Will return an [`Error`] if the buffer is of the wrong length
     ///
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (pk, sk) = generate_keypair_768(None).unwrap();
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
     /// let mut pk_bytes = [0u8; 1184];
     /// pk.pack(&mut pk_bytes)?;
     ///
-    /// # Ok::<(), enc_rust::errors::PackingError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
-    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), PackingError> {
+    pub fn pack(&self, bytes: &mut [u8]) -> Result<(), Error> {
         if bytes.len() != self.sec_level().public_key_bytes() {
-            return Err(CrystalsError::IncorrectBufferLength(
-                bytes.len(),
-                self.sec_level().public_key_bytes(),
-            )
-            .into());
+            return Err(Error::InvalidLength {
+                expected: self.sec_level().public_key_bytes(),
+                actual: bytes.len(),
+            });
         }
 
         self.pk.pack(bytes)?;
@@ -636,6 +1233,26 @@ impl PublicKey {
         Ok(())
     }
 
+    /// Packs [`PublicKey`] into an owned buffer
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let pk_bytes = pk.to_vec();
+    /// assert_eq!(pk_bytes.len(), 1184);
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; self.sec_level().public_key_bytes()];
+        // cannot fail, as the buffer is always the correct length
+        self.pack(&mut bytes).unwrap();
+
+        bytes
+    }
+
     /// Unpacks a buffer of bytes into a [`PublicKey`]
     ///
     /// # Inputs
@@ -645,84 +1262,145 @@ impl PublicKey {
     /// - [`PublicKey`] object
     ///
     /// # Errors
-    /// Will return a [`PackingError`] if the buffer is of the wrong length
+    /// Will return an [`Error`] if:
+    /// - The buffer is not 800, 1184, or 1568 bytes long
+    /// - The key fails the FIPS 203 modulus check, i.e. a coefficient is not less than q
     ///
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (new_pk, sk) = generate_keypair_768(None).unwrap();
+    /// # let (new_pk, sk) = generate_keypair_768_with_default_rng().unwrap();
     /// # let mut pk_bytes = [0u8; 1184];
     /// # new_pk.pack(&mut pk_bytes)?;
     /// let pk = PublicKey::unpack(&pk_bytes)?;
     ///
-    /// # Ok::<(), enc_rust::errors::PackingError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
-    pub fn unpack(bytes: &[u8]) -> Result<Self, PackingError> {
+    pub fn unpack(bytes: &[u8]) -> Result<Self, Error> {
+        if ![K::Two, K::Three, K::Four]
+            .into_iter()
+            .any(|k| bytes.len() == SecurityLevel::new(k).public_key_bytes())
+        {
+            return Err(Error::UnsupportedLength {
+                actual: bytes.len(),
+            });
+        }
         let pk = IndcpaPublicKey::unpack(bytes)?;
         let h_pk = sha3_256_from(bytes);
 
         Ok(Self { pk, h_pk })
     }
 
-    /// Encapsulates a generated shared secret into a ciphertext to be shared
+    /// Encapsulates a generated shared secret into a ciphertext to be shared, drawing the
+    /// randomness from the given RNG
     ///
     /// # Inputs
-    /// - `seed`: (Optional) a 64 byte slice used as a seed for randomness
-    /// - `rng`: (Optional) RNG to be used during encapsulation. Must satisfy the
-    ///   [`RngCore`](https://docs.rs/rand_core/latest/rand_core/trait.RngCore.html) and
-    ///   [`CryptoRng`](https://docs.rs/rand_core/latest/rand_core/trait.CryptoRng.html) traits.
-    ///   If RNG is not present, then
-    ///   [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
-    ///   will be used.
+    /// - `rng`: RNG to be used during encapsulation. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
     ///
     /// # Outputs
     /// - [`Ciphertext`] object
-    /// - `[u8; 32]`: The shared secret, a 32 byte array
+    /// - [`SharedSecret`] object
     ///
     /// # Errors
-    /// Will return an [`EncryptionDecryptionError`] if:
-    /// - Given invalid seed length
+    /// Will return an [`Error`] if:
     /// - RNG fails
     ///
     /// # Example
     /// ```
     /// # use enc_rust::kem::*;
-    /// # let (pk, sk) = generate_keypair_768(None).unwrap();
-    /// let (ciphertext_obj, shared_secret) = pk.encapsulate(None, None)?;
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (ciphertext_obj, shared_secret) = pk.encapsulate(&mut rng)?;
     ///
-    /// # Ok::<(), enc_rust::errors::EncryptionDecryptionError>(())
+    /// # Ok::<(), enc_rust::errors::Error>(())
     /// ```
-    pub fn encapsulate(
+    pub fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
         &self,
-        seed: Option<&[u8]>,
-        rng: Option<&mut dyn AcceptableRng>,
-    ) -> Result<(Ciphertext, [u8; SHAREDSECRETBYTES]), EncryptionDecryptionError> {
-        let sec_level = self.pk.sec_level();
-
+        rng: &mut R,
+    ) -> Result<(Ciphertext, SharedSecret), Error> {
         let mut m = [0u8; SYMBYTES];
-        if let Some(seed) = seed {
-            if seed.len() != SYMBYTES {
-                return Err(CrystalsError::InvalidSeedLength(seed.len(), SYMBYTES).into());
-            }
-            m.copy_from_slice(seed);
-        } else if let Some(rng) = rng {
-            rng.try_fill_bytes(&mut m)?;
-        } else {
-            let mut chacha = ChaCha20Rng::from_entropy();
-            chacha.try_fill_bytes(&mut m)?;
-        }
+        rng.try_fill_bytes(&mut m)?;
+
+        let result = self.encapsulate_deterministic(&m);
+        m.zeroize();
+
+        result
+    }
+
+    /// Encapsulates a generated shared secret into a ciphertext to be shared, drawing the
+    /// randomness from [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source
+    ///
+    /// # Outputs
+    /// - [`Ciphertext`] object
+    /// - [`SharedSecret`] object
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let (ciphertext_obj, shared_secret) = pk.encapsulate_with_default_rng()?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate_with_default_rng(&self) -> Result<(Ciphertext, SharedSecret), Error> {
+        let mut chacha = ChaCha20Rng::from_entropy();
 
-        let (k, r) = sha3_512_from(&[m, self.h_pk].concat());
+        self.encapsulate(&mut chacha)
+    }
+
+    /// Deterministically encapsulates the shared secret derived from the message `m`, as in
+    /// FIPS 203 `ML-KEM.Encaps_internal`.
+    ///
+    /// **This is only for testing against known answer vectors.** The security of the shared
+    /// secret relies entirely on `m` being 32 fresh bytes from an approved RNG that are never
+    /// reused, so use [`encapsulate`](PublicKey::encapsulate) or
+    /// [`encapsulate_with_default_rng`](PublicKey::encapsulate_with_default_rng) instead.
+    ///
+    /// # Inputs
+    /// - `m`: The 32 byte message to encapsulate
+    ///
+    /// # Outputs
+    /// - [`Ciphertext`] object
+    /// - [`SharedSecret`] object
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if encryption fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::*;
+    /// # let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+    /// let (ciphertext_obj, shared_secret) = pk.encapsulate_deterministic(&[0u8; 32])?;
+    /// assert_eq!(pk.encapsulate_deterministic(&[0u8; 32])?.1, shared_secret);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate_deterministic(
+        &self,
+        m: &[u8; SYMBYTES],
+    ) -> Result<(Ciphertext, SharedSecret), Error> {
+        let sec_level = self.pk.sec_level();
+
+        let (mut k, mut r) = sha3_512_from(&[m, &self.h_pk]);
         let mut bytes = [0u8; MAX_CIPHERTEXT]; // max ciphertext_bytes
-        self.pk
-            .encrypt(&m, &r, &mut bytes[..sec_level.ciphertext_bytes()])?;
-
-        Ok((
-            Ciphertext {
-                bytes,
-                len: sec_level.ciphertext_bytes(),
-            },
-            k,
-        ))
+        let encrypted = self
+            .pk
+            .encrypt(m, &r, &mut bytes[..sec_level.ciphertext_bytes()]);
+        let shared_secret = SharedSecret(k);
+
+        k.zeroize();
+        r.zeroize();
+        encrypted?;
+
+        Ok((Ciphertext { bytes, sec_level }, shared_secret))
     }
 }
@@ -0,0 +1,249 @@
+use super::{PrivateKey, PublicKey};
+use crate::{
+    errors::Error,
+    params::{SecurityLevel, K, SYMBYTES},
+};
+use ::pkcs8::{
+    der::{
+        asn1::{ContextSpecific, OctetStringRef},
+        Decode, Reader, SliceReader, Tag, TagNumber,
+    },
+    spki::{self, AlgorithmIdentifierRef, SubjectPublicKeyInfoRef},
+    KeyError, ObjectIdentifier, PrivateKeyInfoRef,
+};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+#[cfg(feature = "alloc")]
+use {
+    super::{KeyDerivation, PrivateSeed},
+    ::pkcs8::{
+        der::{asn1::BitStringRef, Document, Encode, SecretDocument, TagMode},
+        EncodePrivateKey, EncodePublicKey,
+    },
+    zeroize::Zeroizing,
+};
+
+// IETF LAMPS `id-alg-ml-kem-512/768/1024`, under NIST's `sigAlgs` arc 2.16.840.1.101.3.4.4
+const ID_ALG_ML_KEM_512: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.1");
+const ID_ALG_ML_KEM_768: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.2");
+const ID_ALG_ML_KEM_1024: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.3");
+
+// `seed [0] IMPLICIT OCTET STRING` in the `ML-KEM-PrivateKey` CHOICE
+const SEED_TAG_NUMBER: TagNumber = TagNumber(0);
+
+const MAX_PRIVATE_KEY_BYTES: usize = SecurityLevel::new(K::Four).private_key_bytes();
+
+// Largest `expandedKey` encoding, a 3168 byte OCTET STRING with a 4 byte header
+#[cfg(feature = "alloc")]
+const MAX_PRIVATE_KEY_DER: usize = 4 + SecurityLevel::new(K::Four).private_key_bytes();
+
+#[cfg(feature = "alloc")]
+const fn oid(sec_level: SecurityLevel) -> ObjectIdentifier {
+    match sec_level {
+        SecurityLevel::FiveOneTwo { .. } => ID_ALG_ML_KEM_512,
+        SecurityLevel::SevenSixEight { .. } => ID_ALG_ML_KEM_768,
+        SecurityLevel::TenTwoFour { .. } => ID_ALG_ML_KEM_1024,
+    }
+}
+
+// The parameters field must be absent for the ML-KEM algorithm identifiers
+const fn sec_level(algorithm: &AlgorithmIdentifierRef<'_>) -> spki::Result<SecurityLevel> {
+    let k = match algorithm.oid {
+        ID_ALG_ML_KEM_512 => K::Two,
+        ID_ALG_ML_KEM_768 => K::Three,
+        ID_ALG_ML_KEM_1024 => K::Four,
+        oid => return Err(spki::Error::OidUnknown { oid }),
+    };
+    if algorithm.parameters.is_some() {
+        return Err(spki::Error::KeyMalformed);
+    }
+
+    Ok(SecurityLevel::new(k))
+}
+
+const fn key_error(err: Error) -> KeyError {
+    match err {
+        Error::InvalidLength { expected, actual } if actual < expected => KeyError::TooShort,
+        Error::InvalidLength { .. } => KeyError::TooLong,
+        _ => KeyError::Invalid,
+    }
+}
+
+impl TryFrom<SubjectPublicKeyInfoRef<'_>> for PublicKey {
+    type Error = spki::Error;
+
+    fn try_from(spki: SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+        let sec_level = sec_level(&spki.algorithm)?;
+        let bytes = spki
+            .subject_public_key
+            .as_bytes()
+            .ok_or(spki::Error::KeyMalformed)?;
+        if bytes.len() != sec_level.public_key_bytes() {
+            return Err(spki::Error::KeyMalformed);
+        }
+
+        Self::unpack(bytes).map_err(|_| spki::Error::KeyMalformed)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl EncodePublicKey for PublicKey {
+    fn to_public_key_der(&self) -> spki::Result<Document> {
+        let sec_level = self.sec_level();
+        let mut bytes = [0u8; SecurityLevel::new(K::Four).public_key_bytes()];
+        let bytes = &mut bytes[..sec_level.public_key_bytes()];
+        self.pack(bytes).map_err(|_| spki::Error::KeyMalformed)?;
+
+        SubjectPublicKeyInfoRef {
+            algorithm: AlgorithmIdentifierRef {
+                oid: oid(sec_level),
+                parameters: None,
+            },
+            subject_public_key: BitStringRef::new(0, bytes)?,
+        }
+        .try_into()
+    }
+}
+
+// ML-KEM-PrivateKey ::= CHOICE {
+//     seed [0] IMPLICIT OCTET STRING (SIZE (64)),
+//     expandedKey OCTET STRING (SIZE (1632 | 2400 | 3168)),
+//     both SEQUENCE {
+//         seed OCTET STRING (SIZE (64)),
+//         expandedKey OCTET STRING (SIZE (1632 | 2400 | 3168)) } }
+fn decode_private_key(bytes: &[u8], sec_level: SecurityLevel) -> ::pkcs8::Result<PrivateKey> {
+    let mut reader = SliceReader::new(bytes)?;
+    let sk = match Tag::peek(&reader)? {
+        Tag::OctetString => {
+            let expanded = <&OctetStringRef>::decode(&mut reader)?;
+            expanded_private_key(expanded.as_bytes(), sec_level)?
+        }
+        Tag::Sequence => reader.sequence(|reader| {
+            let seed = seed(<&OctetStringRef>::decode(reader)?)?;
+            let expanded = <&OctetStringRef>::decode(reader)?.as_bytes();
+            check_expanded_length(expanded, sec_level)?;
+            let mut sk = PrivateKey::from_seed(seed, sec_level);
+            sk.expanded = Some(sk.derive_expanded().map_err(key_error)?);
+
+            // The seed must expand to expandedKey, compared in constant time
+            let mut derived = [0u8; MAX_PRIVATE_KEY_BYTES];
+            sk.to_expanded_bytes(&mut derived[..expanded.len()])
+                .map_err(key_error)?;
+            let matches = derived[..expanded.len()].ct_eq(expanded);
+            derived.zeroize();
+            if !bool::from(matches) {
+                return Err(::pkcs8::Error::KeyMalformed(KeyError::Invalid));
+            }
+
+            Ok(sk)
+        })?,
+        _ => {
+            let seed =
+                ContextSpecific::<&OctetStringRef>::decode_implicit(&mut reader, SEED_TAG_NUMBER)?
+                    .ok_or(KeyError::Invalid)?;
+            PrivateKey::from_seed(self::seed(seed.value)?, sec_level)
+        }
+    };
+    reader.finish()?;
+
+    Ok(sk)
+}
+
+fn seed(seed: &OctetStringRef) -> Result<[u8; 2 * SYMBYTES], KeyError> {
+    seed.as_bytes().try_into().map_err(|_| {
+        if seed.as_bytes().len() < 2 * SYMBYTES {
+            KeyError::TooShort
+        } else {
+            KeyError::TooLong
+        }
+    })
+}
+
+const fn check_expanded_length(bytes: &[u8], sec_level: SecurityLevel) -> Result<(), KeyError> {
+    if bytes.len() == sec_level.private_key_bytes() {
+        Ok(())
+    } else {
+        Err(key_error(Error::InvalidLength {
+            expected: sec_level.private_key_bytes(),
+            actual: bytes.len(),
+        }))
+    }
+}
+
+fn expanded_private_key(bytes: &[u8], sec_level: SecurityLevel) -> Result<PrivateKey, KeyError> {
+    check_expanded_length(bytes, sec_level)?;
+
+    PrivateKey::from_expanded_bytes(bytes).map_err(key_error)
+}
+
+impl TryFrom<PrivateKeyInfoRef<'_>> for PrivateKey {
+    type Error = ::pkcs8::Error;
+
+    fn try_from(private_key_info: PrivateKeyInfoRef<'_>) -> ::pkcs8::Result<Self> {
+        let sec_level = sec_level(&private_key_info.algorithm)?;
+        let sk = decode_private_key(private_key_info.private_key.as_bytes(), sec_level)?;
+
+        // The optional public key of a v2 `OneAsymmetricKey` must match the private key
+        if let Some(public_key) = private_key_info.public_key {
+            let pk = PublicKey::try_from(SubjectPublicKeyInfoRef {
+                algorithm: private_key_info.algorithm,
+                subject_public_key: public_key,
+            })?;
+            if pk != sk.get_public_key() {
+                return Err(::pkcs8::Error::KeyMalformed(KeyError::Invalid));
+            }
+        }
+
+        Ok(sk)
+    }
+}
+
+/// Private keys holding a FIPS 203 seed are encoded in the `seed` form, and otherwise in the
+/// `expandedKey` form. All three forms are accepted when decoding, and when both a seed and an
+/// expanded key are given they must agree.
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::*;
+/// let (pk, sk) = generate_keypair_768_with_default_rng()?;
+///
+/// let pk_der = pk.to_public_key_der()?;
+/// let sk_der = sk.to_pkcs8_der()?;
+///
+/// let pk = PublicKey::from_public_key_der(pk_der.as_bytes())?;
+/// let sk = PrivateKey::from_pkcs8_der(sk_der.as_bytes())?;
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "alloc")]
+impl EncodePrivateKey for PrivateKey {
+    fn to_pkcs8_der(&self) -> ::pkcs8::Result<SecretDocument> {
+        let sec_level = self.sec_level;
+        let mut der = Zeroizing::new([0u8; MAX_PRIVATE_KEY_DER]);
+        let private_key = if let Some(PrivateSeed {
+            seed,
+            derivation: KeyDerivation::Fips203,
+        }) = &self.seed
+        {
+            ContextSpecific {
+                tag_number: SEED_TAG_NUMBER,
+                tag_mode: TagMode::Implicit,
+                value: OctetStringRef::new(seed)?,
+            }
+            .encode_to_slice(der.as_mut())?
+        } else {
+            let mut expanded = Zeroizing::new([0u8; MAX_PRIVATE_KEY_DER]);
+            let expanded = &mut expanded[..sec_level.private_key_bytes()];
+            self.to_expanded_bytes(expanded).map_err(key_error)?;
+            OctetStringRef::new(expanded)?.encode_to_slice(der.as_mut())?
+        };
+
+        let algorithm = AlgorithmIdentifierRef {
+            oid: oid(sec_level),
+            parameters: None,
+        };
+        let private_key_info = PrivateKeyInfoRef::new(algorithm, OctetStringRef::new(private_key)?);
+
+        SecretDocument::encode_msg(&private_key_info).map_err(::pkcs8::Error::Asn1)
+    }
+}
@@ -0,0 +1,238 @@
+use super::{typed, Ciphertext, PrivateKey, PublicKey};
+use crate::params::{SecurityLevel, K};
+use base64ct::{Base64, Encoding};
+use core::{borrow::Borrow, fmt::Formatter};
+use serde::{
+    de::{Error as _, SeqAccess, Visitor},
+    ser::Error as _,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use zeroize::Zeroizing;
+
+// Largest encoding of any key or ciphertext, the expanded ML-KEM-1024 private key
+const MAX_BYTES: usize = SecurityLevel::new(K::Four).private_key_bytes();
+const MAX_BASE64: usize = MAX_BYTES.div_ceil(3) * 4;
+
+// Base64 for human readable formats, raw bytes otherwise
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        let mut buf = Zeroizing::new([0u8; MAX_BASE64]);
+        let encoded = Base64::encode(bytes, buf.as_mut()).map_err(S::Error::custom)?;
+        serializer.serialize_str(encoded)
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+// Reads the encoding into `buf`, returning the number of bytes read
+fn deserialize_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    buf: &mut [u8],
+    expecting: &'static str,
+) -> Result<usize, D::Error> {
+    let visitor = BytesVisitor { buf, expecting };
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(visitor)
+    } else {
+        deserializer.deserialize_bytes(visitor)
+    }
+}
+
+struct BytesVisitor<'a> {
+    buf: &'a mut [u8],
+    expecting: &'static str,
+}
+
+impl<'de> Visitor<'de> for BytesVisitor<'_> {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    // The input is not included in errors, as it may be a private key
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Base64::decode(v, self.buf)
+            .map(<[u8]>::len)
+            .map_err(E::custom)
+    }
+
+    fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let buf = self
+            .buf
+            .get_mut(..v.len())
+            .ok_or_else(|| E::invalid_length(v.len(), &self.expecting))?;
+        buf.copy_from_slice(v);
+
+        Ok(v.len())
+    }
+
+    // Binary formats without a native byte string type give a sequence of bytes instead
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut len = 0;
+        while let Some(byte) = seq.next_element::<u8>()? {
+            *self
+                .buf
+                .get_mut(len)
+                .ok_or_else(|| A::Error::invalid_length(len + 1, &self.expecting))? = byte;
+            len += 1;
+        }
+
+        Ok(len)
+    }
+}
+
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = [0u8; MAX_BYTES];
+        let bytes = &mut bytes[..self.sec_level().public_key_bytes()];
+        self.pack(bytes).map_err(S::Error::custom)?;
+
+        serialize_bytes(bytes, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut bytes = [0u8; MAX_BYTES];
+        let len = deserialize_bytes(deserializer, &mut bytes, "an ML-KEM public key")?;
+
+        Self::unpack(&bytes[..len]).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for Ciphertext {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_bytes(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ciphertext {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut bytes = [0u8; MAX_BYTES];
+        let len = deserialize_bytes(deserializer, &mut bytes, "an ML-KEM ciphertext")?;
+
+        Self::from_bytes(&bytes[..len]).map_err(D::Error::custom)
+    }
+}
+
+// Private keys are only serialised through `serde_private_key`
+impl<'de> Deserialize<'de> for PrivateKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut bytes = Zeroizing::new([0u8; MAX_BYTES]);
+        let len = deserialize_bytes(
+            deserializer,
+            bytes.as_mut(),
+            "an expanded ML-KEM private key",
+        )?;
+
+        Self::from_expanded_bytes(&bytes[..len]).map_err(D::Error::custom)
+    }
+}
+
+impl<P: typed::ParameterSet> Serialize for typed::PublicKey<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.to_bytes().as_ref(), serializer)
+    }
+}
+
+impl<'de, P: typed::ParameterSet> Deserialize<'de> for typed::PublicKey<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::try_from(PublicKey::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<P: typed::ParameterSet> Serialize for typed::Ciphertext<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_ref(), serializer)
+    }
+}
+
+impl<'de, P: typed::ParameterSet> Deserialize<'de> for typed::Ciphertext<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::try_from(Ciphertext::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl<'de, P: typed::ParameterSet> Deserialize<'de> for typed::PrivateKey<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::try_from(PrivateKey::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Opt-in serialisation of private keys, for use with `#[serde(with = "...")]`.
+///
+/// [`PrivateKey`] and [`typed::PrivateKey`] implement [`Deserialize`], but not [`Serialize`], so
+/// that private keys cannot be written out by accident, such as in a struct that is also logged.
+/// Fields holding a private key must instead be marked with this module, making the intent to
+/// serialise the key explicit.
+///
+/// Private keys are serialised as the expanded FIPS 203 decapsulation key, as the seed alone
+/// does not identify the parameter set. Human readable formats use Base64, while binary formats
+/// use raw bytes. On deserialisation the key is checked as in
+/// [`from_expanded_bytes`](PrivateKey::from_expanded_bytes).
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Identity {
+///     pk: PublicKey,
+///     #[serde(with = "enc_rust::kem::serde_private_key")]
+///     sk: PrivateKey,
+/// }
+///
+/// let (pk, sk) = generate_keypair_768_with_default_rng()?;
+/// let json = serde_json::to_string(&Identity { pk, sk })?;
+/// let identity: Identity = serde_json::from_str(&json)?;
+///
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Without the attribute, deriving [`Serialize`] fails to compile:
+/// ```compile_fail
+/// # use enc_rust::kem::*;
+/// #[derive(serde::Serialize)]
+/// struct Identity {
+///     sk: PrivateKey,
+/// }
+/// ```
+pub mod serde_private_key {
+    use super::{serialize_bytes, Borrow, Deserialize, Deserializer, PrivateKey, Serializer};
+    use serde::ser::Error as _;
+    use zeroize::Zeroizing;
+
+    /// Serialises a [`PrivateKey`] or [`typed::PrivateKey`](super::typed::PrivateKey) as its
+    /// expanded encoding
+    ///
+    /// # Errors
+    /// Will return an error if the serializer fails
+    pub fn serialize<T, S>(sk: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Borrow<PrivateKey>,
+        S: Serializer,
+    {
+        let sk = sk.borrow();
+        let mut bytes = Zeroizing::new([0u8; super::MAX_BYTES]);
+        let bytes = &mut bytes[..sk.sec_level().private_key_bytes()];
+        sk.to_expanded_bytes(bytes).map_err(S::Error::custom)?;
+
+        serialize_bytes(bytes, serializer)
+    }
+
+    /// Deserialises a [`PrivateKey`] or [`typed::PrivateKey`](super::typed::PrivateKey) from its
+    /// expanded encoding, equivalent to [`Deserialize::deserialize`]
+    ///
+    /// # Errors
+    /// Will return an error if the encoding is invalid, or the key fails the FIPS 203
+    /// decapsulation key checks
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
@@ -0,0 +1,49 @@
+//! Implementations of the `RustCrypto` [`kem`](https://docs.rs/kem/0.3.0-pre.0) traits, so that
+//! ML-KEM keys can be used in code generic over KEMs.
+
+use super::{typed, Ciphertext, PrivateKey, PublicKey, SharedSecret};
+use crate::errors::Error;
+use ::kem::{Decapsulate, Encapsulate};
+use rand_core::CryptoRngCore;
+
+impl Encapsulate<Ciphertext, SharedSecret> for PublicKey {
+    type Error = Error;
+
+    fn encapsulate(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(Ciphertext, SharedSecret), Self::Error> {
+        Self::encapsulate(self, rng)
+    }
+}
+
+impl Decapsulate<Ciphertext, SharedSecret> for PrivateKey {
+    type Error = Error;
+
+    fn decapsulate(&self, ciphertext: &Ciphertext) -> Result<SharedSecret, Self::Error> {
+        Self::decapsulate(self, ciphertext)
+    }
+}
+
+impl<P: typed::ParameterSet> Encapsulate<typed::Ciphertext<P>, SharedSecret>
+    for typed::PublicKey<P>
+{
+    type Error = Error;
+
+    fn encapsulate(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(typed::Ciphertext<P>, SharedSecret), Self::Error> {
+        Self::encapsulate(self, rng)
+    }
+}
+
+impl<P: typed::ParameterSet> Decapsulate<typed::Ciphertext<P>, SharedSecret>
+    for typed::PrivateKey<P>
+{
+    type Error = Error;
+
+    fn decapsulate(&self, ciphertext: &typed::Ciphertext<P>) -> Result<SharedSecret, Self::Error> {
+        Self::decapsulate(self, ciphertext)
+    }
+}
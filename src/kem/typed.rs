@@ -0,0 +1,587 @@
+use super::{
+    generate_key_pair, generate_key_pair_from_seed, Ciphertext as DynCiphertext,
+    PrivateKey as DynPrivateKey, PublicKey as DynPublicKey, SharedSecret,
+};
+use crate::{
+    errors::Error,
+    params::{SecurityLevel, K, MAX_CIPHERTEXT, SYMBYTES},
+};
+use core::{borrow::Borrow, fmt::Debug, marker::PhantomData};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use subtle::{Choice, ConstantTimeEq};
+
+mod private {
+    use crate::params::SecurityLevel;
+    use core::fmt::Debug;
+
+    pub trait Sealed {
+        const SEC_LEVEL: SecurityLevel;
+    }
+
+    pub trait ByteArray: Copy + Debug + Eq + AsRef<[u8]> + AsMut<[u8]> {
+        fn zeroed() -> Self;
+    }
+
+    impl<const N: usize> ByteArray for [u8; N] {
+        fn zeroed() -> Self {
+            [0u8; N]
+        }
+    }
+}
+
+use private::ByteArray;
+
+/// An ML-KEM parameter set, one of [`MlKem512`], [`MlKem768`], or [`MlKem1024`].
+///
+/// The byte lengths of keys and ciphertexts are fixed by the parameter set, so passing a key or
+/// ciphertext of one parameter set where another is expected is a compile time error.
+///
+/// # Example
+/// ```
+/// # use enc_rust::kem::typed::*;
+/// let (pk, sk) = MlKem768::generate_keypair_with_default_rng()?;
+/// let pk_bytes: [u8; 1184] = pk.to_bytes();
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub trait ParameterSet: private::Sealed + Copy + Debug + Eq {
+    /// Byte array holding a packed [`PublicKey`]
+    type PublicKeyBytes: ByteArray;
+    /// Byte array holding a packed, expanded [`PrivateKey`]
+    type PrivateKeyBytes: ByteArray;
+    /// Byte array holding a [`Ciphertext`]
+    type CiphertextBytes: ByteArray;
+
+    /// Generates a new keypair for the parameter set.
+    ///
+    /// # Inputs
+    /// - `rng`: RNG to be used when generating the keypair. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (pk, sk) = MlKem1024::generate_keypair(&mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    fn generate_keypair<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<(PublicKey<Self>, PrivateKey<Self>), Error> {
+        let (pk, sk) = generate_key_pair(rng, Self::SEC_LEVEL.k())?;
+
+        Ok((PublicKey::new(pk), PrivateKey::new(sk)))
+    }
+
+    /// Generates a new keypair for the parameter set, using
+    /// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// let (pk, sk) = MlKem1024::generate_keypair_with_default_rng()?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    fn generate_keypair_with_default_rng() -> Result<(PublicKey<Self>, PrivateKey<Self>), Error> {
+        Self::generate_keypair(&mut ChaCha20Rng::from_entropy())
+    }
+
+    /// Deterministically derives a keypair for the parameter set from the seeds `d` and `z`, as
+    /// in FIPS 203 `ML-KEM.KeyGen_internal`, see
+    /// [`kem::generate_keypair_from_seed_768`](super::generate_keypair_from_seed_768)
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if key generation fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// let (pk, sk) = MlKem768::generate_keypair_from_seed(&[1u8; 32], &[2u8; 32])?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    fn generate_keypair_from_seed(
+        d: &[u8; SYMBYTES],
+        z: &[u8; SYMBYTES],
+    ) -> Result<(PublicKey<Self>, PrivateKey<Self>), Error> {
+        let (pk, sk) = generate_key_pair_from_seed(d, z, Self::SEC_LEVEL.k())?;
+
+        Ok((PublicKey::new(pk), PrivateKey::new(sk)))
+    }
+}
+
+/// ML-KEM-512 parameter set
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MlKem512;
+
+impl private::Sealed for MlKem512 {
+    const SEC_LEVEL: SecurityLevel = SecurityLevel::new(K::Two);
+}
+
+impl ParameterSet for MlKem512 {
+    type PublicKeyBytes = [u8; SecurityLevel::new(K::Two).public_key_bytes()];
+    type PrivateKeyBytes = [u8; SecurityLevel::new(K::Two).private_key_bytes()];
+    type CiphertextBytes = [u8; SecurityLevel::new(K::Two).ciphertext_bytes()];
+}
+
+/// ML-KEM-768 parameter set
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MlKem768;
+
+impl private::Sealed for MlKem768 {
+    const SEC_LEVEL: SecurityLevel = SecurityLevel::new(K::Three);
+}
+
+impl ParameterSet for MlKem768 {
+    type PublicKeyBytes = [u8; SecurityLevel::new(K::Three).public_key_bytes()];
+    type PrivateKeyBytes = [u8; SecurityLevel::new(K::Three).private_key_bytes()];
+    type CiphertextBytes = [u8; SecurityLevel::new(K::Three).ciphertext_bytes()];
+}
+
+/// ML-KEM-1024 parameter set
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MlKem1024;
+
+impl private::Sealed for MlKem1024 {
+    const SEC_LEVEL: SecurityLevel = SecurityLevel::new(K::Four);
+}
+
+impl ParameterSet for MlKem1024 {
+    type PublicKeyBytes = [u8; SecurityLevel::new(K::Four).public_key_bytes()];
+    type PrivateKeyBytes = [u8; SecurityLevel::new(K::Four).private_key_bytes()];
+    type CiphertextBytes = [u8; SecurityLevel::new(K::Four).ciphertext_bytes()];
+}
+
+/// A [`kem::PublicKey`](super::PublicKey) that is known to belong to the parameter set `P`.
+///
+/// Can be converted to and from the runtime [`kem::PublicKey`](super::PublicKey) using [`From`]
+/// and [`TryFrom`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct PublicKey<P: ParameterSet> {
+    inner: DynPublicKey,
+    parameter_set: PhantomData<P>,
+}
+
+/// A [`kem::PrivateKey`](super::PrivateKey) that is known to belong to the parameter set `P`.
+///
+/// Can be converted to and from the runtime [`kem::PrivateKey`](super::PrivateKey) using [`From`]
+/// and [`TryFrom`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct PrivateKey<P: ParameterSet> {
+    inner: DynPrivateKey,
+    parameter_set: PhantomData<P>,
+}
+
+/// A ciphertext for the parameter set `P`, wrapping a byte array of exactly
+/// the ciphertext length of `P`.
+///
+/// Equality is checked in constant time.
+#[derive(Clone, Debug)]
+pub struct Ciphertext<P: ParameterSet> {
+    bytes: P::CiphertextBytes,
+}
+
+impl<P: ParameterSet> PublicKey<P> {
+    const fn new(inner: DynPublicKey) -> Self {
+        Self {
+            inner,
+            parameter_set: PhantomData,
+        }
+    }
+
+    /// Packs the public key into a byte array of the correct length for `P`
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// let (pk, _) = MlKem512::generate_keypair_with_default_rng()?;
+    /// let pk_bytes: [u8; 800] = pk.to_bytes();
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    pub fn to_bytes(&self) -> P::PublicKeyBytes {
+        let mut bytes = P::PublicKeyBytes::zeroed();
+        // cannot fail, as the array is always the correct length for P
+        self.inner.pack(bytes.as_mut()).unwrap();
+
+        bytes
+    }
+
+    /// Unpacks a byte array into a [`PublicKey`]
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the key fails the FIPS 203 modulus check
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// # let (new_pk, _) = MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let pk_bytes = new_pk.to_bytes();
+    /// let pk = PublicKey::<MlKem768>::from_bytes(&pk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn from_bytes(bytes: &P::PublicKeyBytes) -> Result<Self, Error> {
+        Ok(Self::new(DynPublicKey::unpack(bytes.as_ref())?))
+    }
+
+    /// Encapsulates a generated shared secret into a ciphertext for `P`, drawing the randomness
+    /// from the given RNG, see [`kem::PublicKey::encapsulate`](super::PublicKey::encapsulate)
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// # let (pk, sk) = MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let (ciphertext, shared_secret) = pk.encapsulate(&mut rng)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate<R: CryptoRng + RngCore + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Ciphertext<P>, SharedSecret), Error> {
+        Ok(Self::typed(self.inner.encapsulate(rng)?))
+    }
+
+    /// Encapsulates a generated shared secret into a ciphertext for `P`, see
+    /// [`kem::PublicKey::encapsulate_with_default_rng`](super::PublicKey::encapsulate_with_default_rng)
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// # let (pk, sk) = MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng()?;
+    /// let ciphertext_bytes: &[u8; 1088] = ciphertext.as_bytes();
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate_with_default_rng(&self) -> Result<(Ciphertext<P>, SharedSecret), Error> {
+        Ok(Self::typed(self.inner.encapsulate_with_default_rng()?))
+    }
+
+    /// Deterministically encapsulates the shared secret derived from the message `m`, as in
+    /// FIPS 203 `ML-KEM.Encaps_internal`.
+    ///
+    /// **This is only for testing against known answer vectors**, see
+    /// [`kem::PublicKey::encapsulate_deterministic`](super::PublicKey::encapsulate_deterministic)
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if encryption fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// # let (pk, sk) = MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// let (ciphertext, shared_secret) = pk.encapsulate_deterministic(&[0u8; 32])?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn encapsulate_deterministic(
+        &self,
+        m: &[u8; SYMBYTES],
+    ) -> Result<(Ciphertext<P>, SharedSecret), Error> {
+        Ok(Self::typed(self.inner.encapsulate_deterministic(m)?))
+    }
+
+    fn typed(
+        (ciphertext, shared_secret): (DynCiphertext, SharedSecret),
+    ) -> (Ciphertext<P>, SharedSecret) {
+        let mut bytes = P::CiphertextBytes::zeroed();
+        bytes.as_mut().copy_from_slice(ciphertext.as_bytes());
+
+        (Ciphertext { bytes }, shared_secret)
+    }
+}
+
+impl<P: ParameterSet> PrivateKey<P> {
+    const fn new(inner: DynPrivateKey) -> Self {
+        Self {
+            inner,
+            parameter_set: PhantomData,
+        }
+    }
+
+    /// Returns the corresponding public key for a given private key
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// let (_, sk) = MlKem768::generate_keypair_with_default_rng()?;
+    /// let pk = sk.get_public_key();
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[must_use]
+    pub fn get_public_key(&self) -> PublicKey<P> {
+        PublicKey::new(self.inner.get_public_key())
+    }
+
+    /// Returns the 64 byte private key seed, or `None` if the key was loaded from its expanded
+    /// form or uses a legacy seed derivation, see
+    /// [`kem::PrivateKey::to_seed`](super::PrivateKey::to_seed)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// let (_, sk) = MlKem768::generate_keypair_with_default_rng()?;
+    /// let seed: [u8; 64] = sk.to_seed().unwrap();
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[must_use]
+    pub const fn to_seed(&self) -> Option<[u8; 2 * SYMBYTES]> {
+        self.inner.to_seed()
+    }
+
+    /// Loads a 64 byte private key seed into a [`PrivateKey`]
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// # let (_, new_sk) = MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let seed = new_sk.to_seed().unwrap();
+    /// let sk = PrivateKey::<MlKem768>::from_seed(seed);
+    /// ```
+    #[must_use]
+    pub const fn from_seed(seed: [u8; 2 * SYMBYTES]) -> Self {
+        Self::new(DynPrivateKey::from_seed(seed, P::SEC_LEVEL))
+    }
+
+    /// Expands the private key seed, caching the expanded key for later decapsulations, see
+    /// [`kem::PrivateKey::expand`](super::PrivateKey::expand)
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if expanding the seed fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// # let (_, new_sk) = MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let seed = new_sk.to_seed().unwrap();
+    /// let mut sk = PrivateKey::<MlKem768>::from_seed(seed);
+    /// sk.expand()?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn expand(&mut self) -> Result<(), Error> {
+        self.inner.expand()
+    }
+
+    /// Packs the expanded private key into a byte array of the correct length for `P`
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// let (_, sk) = MlKem768::generate_keypair_with_default_rng()?;
+    /// let sk_bytes: [u8; 2400] = sk.to_expanded_bytes();
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    #[allow(clippy::missing_panics_doc, clippy::unwrap_used)]
+    #[must_use]
+    pub fn to_expanded_bytes(&self) -> P::PrivateKeyBytes {
+        let mut bytes = P::PrivateKeyBytes::zeroed();
+        // cannot fail, as the array is always the correct length for P
+        self.inner.to_expanded_bytes(bytes.as_mut()).unwrap();
+
+        bytes
+    }
+
+    /// Loads an expanded private key byte array into a [`PrivateKey`]
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if the key fails the FIPS 203 decapsulation key checks, see
+    /// [`kem::PrivateKey::from_expanded_bytes`](super::PrivateKey::from_expanded_bytes)
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// # let (_, new_sk) = MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let sk_bytes = new_sk.to_expanded_bytes();
+    /// let sk = PrivateKey::<MlKem768>::from_expanded_bytes(&sk_bytes)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn from_expanded_bytes(bytes: &P::PrivateKeyBytes) -> Result<Self, Error> {
+        Ok(Self::new(DynPrivateKey::from_expanded_bytes(
+            bytes.as_ref(),
+        )?))
+    }
+
+    /// Decapsulates a ciphertext for `P` into the shared secret
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if decapsulation fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// # let (pk, sk) = MlKem768::generate_keypair_with_default_rng().unwrap();
+    /// # let (ciphertext, secret) = pk.encapsulate_with_default_rng().unwrap();
+    /// let shared_secret = sk.decapsulate(&ciphertext)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn decapsulate(&self, ciphertext: &Ciphertext<P>) -> Result<SharedSecret, Error> {
+        self.inner.decapsulate_bytes(ciphertext.bytes.as_ref())
+    }
+}
+
+impl<P: ParameterSet> Ciphertext<P> {
+    /// Wraps a byte array of the correct length for `P` as a ciphertext
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::kem::typed::*;
+    /// # let (pk, _) = MlKem512::generate_keypair_with_default_rng().unwrap();
+    /// # let (new_ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+    /// # let bytes = *new_ciphertext.as_bytes();
+    /// let ciphertext = Ciphertext::<MlKem512>::from_bytes(bytes);
+    /// ```
+    #[must_use]
+    pub const fn from_bytes(bytes: P::CiphertextBytes) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the ciphertext as a byte array of the correct length for `P`
+    #[must_use]
+    pub const fn as_bytes(&self) -> &P::CiphertextBytes {
+        &self.bytes
+    }
+}
+
+impl<P: ParameterSet> TryFrom<&[u8]> for Ciphertext<P> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let mut ciphertext = P::CiphertextBytes::zeroed();
+        if bytes.len() != ciphertext.as_ref().len() {
+            return Err(Error::InvalidLength {
+                expected: ciphertext.as_ref().len(),
+                actual: bytes.len(),
+            });
+        }
+        ciphertext.as_mut().copy_from_slice(bytes);
+
+        Ok(Self::from_bytes(ciphertext))
+    }
+}
+
+impl<P: ParameterSet> AsRef<[u8]> for Ciphertext<P> {
+    fn as_ref(&self) -> &[u8] {
+        self.bytes.as_ref()
+    }
+}
+
+impl<P: ParameterSet> ConstantTimeEq for Ciphertext<P> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.bytes.as_ref().ct_eq(other.bytes.as_ref())
+    }
+}
+
+impl<P: ParameterSet> PartialEq for Ciphertext<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl<P: ParameterSet> Eq for Ciphertext<P> {}
+
+impl<P: ParameterSet> From<PublicKey<P>> for DynPublicKey {
+    fn from(pk: PublicKey<P>) -> Self {
+        pk.inner
+    }
+}
+
+impl<P: ParameterSet> TryFrom<DynPublicKey> for PublicKey<P> {
+    type Error = Error;
+
+    fn try_from(pk: DynPublicKey) -> Result<Self, Self::Error> {
+        if pk.sec_level() == P::SEC_LEVEL {
+            Ok(Self::new(pk))
+        } else {
+            Err(Error::parameter_mismatch(P::SEC_LEVEL, pk.sec_level()))
+        }
+    }
+}
+
+impl<P: ParameterSet> From<PrivateKey<P>> for DynPrivateKey {
+    fn from(sk: PrivateKey<P>) -> Self {
+        sk.inner
+    }
+}
+
+impl<P: ParameterSet> Borrow<DynPrivateKey> for PrivateKey<P> {
+    fn borrow(&self) -> &DynPrivateKey {
+        &self.inner
+    }
+}
+
+impl<P: ParameterSet> TryFrom<DynPrivateKey> for PrivateKey<P> {
+    type Error = Error;
+
+    fn try_from(sk: DynPrivateKey) -> Result<Self, Self::Error> {
+        if sk.sec_level() == P::SEC_LEVEL {
+            Ok(Self::new(sk))
+        } else {
+            Err(Error::parameter_mismatch(P::SEC_LEVEL, sk.sec_level()))
+        }
+    }
+}
+
+impl<P: ParameterSet> TryFrom<DynCiphertext> for Ciphertext<P> {
+    type Error = Error;
+
+    fn try_from(ciphertext: DynCiphertext) -> Result<Self, Self::Error> {
+        if ciphertext.sec_level() == P::SEC_LEVEL {
+            Self::try_from(ciphertext.as_bytes())
+        } else {
+            Err(Error::parameter_mismatch(
+                P::SEC_LEVEL,
+                ciphertext.sec_level(),
+            ))
+        }
+    }
+}
+
+impl<P: ParameterSet> From<Ciphertext<P>> for DynCiphertext {
+    fn from(ciphertext: Ciphertext<P>) -> Self {
+        Self::from(&ciphertext)
+    }
+}
+
+impl<P: ParameterSet> From<&Ciphertext<P>> for DynCiphertext {
+    fn from(ciphertext: &Ciphertext<P>) -> Self {
+        let mut bytes = [0u8; MAX_CIPHERTEXT];
+        bytes[..P::SEC_LEVEL.ciphertext_bytes()].copy_from_slice(ciphertext.bytes.as_ref());
+
+        Self {
+            bytes,
+            sec_level: P::SEC_LEVEL,
+        }
+    }
+}
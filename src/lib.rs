@@ -22,7 +22,7 @@
 
 //! ### About
 
-//! A pure rust implementation of the Module-Lattice-based standards [ML-KEM](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.ipd.pdf) and (soon) [ML-DSA](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.204.ipd.pdf), also known as the PQC scheme [Crystals](https://pq-crystals.org/).
+//! A pure rust implementation of the Module-Lattice-based standards [ML-KEM](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf) and (soon) [ML-DSA](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.204.ipd.pdf), also known as the PQC scheme [Crystals](https://pq-crystals.org/).
 //!
 //! This package consists of a library (`enc_rust`), and (soon :TM:) a binary wrapper. The library currently contains implementations for ML-KEM (Kyber), and will in the future also support ML-DSA (Dilithium).
 //!
@@ -38,10 +38,10 @@
 //!
 //! `enc_rust` currently supports:
 //! - ML-KEM
+//! - X-Wing, ML-KEM-768 in hybrid with x25519
 //!
 //! but will provide:
 //!
-//! - ML-KEM in hybrid with x25519
 //! - ML-DSA
 //! - ML-DSA in hybrid with Ed25519
 //!
@@ -56,21 +56,22 @@
 //! ```rust
 //! use enc_rust::kem::*;
 //!
-//! fn alice(pk: PublicKey) -> (Ciphertext, [u8; 32]) {
-//!     let (ciphertext, shared_secret) = pk.encapsulate(None, None).unwrap();
+//! fn alice(pk: PublicKey) -> (Ciphertext, SharedSecret) {
+//!     let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
 //!
 //!     (ciphertext, shared_secret)
 //! }
 //!
-//! fn bob(sk: PrivateKey, ciphertext: &[u8]) -> [u8; 32] {
-//!     let shared_secret = sk.decapsulate(ciphertext).unwrap();
+//! fn bob(sk: PrivateKey, ciphertext: &[u8]) -> SharedSecret {
+//!     let ciphertext = Ciphertext::from_bytes(ciphertext).unwrap();
+//!     let shared_secret = sk.decapsulate(&ciphertext).unwrap();
 //!
 //!     shared_secret
 //! }
 //!
 //!
 //! fn main() {
-//!     let (pk, sk) = generate_keypair_768(None).unwrap();
+//!     let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
 //!
 //!     let (ciphertext, alice_secret) = alice(pk);
 //!
@@ -80,6 +81,22 @@
 //! }
 //! ```
 //!
+//! #### Features
+//!
+//! - `rand_core_09`: `rng::RandCore09` adapter for RNGs implementing the `rand_core` 0.9 traits
+//! - `kem`: implementations of the `RustCrypto` [`kem`](https://docs.rs/kem/0.3.0-pre.0) `Encapsulate` and `Decapsulate` traits
+//! - `alloc`: `to_vec` helpers returning owned buffers
+//! - `std`: implementation of `std::error::Error` for `errors::Error`, enables `alloc`
+//! - `serde`: `Serialize` and `Deserialize` for keys and ciphertexts, as Base64 in human readable formats and raw bytes otherwise. Private keys are only serialised through `kem::serde_private_key`
+//! - `pkcs8`: DER encodings of keys using the IETF LAMPS ML-KEM algorithm identifiers, as SPKI for public keys and PKCS#8 for private keys. Encoding requires `alloc`
+//! - `pem`: PEM encodings of keys, enables `pkcs8` and `alloc`
+//! - `hybrid`: hybrid KEMs combining ML-KEM with elliptic curve Diffie-Hellman, X-Wing in `hybrid::xwing` and ML-KEM with X25519 under a caller chosen label in `hybrid::combiner`
+//! - `x448`: X448 as a classical component in `hybrid::combiner`, enables `hybrid`. Requires `std`
+//! - `nist`: ML-KEM-768 with P-256 and ML-KEM-1024 with P-384 in `hybrid::nist`, as in the `SecP256r1MLKEM768` and `SecP384r1MLKEM1024` TLS groups, enables `hybrid`
+//! - `tls`: sans-IO TLS 1.3 `key_share` helpers for the `X25519MLKEM768`, `SecP256r1MLKEM768`, `MLKEM768` and `MLKEM1024` groups in `tls`, enables `nist`
+//! - `rustls`: `X25519MLKEM768` and `MLKEM768` key exchange groups for a [rustls](https://docs.rs/rustls/0.23) `CryptoProvider` in `tls::rustls`, enables `tls` and `std`
+//! - `hpke`: Hybrid Public Key Encryption ([RFC 9180](https://www.rfc-editor.org/rfc/rfc9180)) in the base and PSK modes with the ML-KEM KEMs in `hpke`
+//!
 //! ### Disclaimer
 //!
 //! This library and binary wrapper is offered as-is, and without a guarantee. Please exercise caution when using this library in a production application, and we accept no liability for any security issues related to the use of this code.
@@ -99,23 +116,49 @@
 //! - Gregor Seiler, IBM Research Zurich (CH)
 //! - Damien Stehle, ENS Lyon (FR)
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 pub mod errors;
 mod field_operations;
+#[cfg(feature = "hpke")]
+pub mod hpke;
+#[cfg(feature = "hybrid")]
+pub mod hybrid;
 mod indcpa;
 pub mod kem;
 mod matrix;
 pub(crate) mod params;
 mod polynomials;
+#[cfg(feature = "rand_core_09")]
+pub mod rng;
+#[cfg(feature = "tls")]
+pub mod tls;
 mod vectors;
 
 mod tests {
+    mod acvp;
+    mod combiner;
+    mod errors;
     mod field_operations;
+    mod hpke;
     mod indcpa;
     mod kem;
     mod matrix;
+    mod nist;
     mod ntt;
     mod params;
+    mod pkcs8;
     mod polynomials;
+    mod rng;
+    mod rustls;
     mod sample;
+    mod serialization;
+    mod tls;
+    mod traits;
+    mod typed;
     mod vectors;
+    mod xwing;
 }
@@ -1,10 +1,11 @@
 use crate::{
-    errors::CrystalsError,
+    errors::Error,
     params::K,
     polynomials::{Montgomery, Poly, State},
     vectors::PolyVec,
 };
 use tinyvec::ArrayVec;
+use zeroize::Zeroize;
 
 #[derive(Default, Clone, Copy, PartialEq, Debug, Eq)]
 pub struct Matrix<S: State> {
@@ -12,12 +13,18 @@ pub struct Matrix<S: State> {
     sec_level: K,
 }
 
+impl<S: State> Zeroize for Matrix<S> {
+    fn zeroize(&mut self) {
+        self.polyvecs.iter_mut().for_each(Zeroize::zeroize);
+    }
+}
+
 impl<S: State + Copy> Matrix<S> {
     pub(crate) fn vectors(&self) -> &[PolyVec<S>] {
         &self.polyvecs.as_slice()[..self.sec_level.into()]
     }
 
-    pub(crate) fn transpose(&self) -> Result<Self, CrystalsError> {
+    pub(crate) fn transpose(&self) -> Result<Self, Error> {
         let mut raw_matrix = [ArrayVec::<[Poly<S>; 4]>::new(); 4];
         self.vectors()
             .iter()
@@ -26,11 +33,11 @@ impl<S: State + Copy> Matrix<S> {
                 raw_matrix[i].push(*poly);
             });
 
-        let polyvecs_result: Result<ArrayVec<[PolyVec<S>; 4]>, CrystalsError> = raw_matrix
+        let polyvecs_result: Result<ArrayVec<[PolyVec<S>; 4]>, Error> = raw_matrix
             [..self.sec_level.into()]
             .iter()
             .map(|vec| PolyVec::from(*vec))
-            .collect::<Result<ArrayVec<[PolyVec<S>; 4]>, CrystalsError>>();
+            .collect::<Result<ArrayVec<[PolyVec<S>; 4]>, Error>>();
 
         match polyvecs_result {
             Ok(polyvecs) => Ok(Self {
@@ -43,11 +50,7 @@ impl<S: State + Copy> Matrix<S> {
 }
 
 impl Matrix<Montgomery> {
-    pub(crate) fn derive(
-        seed: &[u8],
-        transpose: bool,
-        sec_level: K,
-    ) -> Result<Self, CrystalsError> {
+    pub(crate) fn derive(seed: &[u8], transpose: bool, sec_level: K) -> Result<Self, Error> {
         let mut polyvecs = ArrayVec::<[PolyVec<Montgomery>; 4]>::new();
         if transpose {
             for i in 0..sec_level.into() {
@@ -56,7 +59,7 @@ impl Matrix<Montgomery> {
                         #[allow(clippy::cast_possible_truncation)] // we know that max i, j is 4
                         Poly::derive_uniform(seed, i as u8, j as u8)
                     })
-                    .collect::<Result<ArrayVec<[Poly<Montgomery>; 4]>, CrystalsError>>()?;
+                    .collect::<Result<ArrayVec<[Poly<Montgomery>; 4]>, Error>>()?;
 
                 let polyvec = PolyVec::from(row)?;
                 polyvecs.push(polyvec);
@@ -68,7 +71,7 @@ impl Matrix<Montgomery> {
                         #[allow(clippy::cast_possible_truncation)] // we know that max i, j is 4
                         Poly::derive_uniform(seed, j as u8, i as u8)
                     })
-                    .collect::<Result<ArrayVec<[Poly<Montgomery>; 4]>, CrystalsError>>()?;
+                    .collect::<Result<ArrayVec<[Poly<Montgomery>; 4]>, Error>>()?;
 
                 let polyvec = PolyVec::from(row)?;
                 polyvecs.push(polyvec);
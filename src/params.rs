@@ -7,8 +7,6 @@ pub const Q: usize = 3329;
 #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 pub const Q_I16: i16 = Q as i16;
 #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-pub const Q_U16: u16 = Q as u16;
-#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 pub const Q_I32: i32 = Q as i32;
 #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
 pub const Q_U32: u32 = Q as u32;
@@ -16,6 +14,12 @@ pub const Q_U32: u32 = Q as u32;
 pub const Q_DIV: u64 = 80_635; // round(2^28 / Q)
 pub const Q_DIV_VEC: u64 = 1_290_167; // round(2^32 / Q)
 
+// Rounding constants for Compress_d, matching the reference implementation for each d
+pub const Q_HALF_CEIL: u64 = 1665; // d = 4, 10
+pub const Q_HALF_FLOOR: u64 = 1664; // d = 5, 11
+pub const Q_DIV_5: u64 = 40_318; // round(2^27 / Q)
+pub const Q_DIV_VEC_11: u64 = 645_084; // round(2^31 / Q)
+
 pub const SYMBYTES: usize = 32; // size of hashes
 
 pub const SHAREDSECRETBYTES: usize = 32;
@@ -24,6 +28,21 @@ pub const POLYBYTES: usize = 384;
 
 pub const MAX_CIPHERTEXT: usize = 1569;
 
+/// The derivation used to expand a 64 byte private key seed `d || z` into a keypair.
+///
+/// Keys are always generated with [`Fips203`](KeyDerivation::Fips203). Seeds created by earlier
+/// releases of `enc_rust` used the derivation from the FIPS 203 initial public draft, and produce a
+/// different keypair from the same seed. These can be detected and loaded using
+/// [`migrate_seed`](crate::kem::PrivateKey::migrate_seed).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyDerivation {
+    /// Final FIPS 203 `K-PKE.KeyGen`, where `(rho, sigma) = G(d || k)`
+    #[default]
+    Fips203,
+    /// FIPS 203 initial public draft, where `(rho, sigma) = G(d)`
+    Fips203Ipd,
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
 #[repr(usize)]
 // Get the usize repr using .into()
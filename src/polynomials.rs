@@ -2,13 +2,18 @@ mod ntt;
 mod sample;
 
 use crate::{
-    errors::{CrystalsError, PackingError},
+    errors::Error,
     field_operations::{barrett_reduce, conditional_sub_q, mont_form, montgomery_reduce},
-    params::{SecurityLevel, N, POLYBYTES, Q, Q_DIV, Q_I16, Q_I32, Q_U16, Q_U32, SYMBYTES},
+    params::{
+        SecurityLevel, N, POLYBYTES, Q, Q_DIV, Q_DIV_5, Q_HALF_CEIL, Q_HALF_FLOOR, Q_I16, Q_I32,
+        Q_U32, SYMBYTES,
+    },
     polynomials::ntt::ZETAS,
 };
 use core::num::TryFromIntError;
+use subtle::{Choice, ConstantTimeEq};
 use tinyvec::ArrayVec;
+use zeroize::Zeroize;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Poly<S: State> {
@@ -55,6 +60,18 @@ impl<S: State> Default for Poly<S> {
     }
 }
 
+impl<S: State> Zeroize for Poly<S> {
+    fn zeroize(&mut self) {
+        self.coeffs.zeroize();
+    }
+}
+
+impl<S: State> ConstantTimeEq for Poly<S> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.coeffs.ct_eq(&other.coeffs)
+    }
+}
+
 impl<S: State> Poly<S> {
     pub(crate) const fn coeffs(&self) -> &[i16; N] {
         &self.coeffs
@@ -298,19 +315,14 @@ impl Poly<Normalised> {
     // ```
     // my_poly.compress(&buf, sec_level)?;
     // ```
-    pub(crate) fn compress(
-        &self,
-        buf: &mut [u8],
-        sec_level: &SecurityLevel,
-    ) -> Result<(), PackingError> {
+    pub(crate) fn compress(&self, buf: &mut [u8], sec_level: &SecurityLevel) -> Result<(), Error> {
         let mut t = [0u8; 8];
 
         if buf.len() != sec_level.poly_compressed_bytes() {
-            return Err(CrystalsError::IncorrectBufferLength(
-                buf.len(),
-                sec_level.poly_compressed_bytes(),
-            )
-            .into());
+            return Err(Error::InvalidLength {
+                expected: sec_level.poly_compressed_bytes(),
+                actual: buf.len(),
+            });
         }
         match sec_level {
             SecurityLevel::FiveOneTwo { .. } | SecurityLevel::SevenSixEight { .. } => {
@@ -320,10 +332,9 @@ impl Poly<Normalised> {
                     for (coeff, t_elem) in coeff_chunk.iter().zip(t.iter_mut()) {
                         let mut temp = *coeff;
                         temp += (temp >> 15) & Q_I16;
+                        // round(2^4 / Q * x)
                         *t_elem = u8::try_from(
-                            (((((u64::try_from(temp)?) << 4) + u64::from(Q_U16 / 2)) * Q_DIV)
-                                >> 28)
-                                & 0xf,
+                            (((((u64::try_from(temp)?) << 4) + Q_HALF_CEIL) * Q_DIV) >> 28) & 0xf,
                         )?;
                     }
 
@@ -343,10 +354,9 @@ impl Poly<Normalised> {
                     for (coeff, t_elem) in coeff_chunk.iter().zip(t.iter_mut()) {
                         let mut temp = *coeff;
                         temp += (temp >> 15) & Q_I16;
+                        // round(2^5 / Q * x)
                         *t_elem = u8::try_from(
-                            (((((u64::try_from(temp)?) << 5) + u64::from(Q_U32 / 2))
-                                * (Q_DIV / 2))
-                                >> 27)
+                            (((((u64::try_from(temp)?) << 5) + Q_HALF_FLOOR) * Q_DIV_5) >> 27)
                                 & 0x1f,
                         )?;
                     }
@@ -370,9 +380,12 @@ impl Poly<Normalised> {
     // ```
     // unpacked_poly = Poly::unpack(buf);
     // ```
-    pub fn unpack(buf: &[u8]) -> Result<Poly<Unreduced>, PackingError> {
+    pub fn unpack(buf: &[u8]) -> Result<Poly<Unreduced>, Error> {
         if buf.len() != POLYBYTES {
-            return Err(CrystalsError::IncorrectBufferLength(buf.len(), POLYBYTES).into());
+            return Err(Error::InvalidLength {
+                expected: POLYBYTES,
+                actual: buf.len(),
+            });
         }
         let coeffs_arr: [i16; N] = buf
             .chunks_exact(3)
@@ -400,9 +413,9 @@ impl Poly<Normalised> {
     // ```
     // let read_result = Poly::read_msg(msg_buf);
     // ```
-    pub(crate) fn read_msg(msg: &[u8]) -> Result<Poly<Unreduced>, PackingError> {
+    pub(crate) fn read_msg(msg: &[u8]) -> Result<Poly<Unreduced>, Error> {
         if msg.len() == SYMBYTES {
-            let q_plus_one_over_2 = i16::try_from((Q + 1) / 2)?;
+            let q_plus_one_over_2 = i16::try_from(Q.div_ceil(2))?;
             let coeffs_arr: [i16; N] = msg
                 .iter()
                 .flat_map(|&byte| (0..8).map(move |i| ((i16::from(byte) >> i) & 1).wrapping_neg()))
@@ -415,7 +428,10 @@ impl Poly<Normalised> {
                 state: Unreduced,
             })
         } else {
-            Err(CrystalsError::IncorrectBufferLength(msg.len(), SYMBYTES).into())
+            Err(Error::InvalidLength {
+                expected: SYMBYTES,
+                actual: msg.len(),
+            })
         }
     }
 
@@ -427,13 +443,12 @@ impl Poly<Normalised> {
     // ```
     // let decompress_result = Poly::decompress(buf, k);
     // ```
-    pub(crate) fn decompress(buf: &[u8], sec_level: &SecurityLevel) -> Result<Self, PackingError> {
+    pub(crate) fn decompress(buf: &[u8], sec_level: &SecurityLevel) -> Result<Self, Error> {
         if buf.len() != sec_level.poly_compressed_bytes() {
-            return Err(CrystalsError::IncorrectBufferLength(
-                buf.len(),
-                sec_level.poly_compressed_bytes(),
-            )
-            .into());
+            return Err(Error::InvalidLength {
+                expected: sec_level.poly_compressed_bytes(),
+                actual: buf.len(),
+            });
         }
 
         match sec_level {
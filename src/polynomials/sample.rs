@@ -1,5 +1,5 @@
 use crate::{
-    errors::CrystalsError,
+    errors::Error,
     params::{Eta, N, Q, SYMBYTES},
     polynomials::{Montgomery, Poly},
 };
@@ -97,9 +97,12 @@ impl Poly<Montgomery> {
 
     // seed should be of length 32
     // coefficients are reduced, but not normalised (close to normal) {0..q}
-    pub(crate) fn derive_uniform(seed: &[u8], x: u8, y: u8) -> Result<Self, CrystalsError> {
-        if seed.len() != 32 {
-            return Err(CrystalsError::InvalidSeedLength(seed.len(), 32));
+    pub(crate) fn derive_uniform(seed: &[u8], x: u8, y: u8) -> Result<Self, Error> {
+        if seed.len() != SYMBYTES {
+            return Err(Error::InvalidLength {
+                expected: SYMBYTES,
+                actual: seed.len(),
+            });
         }
         let seed_suffix = [x, y];
         let mut buf = [0u8; 168];
@@ -0,0 +1,73 @@
+//! Adapter for RNGs implementing the [`rand_core` 0.9](https://docs.rs/rand_core/0.9) traits.
+//!
+//! The key generation and encapsulation functions in [`kem`](crate::kem) are generic over the
+//! `rand_core` 0.6 [`RngCore`] and [`CryptoRng`] traits. RNGs from the 0.9 trait family can be
+//! used by wrapping them in [`RandCore09`].
+//!
+//! # Example
+//! ```
+//! # use enc_rust::kem::*;
+//! use enc_rust::rng::RandCore09;
+//! # use rand_core_09::{CryptoRng, RngCore};
+//! # struct MyRng(u8);
+//! # impl RngCore for MyRng {
+//! #     fn next_u32(&mut self) -> u32 { 0 }
+//! #     fn next_u64(&mut self) -> u64 { 0 }
+//! #     fn fill_bytes(&mut self, dest: &mut [u8]) { dest.fill(self.0) }
+//! # }
+//! # impl CryptoRng for MyRng {}
+//! # let mut my_rng = MyRng(1);
+//!
+//! let mut rng = RandCore09(&mut my_rng);
+//! let (pk, sk) = generate_keypair_768(&mut rng)?;
+//! let (ciphertext, shared_secret) = pk.encapsulate(&mut rng)?;
+//!
+//! # Ok::<(), enc_rust::errors::Error>(())
+//! ```
+
+use core::num::NonZeroU32;
+use rand_core::{CryptoRng, Error, RngCore};
+use rand_core_09::{TryCryptoRng, TryRngCore};
+
+// Reported in place of the 0.9 error, which cannot be converted
+const RNG_FAILURE: NonZeroU32 = match NonZeroU32::new(Error::CUSTOM_START) {
+    Some(code) => code,
+    None => NonZeroU32::MIN,
+};
+
+/// Wraps an RNG implementing the `rand_core` 0.9 [`TryRngCore`] and [`TryCryptoRng`] traits, so
+/// that it can be passed to functions expecting the `rand_core` 0.6 [`RngCore`] and [`CryptoRng`]
+/// traits.
+///
+/// Any infallible 0.9 [`CryptoRng`](rand_core_09::CryptoRng) can be wrapped, either by value or
+/// by mutable reference. Failures of a fallible RNG are reported through
+/// [`try_fill_bytes`](RngCore::try_fill_bytes), which is the only method used by this crate.
+#[derive(Clone, Debug, Default)]
+pub struct RandCore09<R>(pub R);
+
+impl<R: TryRngCore> RngCore for RandCore09<R> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    // The 0.6 infallible methods panic on RNG failure, as rand_core's own OsRng does
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        assert!(self.try_fill_bytes(dest).is_ok(), "RNG failure");
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0
+            .try_fill_bytes(dest)
+            .map_err(|_| Error::from(RNG_FAILURE))
+    }
+}
+
+impl<R: TryCryptoRng> CryptoRng for RandCore09<R> {}
@@ -0,0 +1,240 @@
+#![allow(warnings)]
+#[cfg(test)]
+mod acvp_tests {
+    extern crate std;
+
+    use crate::{
+        kem::*,
+        params::{SecurityLevel, K, SYMBYTES},
+    };
+    use serde::Deserialize;
+    use sha3::{Digest, Sha3_256};
+    use std::{string::String, vec, vec::Vec};
+
+    // See acvp/README.md for the source of each vector set.
+    const KEY_GEN: &str = include_str!("acvp/ML-KEM-keyGen-FIPS203.json");
+    const ENCAP_DECAP: &str = include_str!("acvp/ML-KEM-encapDecap-FIPS203.json");
+    const KEY_CHECK: &str = include_str!("acvp/derived-keyCheck.json");
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct VectorSet<G> {
+        test_groups: Vec<G>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct KeyGenGroup {
+        parameter_set: String,
+        tests: Vec<KeyGenTest>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct KeyGenTest {
+        tc_id: usize,
+        #[serde(with = "hex")]
+        d: Vec<u8>,
+        #[serde(with = "hex")]
+        z: Vec<u8>,
+        #[serde(with = "hex")]
+        ek: Vec<u8>,
+        #[serde(with = "hex")]
+        dk: Vec<u8>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(
+        tag = "function",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+    )]
+    enum EncapDecapGroup {
+        Encapsulation {
+            parameter_set: String,
+            tests: Vec<EncapsulationTest>,
+        },
+        Decapsulation {
+            parameter_set: String,
+            #[serde(with = "hex")]
+            dk: Vec<u8>,
+            tests: Vec<DecapsulationTest>,
+        },
+        EncapsulationKeyCheck {
+            parameter_set: String,
+            tests: Vec<EncapsulationKeyCheckTest>,
+        },
+        DecapsulationKeyCheck {
+            parameter_set: String,
+            tests: Vec<DecapsulationKeyCheckTest>,
+        },
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct EncapsulationTest {
+        tc_id: usize,
+        #[serde(with = "hex")]
+        ek: Vec<u8>,
+        #[serde(with = "hex")]
+        dk: Vec<u8>,
+        #[serde(with = "hex")]
+        m: Vec<u8>,
+        #[serde(with = "hex")]
+        c: Vec<u8>,
+        #[serde(with = "hex")]
+        k: Vec<u8>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct DecapsulationTest {
+        tc_id: usize,
+        #[serde(with = "hex")]
+        c: Vec<u8>,
+        #[serde(with = "hex")]
+        k: Vec<u8>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct EncapsulationKeyCheckTest {
+        tc_id: usize,
+        #[serde(with = "hex")]
+        ek: Vec<u8>,
+        test_passed: bool,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct DecapsulationKeyCheckTest {
+        tc_id: usize,
+        #[serde(with = "hex")]
+        dk: Vec<u8>,
+        test_passed: bool,
+    }
+
+    fn sec_level(parameter_set: &str) -> SecurityLevel {
+        match parameter_set {
+            "ML-KEM-512" => SecurityLevel::new(K::Two),
+            "ML-KEM-768" => SecurityLevel::new(K::Three),
+            "ML-KEM-1024" => SecurityLevel::new(K::Four),
+            _ => panic!("unknown parameter set {parameter_set}"),
+        }
+    }
+
+    fn encap_decap_groups(vectors: &str) -> Vec<EncapDecapGroup> {
+        serde_json::from_str::<VectorSet<EncapDecapGroup>>(vectors)
+            .unwrap()
+            .test_groups
+    }
+
+    // FIPS 203 7.3: H(ek) == h, for the ek and h embedded in dk
+    fn decapsulation_key_check(dk: &[u8], sec_level: SecurityLevel) -> bool {
+        dk.len() == sec_level.private_key_bytes() && PrivateKey::from_expanded_bytes(dk).is_ok()
+    }
+
+    #[test]
+    fn key_gen() {
+        let vectors: VectorSet<KeyGenGroup> = serde_json::from_str(KEY_GEN).unwrap();
+
+        for group in vectors.test_groups {
+            let sec_level = sec_level(&group.parameter_set);
+            for test in group.tests {
+                let d: [u8; SYMBYTES] = test.d.try_into().unwrap();
+                let z: [u8; SYMBYTES] = test.z.try_into().unwrap();
+
+                let (pk, sk) = match sec_level.k() {
+                    K::Two => generate_keypair_from_seed_512(&d, &z),
+                    K::Three => generate_keypair_from_seed_768(&d, &z),
+                    K::Four => generate_keypair_from_seed_1024(&d, &z),
+                }
+                .unwrap();
+
+                let mut ek = vec![0u8; sec_level.public_key_bytes()];
+                pk.pack(&mut ek).unwrap();
+                assert_eq!(ek, test.ek, "tcId {}", test.tc_id);
+
+                let mut dk = vec![0u8; sec_level.private_key_bytes()];
+                sk.to_expanded_bytes(&mut dk).unwrap();
+                assert_eq!(dk, test.dk, "tcId {}", test.tc_id);
+            }
+        }
+    }
+
+    #[test]
+    fn encapsulation() {
+        for group in encap_decap_groups(ENCAP_DECAP) {
+            let EncapDecapGroup::Encapsulation { tests, .. } = group else {
+                continue;
+            };
+            for test in tests {
+                let pk = PublicKey::unpack(&test.ek).unwrap();
+                let m: [u8; SYMBYTES] = test.m.try_into().unwrap();
+
+                let (ciphertext, shared_secret) = pk.encapsulate_deterministic(&m).unwrap();
+                assert_eq!(ciphertext.as_bytes(), test.c, "tcId {}", test.tc_id);
+                assert_eq!(shared_secret.as_bytes()[..], test.k, "tcId {}", test.tc_id);
+
+                let sk = PrivateKey::from_expanded_bytes(&test.dk).unwrap();
+                let decap_secret = sk
+                    .decapsulate(&Ciphertext::from_bytes(&test.c).unwrap())
+                    .unwrap();
+                assert_eq!(decap_secret.as_bytes()[..], test.k, "tcId {}", test.tc_id);
+            }
+        }
+    }
+
+    // Includes the modified ciphertext cases, which must return the implicit rejection secret.
+    #[test]
+    fn decapsulation() {
+        for group in encap_decap_groups(ENCAP_DECAP) {
+            let EncapDecapGroup::Decapsulation { dk, tests, .. } = group else {
+                continue;
+            };
+            let sk = PrivateKey::from_expanded_bytes(&dk).unwrap();
+            for test in tests {
+                let shared_secret = sk
+                    .decapsulate(&Ciphertext::from_bytes(&test.c).unwrap())
+                    .unwrap();
+                assert_eq!(shared_secret.as_bytes()[..], test.k, "tcId {}", test.tc_id);
+            }
+        }
+    }
+
+    // Runs the key check groups of the NIST encapDecap file, if it has them, and the derived cases.
+    #[test]
+    fn key_check() {
+        let groups = encap_decap_groups(ENCAP_DECAP)
+            .into_iter()
+            .chain(encap_decap_groups(KEY_CHECK));
+        for group in groups {
+            match group {
+                EncapDecapGroup::EncapsulationKeyCheck { tests, .. } => {
+                    for test in tests {
+                        assert_eq!(
+                            PublicKey::unpack(&test.ek).is_ok(),
+                            test.test_passed,
+                            "tcId {}",
+                            test.tc_id
+                        );
+                    }
+                }
+                EncapDecapGroup::DecapsulationKeyCheck {
+                    parameter_set,
+                    tests,
+                } => {
+                    for test in tests {
+                        assert_eq!(
+                            decapsulation_key_check(&test.dk, sec_level(&parameter_set)),
+                            test.test_passed,
+                            "tcId {}",
+                            test.tc_id
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
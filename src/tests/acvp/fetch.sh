@@ -0,0 +1,13 @@
+#!/bin/sh
+# Fetches the current NIST ACVP-Server ML-KEM vectors into this directory.
+# The encapDecap vectors include the encapsulationKeyCheck and decapsulationKeyCheck groups, which
+# the key_check test in src/tests/acvp.rs runs alongside derived-keyCheck.json.
+set -eu
+
+ref="${1:-master}"
+base="https://raw.githubusercontent.com/usnistgov/ACVP-Server/$ref/gen-val/json-files"
+dir="$(dirname "$0")"
+
+for name in keyGen encapDecap; do
+    curl -fsSL "$base/ML-KEM-$name-FIPS203/internalProjection.json" -o "$dir/ML-KEM-$name-FIPS203.json"
+done
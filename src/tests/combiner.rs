@@ -0,0 +1,227 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "hybrid")]
+mod combiner_tests {
+    extern crate std;
+
+    use crate::{errors::Error, hybrid::combiner::*};
+    use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+    use std::{format, vec, vec::Vec};
+
+    const LABEL: &[u8] = b"enc_rust test";
+
+    fn round_trip<P: ParameterSet, C: ClassicalKem>(seed: [u8; 32], label: &[u8]) {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let (pk, sk) = HybridKem::<P, C>::generate_keypair(&mut rng).unwrap();
+        assert_eq!(sk.get_public_key(), pk);
+
+        let (ciphertext, shared_secret) = pk.encapsulate(label, &mut rng).unwrap();
+        assert_eq!(sk.decapsulate(label, &ciphertext).unwrap(), shared_secret);
+
+        // The label is bound into the shared secret
+        assert_ne!(
+            sk.decapsulate(b"other label", &ciphertext).unwrap(),
+            shared_secret
+        );
+    }
+
+    fn encodings<P: ParameterSet, C: ClassicalKem>(seed: [u8; 32]) {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let (pk, sk) = HybridKem::<P, C>::generate_keypair(&mut rng).unwrap();
+        let (ciphertext, shared_secret) = pk.encapsulate(LABEL, &mut rng).unwrap();
+
+        let mut pk_bytes = vec![0u8; PublicKey::<P, C>::BYTES];
+        pk.pack(&mut pk_bytes).unwrap();
+        assert_eq!(PublicKey::<P, C>::unpack(&pk_bytes).unwrap(), pk);
+        // ML-KEM public key followed by the classical public key
+        assert_eq!(
+            &pk_bytes[PublicKey::<P, C>::BYTES - C::PUBLIC_KEY_BYTES..],
+            classical_public_key(&sk)
+        );
+
+        let mut sk_bytes = vec![0u8; PrivateKey::<P, C>::BYTES];
+        sk.pack(&mut sk_bytes).unwrap();
+        let parsed = PrivateKey::<P, C>::unpack(&sk_bytes).unwrap();
+        assert_eq!(parsed, sk);
+        assert_eq!(parsed.get_public_key(), pk);
+
+        let mut ct_bytes = vec![0u8; Ciphertext::<P, C>::BYTES];
+        ciphertext.pack(&mut ct_bytes).unwrap();
+        let parsed = Ciphertext::<P, C>::try_from(ct_bytes.as_slice()).unwrap();
+        assert_eq!(parsed, ciphertext);
+        assert_eq!(sk.decapsulate(LABEL, &parsed).unwrap(), shared_secret);
+    }
+
+    fn classical_public_key<P: ParameterSet, C: ClassicalKem>(sk: &PrivateKey<P, C>) -> Vec<u8> {
+        let mut sk_bytes = vec![0u8; PrivateKey::<P, C>::BYTES];
+        sk.pack(&mut sk_bytes).unwrap();
+        let sk_c = C::private_key_from_bytes(
+            &sk_bytes[PrivateKey::<P, C>::BYTES - C::PRIVATE_KEY_BYTES..],
+        )
+        .unwrap();
+
+        C::public_key(&sk_c).as_ref().to_vec()
+    }
+
+    fn modified_ciphertext<P: ParameterSet, C: ClassicalKem>(seed: [u8; 32], index: usize) {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let (pk, sk) = HybridKem::<P, C>::generate_keypair(&mut rng).unwrap();
+        let (ciphertext, shared_secret) = pk.encapsulate(LABEL, &mut rng).unwrap();
+
+        let mut ct_bytes = vec![0u8; Ciphertext::<P, C>::BYTES];
+        ciphertext.pack(&mut ct_bytes).unwrap();
+        ct_bytes[index % Ciphertext::<P, C>::BYTES] ^= 1;
+
+        // Either the classical KEM rejects the ciphertext, or the shared secret differs
+        if let Ok(modified) = Ciphertext::<P, C>::from_bytes(&ct_bytes) {
+            if let Ok(modified_secret) = sk.decapsulate(LABEL, &modified) {
+                assert_ne!(modified_secret, shared_secret);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn x25519_round_trip(seed in prop::array::uniform32(any::<u8>()), label in prop::collection::vec(any::<u8>(), 0..64)) {
+            round_trip::<MlKem512, X25519>(seed, &label);
+            round_trip::<MlKem768, X25519>(seed, &label);
+            round_trip::<MlKem1024, X25519>(seed, &label);
+        }
+
+        #[test]
+        fn x25519_encodings(seed in prop::array::uniform32(any::<u8>())) {
+            encodings::<MlKem512, X25519>(seed);
+            encodings::<MlKem768, X25519>(seed);
+            encodings::<MlKem1024, X25519>(seed);
+        }
+
+        #[test]
+        fn x25519_modified_ciphertext(seed in prop::array::uniform32(any::<u8>()), index in any::<usize>()) {
+            modified_ciphertext::<MlKem512, X25519>(seed, index);
+            modified_ciphertext::<MlKem768, X25519>(seed, index);
+            modified_ciphertext::<MlKem1024, X25519>(seed, index);
+        }
+
+        #[cfg(feature = "x448")]
+        #[test]
+        fn x448_round_trip(seed in prop::array::uniform32(any::<u8>()), label in prop::collection::vec(any::<u8>(), 0..64)) {
+            round_trip::<MlKem512, X448>(seed, &label);
+            round_trip::<MlKem768, X448>(seed, &label);
+            round_trip::<MlKem1024, X448>(seed, &label);
+        }
+
+        #[cfg(feature = "x448")]
+        #[test]
+        fn x448_encodings(seed in prop::array::uniform32(any::<u8>())) {
+            encodings::<MlKem512, X448>(seed);
+            encodings::<MlKem768, X448>(seed);
+            encodings::<MlKem1024, X448>(seed);
+        }
+
+        #[cfg(feature = "x448")]
+        #[test]
+        fn x448_modified_ciphertext(seed in prop::array::uniform32(any::<u8>()), index in any::<usize>()) {
+            modified_ciphertext::<MlKem512, X448>(seed, index);
+            modified_ciphertext::<MlKem768, X448>(seed, index);
+            modified_ciphertext::<MlKem1024, X448>(seed, index);
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(PublicKey::<MlKem512, X25519>::BYTES, 800 + 32);
+        assert_eq!(PublicKey::<MlKem768, X25519>::BYTES, 1184 + 32);
+        assert_eq!(PublicKey::<MlKem1024, X25519>::BYTES, 1568 + 32);
+        assert_eq!(PrivateKey::<MlKem768, X25519>::BYTES, 2400 + 32);
+        assert_eq!(Ciphertext::<MlKem512, X25519>::BYTES, 768 + 32);
+        assert_eq!(Ciphertext::<MlKem768, X25519>::BYTES, 1088 + 32);
+        assert_eq!(Ciphertext::<MlKem1024, X25519>::BYTES, 1568 + 32);
+    }
+
+    #[cfg(feature = "x448")]
+    #[test]
+    fn x448_sizes() {
+        assert_eq!(PublicKey::<MlKem768, X448>::BYTES, 1184 + 56);
+        assert_eq!(PrivateKey::<MlKem768, X448>::BYTES, 2400 + 56);
+        assert_eq!(Ciphertext::<MlKem1024, X448>::BYTES, 1568 + 56);
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        let expected = PublicKey::<MlKem768, X25519>::BYTES;
+        assert_eq!(
+            PublicKey::<MlKem768, X25519>::unpack(&[0u8; 1184]),
+            Err(Error::InvalidLength {
+                expected,
+                actual: 1184
+            })
+        );
+
+        let (pk, _) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+        let mut bytes = [0u8; 1184];
+        assert_eq!(
+            pk.pack(&mut bytes),
+            Err(Error::InvalidLength {
+                expected,
+                actual: 1184
+            })
+        );
+
+        let expected = Ciphertext::<MlKem768, X25519>::BYTES;
+        assert_eq!(
+            Ciphertext::<MlKem768, X25519>::from_bytes(&[0u8; 1088]),
+            Err(Error::InvalidLength {
+                expected,
+                actual: 1088
+            })
+        );
+    }
+
+    #[test]
+    fn low_order_x25519() {
+        let (pk, sk) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, _) = pk.encapsulate_with_default_rng(LABEL).unwrap();
+
+        // The identity, giving an all zero Diffie-Hellman output
+        let mut ct_bytes = [0u8; 1120];
+        ciphertext.pack(&mut ct_bytes).unwrap();
+        ct_bytes[1088..].fill(0);
+        let ciphertext = Ciphertext::<MlKem768, X25519>::from_bytes(&ct_bytes).unwrap();
+        assert_eq!(
+            sk.decapsulate(LABEL, &ciphertext),
+            Err(Error::InvalidEncoding)
+        );
+
+        let mut pk_bytes = [0u8; 1216];
+        pk.pack(&mut pk_bytes).unwrap();
+        pk_bytes[1184..].fill(0);
+        let pk = PublicKey::<MlKem768, X25519>::unpack(&pk_bytes).unwrap();
+        assert_eq!(
+            pk.encapsulate_with_default_rng(LABEL).map(|_| ()),
+            Err(Error::InvalidEncoding)
+        );
+    }
+
+    #[cfg(feature = "x448")]
+    #[test]
+    fn low_order_x448() {
+        let (pk, _) = MlKem768X448::generate_keypair_with_default_rng().unwrap();
+        let mut pk_bytes = [0u8; 1240];
+        pk.pack(&mut pk_bytes).unwrap();
+        pk_bytes[1184..].fill(0);
+
+        assert_eq!(
+            PublicKey::<MlKem768, X448>::unpack(&pk_bytes),
+            Err(Error::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn private_key_debug_is_redacted() {
+        let (_, sk) = MlKem768X25519::generate_keypair_with_default_rng().unwrap();
+
+        assert_eq!(format!("{sk:?}"), "PrivateKey { .. }");
+    }
+}
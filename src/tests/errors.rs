@@ -0,0 +1,85 @@
+#![allow(warnings)]
+#[cfg(test)]
+mod errors_tests {
+    extern crate std;
+
+    use crate::{
+        errors::{Error, Hpke, Validation},
+        kem::*,
+        params::{SecurityLevel, K},
+    };
+    use std::string::ToString;
+
+    #[test]
+    fn display() {
+        let error = PublicKey::unpack(&[0u8; 1000]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unsupported length, 1000 bytes does not match any parameter set"
+        );
+
+        let error = typed::Ciphertext::<typed::MlKem768>::try_from(&[0u8; 1000][..]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid length, expected 1088 bytes, got 1000 bytes"
+        );
+
+        let error =
+            Error::parameter_mismatch(SecurityLevel::new(K::Three), SecurityLevel::new(K::Two));
+        assert_eq!(
+            error.to_string(),
+            "Mismatched parameter sets, expected ML-KEM-768, got ML-KEM-512"
+        );
+
+        for error in [
+            Error::InvalidEncoding,
+            Error::RngFailure,
+            Error::ValidationFailure(Validation::PrivateKeyHash),
+            Error::ValidationFailure(Validation::PairwiseConsistency),
+            Error::ValidationFailure(Validation::KeyDerivation),
+            Error::HpkeFailure(Hpke::PskInputs),
+            Error::HpkeFailure(Hpke::Open),
+            Error::HpkeFailure(Hpke::MessageLimit),
+            Error::HpkeFailure(Hpke::MessageLength),
+            Error::Internal,
+        ] {
+            assert!(!error.to_string().is_empty());
+        }
+    }
+
+    #[test]
+    fn parameter_mismatch() {
+        let levels = [K::Two, K::Three, K::Four].map(SecurityLevel::new);
+
+        for (expected, expected_name) in levels.iter().zip([512, 768, 1024]) {
+            for (actual, actual_name) in levels.iter().zip([512, 768, 1024]) {
+                assert_eq!(
+                    Error::parameter_mismatch(*expected, *actual),
+                    Error::ParameterMismatch {
+                        expected: expected_name,
+                        actual: actual_name,
+                    }
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rng_failure() {
+        assert_eq!(
+            Error::from(rand_core::Error::from(
+                core::num::NonZeroU32::new(rand_core::Error::CUSTOM_START).unwrap()
+            )),
+            Error::RngFailure
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn std_error() {
+        let error: std::boxed::Box<dyn std::error::Error> =
+            std::boxed::Box::new(Error::InvalidEncoding);
+        assert!(error.source().is_none());
+        assert_eq!(error.to_string(), "Invalid encoding");
+    }
+}
@@ -0,0 +1,598 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "hpke")]
+mod hpke_tests {
+    extern crate std;
+
+    use crate::{
+        errors::{Error, Hpke},
+        hpke::*,
+        kem::{
+            generate_keypair_1024, generate_keypair_512, generate_keypair_768, PrivateKey,
+            PublicKey,
+        },
+    };
+    use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{CryptoRng, RngCore, SeedableRng};
+    use serde::Deserialize;
+    use std::{format, vec, vec::Vec};
+
+    // See hpke/README.md for the source of the vectors.
+    const PQ_TEST_VECTORS: &str = include_str!("hpke/pq-test-vectors.json");
+    const PSK_TEST_VECTORS: &str = include_str!("hpke/rfc9180-psk-test-vectors.json");
+    const PQ_PSK_TEST_VECTORS: &str = include_str!("hpke/pq-psk-test-vectors.json");
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        mode: u8,
+        kem_id: u16,
+        kdf_id: u16,
+        aead_id: u16,
+        #[serde(with = "hex")]
+        info: Vec<u8>,
+        #[serde(rename = "ikmE", with = "hex")]
+        ikm_e: Vec<u8>,
+        #[serde(rename = "ikmR", with = "hex")]
+        ikm_r: Vec<u8>,
+        #[serde(rename = "skRm", with = "hex")]
+        sk_rm: Vec<u8>,
+        #[serde(rename = "pkRm", with = "hex")]
+        pk_rm: Vec<u8>,
+        #[serde(default, with = "hex")]
+        psk: Vec<u8>,
+        #[serde(default, with = "hex")]
+        psk_id: Vec<u8>,
+        #[serde(with = "hex")]
+        enc: Vec<u8>,
+        #[serde(with = "hex")]
+        shared_secret: Vec<u8>,
+        // The intermediate values are absent from the generated PSK vectors
+        #[serde(default, with = "hex")]
+        key: Vec<u8>,
+        #[serde(default, with = "hex")]
+        base_nonce: Vec<u8>,
+        #[serde(default, with = "hex")]
+        exporter_secret: Vec<u8>,
+        encryptions: Vec<Encryption>,
+        exports: Vec<Export>,
+    }
+
+    #[derive(Deserialize)]
+    struct Encryption {
+        #[serde(with = "hex")]
+        aad: Vec<u8>,
+        #[serde(with = "hex")]
+        ct: Vec<u8>,
+        #[serde(default, with = "hex")]
+        nonce: Vec<u8>,
+        #[serde(with = "hex")]
+        pt: Vec<u8>,
+    }
+
+    #[derive(Deserialize)]
+    struct Export {
+        #[serde(with = "hex")]
+        exporter_context: Vec<u8>,
+        #[serde(rename = "L")]
+        len: usize,
+        #[serde(with = "hex")]
+        exported_value: Vec<u8>,
+    }
+
+    // Returns the vector's encapsulation randomness, ikmE, as ML-KEM's message m
+    struct FixedRng<'a>(&'a [u8]);
+
+    impl RngCore for FixedRng<'_> {
+        fn next_u32(&mut self) -> u32 {
+            rand_core::impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            assert!(self.0.len() >= dest.len(), "ikmE exhausted");
+            let (bytes, rest) = self.0.split_at(dest.len());
+            dest.copy_from_slice(bytes);
+            self.0 = rest;
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for FixedRng<'_> {}
+
+    const SUITES: [Suite; 36] = {
+        let kems = [Kem::MlKem512, Kem::MlKem768, Kem::MlKem1024];
+        let kdfs = [
+            Kdf::HkdfSha256,
+            Kdf::HkdfSha384,
+            Kdf::Shake128,
+            Kdf::Shake256,
+        ];
+        let aeads = [Aead::Aes128Gcm, Aead::Aes256Gcm, Aead::ChaCha20Poly1305];
+
+        let mut suites = [Suite::new(Kem::MlKem512, Kdf::HkdfSha256, Aead::Aes128Gcm); 36];
+        let mut i = 0;
+        while i < 36 {
+            suites[i] = Suite::new(kems[i / 12], kdfs[(i / 3) % 4], aeads[i % 3]);
+            i += 1;
+        }
+        suites
+    };
+
+    fn key_pair(kem: Kem, seed: [u8; 32]) -> (PublicKey, PrivateKey) {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        match kem {
+            Kem::MlKem512 => generate_keypair_512(&mut rng),
+            Kem::MlKem768 => generate_keypair_768(&mut rng),
+            Kem::MlKem1024 => generate_keypair_1024(&mut rng),
+        }
+        .unwrap()
+    }
+
+    fn check_contexts(
+        vector: &TestVector,
+        mut sender: SenderContext,
+        mut receiver: ReceiverContext,
+        aead: Aead,
+    ) {
+        if !vector.key.is_empty() {
+            for context in [&sender.0, &receiver.0] {
+                assert_eq!(&context.key[..aead.key_bytes()], vector.key);
+                assert_eq!(context.base_nonce, *vector.base_nonce);
+                assert_eq!(context.exporter_secret(), vector.exporter_secret);
+            }
+        }
+
+        for encryption in &vector.encryptions {
+            if !encryption.nonce.is_empty() {
+                assert_eq!(sender.0.nonce().unwrap(), *encryption.nonce);
+            }
+            let mut ct = vec![0u8; encryption.pt.len() + TAG_BYTES];
+            sender
+                .seal(&encryption.aad, &encryption.pt, &mut ct)
+                .unwrap();
+            assert_eq!(ct, encryption.ct);
+
+            let mut pt = vec![0u8; encryption.pt.len()];
+            receiver.open(&encryption.aad, &ct, &mut pt).unwrap();
+            assert_eq!(pt, encryption.pt);
+        }
+
+        for export in &vector.exports {
+            for exported in [
+                {
+                    let mut out = vec![0u8; export.len];
+                    sender.export(&export.exporter_context, &mut out).unwrap();
+                    out
+                },
+                {
+                    let mut out = vec![0u8; export.len];
+                    receiver.export(&export.exporter_context, &mut out).unwrap();
+                    out
+                },
+            ] {
+                assert_eq!(exported, export.exported_value);
+            }
+        }
+    }
+
+    // Runs the key schedule from the vector's shared secret, for vectors of any KEM
+    fn check_key_schedule(vector: &TestVector) {
+        let kdf = Kdf::from_id(vector.kdf_id).unwrap();
+        let aead = Aead::from_id(vector.aead_id).unwrap();
+
+        let mut suite_id = *b"HPKE______";
+        suite_id[4..6].copy_from_slice(&vector.kem_id.to_be_bytes());
+        suite_id[6..8].copy_from_slice(&vector.kdf_id.to_be_bytes());
+        suite_id[8..10].copy_from_slice(&vector.aead_id.to_be_bytes());
+
+        let context = || {
+            crate::hpke::key_schedule(
+                suite_id,
+                kdf,
+                aead,
+                vector.mode,
+                &vector.shared_secret,
+                &vector.info,
+                &vector.psk,
+                &vector.psk_id,
+            )
+            .unwrap()
+        };
+
+        check_contexts(
+            vector,
+            SenderContext(context()),
+            ReceiverContext(context()),
+            aead,
+        );
+    }
+
+    fn check_ml_kem(vector: &TestVector, suite: Suite) {
+        let (pk, sk) = suite.kem().derive_key_pair(&vector.ikm_r).unwrap();
+        assert_eq!(sk.to_seed().unwrap(), *vector.sk_rm);
+        let mut pk_bytes = vec![0u8; suite.kem().public_key_bytes()];
+        pk.pack(&mut pk_bytes).unwrap();
+        assert_eq!(pk_bytes, vector.pk_rm);
+
+        let mut rng = FixedRng(&vector.ikm_e);
+        let (enc, sender) = match vector.mode {
+            0 => suite.setup_base_sender(&pk, &vector.info, &mut rng),
+            _ => suite.setup_psk_sender(&pk, &vector.info, &vector.psk, &vector.psk_id, &mut rng),
+        }
+        .unwrap();
+        assert!(rng.0.is_empty());
+        assert_eq!(enc.as_bytes(), vector.enc);
+        assert_eq!(
+            sk.decapsulate(&enc).unwrap().as_bytes(),
+            vector.shared_secret.as_slice()
+        );
+
+        let receiver = match vector.mode {
+            0 => suite.setup_base_receiver(&enc, &sk, &vector.info),
+            _ => suite.setup_psk_receiver(&enc, &sk, &vector.info, &vector.psk, &vector.psk_id),
+        }
+        .unwrap();
+        check_contexts(vector, sender, receiver, suite.aead());
+    }
+
+    #[test]
+    fn pq_test_vectors() {
+        let vectors: Vec<TestVector> = serde_json::from_str(PQ_TEST_VECTORS).unwrap();
+
+        let (mut ml_kem, mut key_schedules) = (0, 0);
+        for vector in &vectors {
+            assert_eq!(vector.mode, 0);
+
+            if let Some(suite) = Suite::from_ids(vector.kem_id, vector.kdf_id, vector.aead_id) {
+                check_ml_kem(vector, suite);
+                ml_kem += 1;
+            }
+
+            // The key schedule of the vectors for other KEMs, except the TurboSHAKE KDFs
+            if Kdf::from_id(vector.kdf_id).is_some() && Aead::from_id(vector.aead_id).is_some() {
+                check_key_schedule(vector);
+                key_schedules += 1;
+            }
+        }
+
+        assert_eq!(ml_kem, 3);
+        assert_eq!(key_schedules, 10);
+    }
+
+    #[test]
+    fn psk_test_vectors() {
+        let vectors: Vec<TestVector> = serde_json::from_str(PSK_TEST_VECTORS).unwrap();
+
+        for vector in &vectors {
+            assert_eq!(vector.mode, 1);
+            check_key_schedule(vector);
+        }
+    }
+
+    #[test]
+    fn pq_psk_test_vectors() {
+        let vectors: Vec<TestVector> = serde_json::from_str(PQ_PSK_TEST_VECTORS).unwrap();
+        assert_eq!(vectors.len(), 7);
+
+        for vector in &vectors {
+            assert_eq!(vector.mode, 1);
+            let suite = Suite::from_ids(vector.kem_id, vector.kdf_id, vector.aead_id).unwrap();
+            check_ml_kem(vector, suite);
+        }
+    }
+
+    prop_compose! {
+        fn suite_and_key_pair()
+            (index in 0..36usize, seed in prop::array::uniform32(any::<u8>()))
+            -> (Suite, PublicKey, PrivateKey) {
+                let suite = SUITES[index];
+                let (pk, sk) = key_pair(suite.kem(), seed);
+                (suite, pk, sk)
+            }
+    }
+
+    proptest! {
+        #[test]
+        fn base_round_trip(
+            (suite, pk, sk) in suite_and_key_pair(),
+            info: Vec<u8>,
+            aad: Vec<u8>,
+            messages in prop::collection::vec(any::<Vec<u8>>(), 1..4),
+        ) {
+            let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, &info).unwrap();
+            let mut receiver = suite.setup_base_receiver(&enc, &sk, &info).unwrap();
+
+            for message in messages {
+                let mut ciphertext = vec![0u8; message.len() + TAG_BYTES];
+                sender.seal(&aad, &message, &mut ciphertext).unwrap();
+
+                let mut plaintext = vec![0u8; message.len()];
+                receiver.open(&aad, &ciphertext, &mut plaintext).unwrap();
+                assert_eq!(plaintext, message);
+            }
+        }
+
+        #[test]
+        fn psk_round_trip(
+            (suite, pk, sk) in suite_and_key_pair(),
+            info: Vec<u8>,
+            psk in prop::collection::vec(any::<u8>(), 32..64),
+            psk_id in prop::collection::vec(any::<u8>(), 1..16),
+            message: Vec<u8>,
+        ) {
+            let (enc, mut sender) = suite
+                .setup_psk_sender_with_default_rng(&pk, &info, &psk, &psk_id)
+                .unwrap();
+            let mut receiver = suite
+                .setup_psk_receiver(&enc, &sk, &info, &psk, &psk_id)
+                .unwrap();
+
+            let mut ciphertext = vec![0u8; message.len() + TAG_BYTES];
+            sender.seal(b"aad", &message, &mut ciphertext).unwrap();
+            let mut plaintext = vec![0u8; message.len()];
+            receiver.open(b"aad", &ciphertext, &mut plaintext).unwrap();
+            assert_eq!(plaintext, message);
+
+            let (mut sender_secret, mut receiver_secret) = ([0u8; 42], [0u8; 42]);
+            sender.export(&info, &mut sender_secret).unwrap();
+            receiver.export(&info, &mut receiver_secret).unwrap();
+            assert_eq!(sender_secret, receiver_secret);
+
+            // A different PSK gives a different key
+            let mut receiver = suite
+                .setup_psk_receiver(&enc, &sk, &info, &[0u8; 32], &psk_id)
+                .unwrap();
+            assert_eq!(
+                receiver.open(b"aad", &ciphertext, &mut plaintext),
+                Err(Error::HpkeFailure(Hpke::Open))
+            );
+        }
+
+        #[test]
+        fn modified_ciphertext(
+            (suite, pk, sk) in suite_and_key_pair(),
+            message in prop::collection::vec(any::<u8>(), 1..64),
+            index: usize,
+        ) {
+            let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, b"info").unwrap();
+            let mut ciphertext = vec![0u8; message.len() + TAG_BYTES];
+            sender.seal(b"aad", &message, &mut ciphertext).unwrap();
+            let mut plaintext = vec![0u8; message.len()];
+
+            let mut modified = ciphertext.clone();
+            modified[index % ciphertext.len()] ^= 1;
+            let mut receiver = suite.setup_base_receiver(&enc, &sk, b"info").unwrap();
+            assert_eq!(
+                receiver.open(b"aad", &modified, &mut plaintext),
+                Err(Error::HpkeFailure(Hpke::Open))
+            );
+            // Only the failed message is rejected
+            assert_eq!(plaintext, vec![0u8; message.len()]);
+            receiver.open(b"aad", &ciphertext, &mut plaintext).unwrap();
+
+            let mut receiver = suite.setup_base_receiver(&enc, &sk, b"info").unwrap();
+            assert_eq!(
+                receiver.open(b"other aad", &ciphertext, &mut plaintext),
+                Err(Error::HpkeFailure(Hpke::Open))
+            );
+
+            let mut receiver = suite.setup_base_receiver(&enc, &sk, b"other info").unwrap();
+            assert_eq!(
+                receiver.open(b"aad", &ciphertext, &mut plaintext),
+                Err(Error::HpkeFailure(Hpke::Open))
+            );
+        }
+
+        #[test]
+        fn derive_key_pair(ikm: Vec<u8>) {
+            for kem in [Kem::MlKem512, Kem::MlKem768, Kem::MlKem1024] {
+                let (pk, sk) = kem.derive_key_pair(&ikm).unwrap();
+                assert_eq!(sk.get_public_key(), pk);
+                assert_eq!(kem.derive_key_pair(&ikm).unwrap().1, sk);
+                sk.check_pairwise_consistency().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn ids() {
+        for (kem, id) in [Kem::MlKem512, Kem::MlKem768, Kem::MlKem1024]
+            .into_iter()
+            .zip([0x0040, 0x0041, 0x0042])
+        {
+            assert_eq!(kem.id(), id);
+            assert_eq!(Kem::from_id(id), Some(kem));
+        }
+        for (kdf, id) in [
+            Kdf::HkdfSha256,
+            Kdf::HkdfSha384,
+            Kdf::Shake128,
+            Kdf::Shake256,
+        ]
+        .into_iter()
+        .zip([0x0001, 0x0002, 0x0010, 0x0011])
+        {
+            assert_eq!(kdf.id(), id);
+            assert_eq!(Kdf::from_id(id), Some(kdf));
+        }
+        for (aead, id) in [Aead::Aes128Gcm, Aead::Aes256Gcm, Aead::ChaCha20Poly1305]
+            .into_iter()
+            .zip([0x0001, 0x0002, 0x0003])
+        {
+            assert_eq!(aead.id(), id);
+            assert_eq!(Aead::from_id(id), Some(aead));
+        }
+
+        // DHKEM(X25519, HKDF-SHA256), HKDF-SHA512, TurboSHAKE256 and the export only AEAD
+        assert_eq!(Kem::from_id(0x0020), None);
+        assert_eq!(Kdf::from_id(0x0003), None);
+        assert_eq!(Kdf::from_id(0x0013), None);
+        assert_eq!(Aead::from_id(0xFFFF), None);
+
+        for suite in SUITES {
+            assert_eq!(
+                Suite::from_ids(suite.kem().id(), suite.kdf().id(), suite.aead().id()),
+                Some(suite)
+            );
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        for (kem, enc, pk) in [
+            (Kem::MlKem512, 768, 800),
+            (Kem::MlKem768, 1088, 1184),
+            (Kem::MlKem1024, 1568, 1568),
+        ] {
+            assert_eq!(kem.enc_bytes(), enc);
+            assert_eq!(kem.public_key_bytes(), pk);
+        }
+    }
+
+    #[test]
+    fn parameter_mismatch() {
+        let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::Aes128Gcm);
+        let (pk, sk) = key_pair(Kem::MlKem512, [0u8; 32]);
+        let mismatch = Error::ParameterMismatch {
+            expected: 768,
+            actual: 512,
+        };
+
+        assert_eq!(
+            suite
+                .setup_base_sender_with_default_rng(&pk, b"")
+                .unwrap_err(),
+            mismatch
+        );
+
+        let (enc, _) = Suite::new(Kem::MlKem512, Kdf::HkdfSha256, Aead::Aes128Gcm)
+            .setup_base_sender_with_default_rng(&pk, b"")
+            .unwrap();
+        assert_eq!(
+            suite.setup_base_receiver(&enc, &sk, b"").unwrap_err(),
+            mismatch
+        );
+    }
+
+    #[test]
+    fn psk_inputs() {
+        let suite = Suite::new(Kem::MlKem768, Kdf::Shake256, Aead::Aes256Gcm);
+        let (pk, sk) = key_pair(Kem::MlKem768, [0u8; 32]);
+        let (enc, _) = suite.setup_base_sender_with_default_rng(&pk, b"").unwrap();
+
+        for (psk, psk_id) in [(&[][..], &b"id"[..]), (&[1u8; 32], &[]), (&[], &[])] {
+            assert_eq!(
+                suite
+                    .setup_psk_sender_with_default_rng(&pk, b"", psk, psk_id)
+                    .unwrap_err(),
+                Error::HpkeFailure(Hpke::PskInputs)
+            );
+            assert_eq!(
+                suite
+                    .setup_psk_receiver(&enc, &sk, b"", psk, psk_id)
+                    .unwrap_err(),
+                Error::HpkeFailure(Hpke::PskInputs)
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha256, Aead::ChaCha20Poly1305);
+        let (pk, sk) = key_pair(Kem::MlKem768, [0u8; 32]);
+        let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, b"").unwrap();
+        let mut receiver = suite.setup_base_receiver(&enc, &sk, b"").unwrap();
+
+        assert_eq!(
+            sender.seal(b"", b"hello", &mut [0u8; 5]),
+            Err(Error::InvalidLength {
+                expected: 5 + TAG_BYTES,
+                actual: 5,
+            })
+        );
+        assert_eq!(
+            receiver.open(b"", &[0u8; TAG_BYTES - 1], &mut []),
+            Err(Error::HpkeFailure(Hpke::Open))
+        );
+        assert_eq!(
+            receiver.open(b"", &[0u8; TAG_BYTES + 5], &mut [0u8; 4]),
+            Err(Error::InvalidLength {
+                expected: 5,
+                actual: 4,
+            })
+        );
+
+        // 255 * Nh for HKDF, and 65535 for SHAKE
+        let mut out = vec![0u8; 255 * 32 + 1];
+        assert_eq!(
+            sender.export(b"", &mut out),
+            Err(Error::InvalidLength {
+                expected: 255 * 32,
+                actual: 255 * 32 + 1,
+            })
+        );
+        sender.export(b"", &mut out[..255 * 32]).unwrap();
+
+        let suite = Suite::new(Kem::MlKem768, Kdf::Shake128, Aead::ChaCha20Poly1305);
+        let (_, sender) = suite.setup_base_sender_with_default_rng(&pk, b"").unwrap();
+        let mut out = vec![0u8; 65536];
+        assert_eq!(
+            sender.export(b"", &mut out),
+            Err(Error::InvalidLength {
+                expected: 65535,
+                actual: 65536,
+            })
+        );
+        assert_eq!(
+            suite
+                .setup_base_sender_with_default_rng(&pk, &out)
+                .unwrap_err(),
+            Error::InvalidLength {
+                expected: 65535,
+                actual: 65536,
+            }
+        );
+    }
+
+    #[test]
+    fn message_limit() {
+        let suite = Suite::new(Kem::MlKem512, Kdf::Shake256, Aead::Aes128Gcm);
+        let (pk, sk) = key_pair(Kem::MlKem512, [0u8; 32]);
+        let (enc, mut sender) = suite.setup_base_sender_with_default_rng(&pk, b"").unwrap();
+        let mut receiver = suite.setup_base_receiver(&enc, &sk, b"").unwrap();
+        sender.0.seq = u64::MAX - 1;
+        receiver.0.seq = u64::MAX - 1;
+
+        let mut ciphertext = [0u8; TAG_BYTES];
+        sender.seal(b"", b"", &mut ciphertext).unwrap();
+        receiver.open(b"", &ciphertext, &mut []).unwrap();
+
+        assert_eq!(
+            sender.seal(b"", b"", &mut ciphertext),
+            Err(Error::HpkeFailure(Hpke::MessageLimit))
+        );
+        assert_eq!(
+            receiver.open(b"", &ciphertext, &mut []),
+            Err(Error::HpkeFailure(Hpke::MessageLimit))
+        );
+    }
+
+    #[test]
+    fn debug() {
+        let suite = Suite::new(Kem::MlKem768, Kdf::HkdfSha384, Aead::Aes256Gcm);
+        let (pk, sk) = key_pair(Kem::MlKem768, [0u8; 32]);
+        let (enc, sender) = suite.setup_base_sender_with_default_rng(&pk, b"").unwrap();
+        let receiver = suite.setup_base_receiver(&enc, &sk, b"").unwrap();
+
+        assert_eq!(format!("{sender:?}"), "SenderContext { .. }");
+        assert_eq!(format!("{receiver:?}"), "ReceiverContext { .. }");
+    }
+}
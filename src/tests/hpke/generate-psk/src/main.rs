@@ -0,0 +1,125 @@
+//! Generates PSK mode test vectors for the ML-KEM HPKE suites, in the format of the hpke-pq test
+//! vectors, using the independent `hpke` crate.
+//!
+//! The `hpke` crate does not expose the key schedule's intermediate values, so the vectors have no
+//! `key`, `base_nonce`, `exporter_secret`, or per encryption `nonce`. The encryptions and exports
+//! depend on all of them.
+
+use hpke::{
+    aead::{Aead, AesGcm128, AesGcm256, ChaCha20Poly1305},
+    kdf::{HkdfSha256, HkdfSha384, Kdf, KdfShake128, KdfShake256},
+    kem::{Kem, MlKem1024, MlKem768},
+    rand_core::{TryCryptoRng, TryRng},
+    setup_receiver, setup_sender_with_rng, OpModeR, OpModeS, PskBundle, Serializable,
+};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+
+// Returns ikmE as ML-KEM's message m
+struct FixedRng<'a>(&'a [u8]);
+
+impl TryRng for FixedRng<'_> {
+    type Error = Infallible;
+
+    fn try_next_u32(&mut self) -> Result<u32, Infallible> {
+        unreachable!()
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Infallible> {
+        unreachable!()
+    }
+
+    fn try_fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), Infallible> {
+        assert!(self.0.len() >= dst.len(), "ikmE exhausted");
+        let (bytes, rest) = self.0.split_at(dst.len());
+        dst.copy_from_slice(bytes);
+        self.0 = rest;
+        Ok(())
+    }
+}
+
+impl TryCryptoRng for FixedRng<'_> {}
+
+// Deterministic test inputs, so the vectors can be regenerated
+fn bytes(label: &str, len: usize) -> Vec<u8> {
+    (0..len)
+        .map(|i| label.bytes().fold(i as u8, |acc, b| acc.wrapping_mul(31).wrapping_add(b)))
+        .collect()
+}
+
+fn vector<A: Aead, K: Kdf, M: Kem>(index: usize, info_len: usize, psk_id_len: usize) -> Value {
+    let ikm_e = bytes(&format!("ikmE {index}"), 32);
+    let ikm_r = bytes(&format!("ikmR {index}"), 32);
+    let info = bytes(&format!("info {index}"), info_len);
+    let psk = bytes(&format!("psk {index}"), 32);
+    let psk_id = bytes(&format!("psk_id {index}"), psk_id_len);
+    let bundle = PskBundle::new(&psk, &psk_id).unwrap();
+
+    let (sk, pk) = M::derive_keypair(&ikm_r);
+    let (enc, mut sender) = setup_sender_with_rng::<A, K, M>(
+        &OpModeS::Psk(bundle),
+        &pk,
+        &info,
+        &mut FixedRng(&ikm_e),
+    )
+    .unwrap();
+    let shared_secret = M::decap(&sk, None, &enc).unwrap();
+    let mut receiver = setup_receiver::<A, K, M>(&OpModeR::Psk(bundle), &sk, &enc, &info).unwrap();
+
+    let encryptions: Vec<Value> = (0..3)
+        .map(|i| {
+            let aad = format!("Count-{i}").into_bytes();
+            let pt = bytes(&format!("pt {index} {i}"), 29 + 100 * i);
+            let ct = sender.seal(&pt, &aad).unwrap();
+            assert_eq!(receiver.open(&ct, &aad).unwrap(), pt);
+            json!({ "aad": hex::encode(aad), "ct": hex::encode(ct), "pt": hex::encode(pt) })
+        })
+        .collect();
+
+    let exports: Vec<Value> = [(0, 32), (1, 48), (300, 64)]
+        .into_iter()
+        .map(|(context_len, len)| {
+            let context = bytes(&format!("exporter_context {index}"), context_len);
+            let mut exported = vec![0u8; len];
+            sender.export(&context, &mut exported).unwrap();
+            json!({
+                "exporter_context": hex::encode(context),
+                "L": len,
+                "exported_value": hex::encode(exported),
+            })
+        })
+        .collect();
+
+    json!({
+        "mode": 1,
+        "kem_id": M::KEM_ID,
+        "kdf_id": K::KDF_ID,
+        "aead_id": A::AEAD_ID,
+        "info": hex::encode(info),
+        "ikmE": hex::encode(ikm_e),
+        "ikmR": hex::encode(ikm_r),
+        "skRm": hex::encode(sk.to_bytes()),
+        "pkRm": hex::encode(pk.to_bytes()),
+        "psk": hex::encode(psk),
+        "psk_id": hex::encode(psk_id),
+        "enc": hex::encode(enc.to_bytes()),
+        "shared_secret": hex::encode(&shared_secret.0),
+        "encryptions": encryptions,
+        "exports": exports,
+    })
+}
+
+fn main() {
+    // info and psk_id lengths of 256 or more exercise both bytes of the SHAKE length prefixes
+    let vectors = [
+        vector::<AesGcm128, HkdfSha256, MlKem768>(0, 20, 10),
+        vector::<ChaCha20Poly1305, HkdfSha384, MlKem1024>(1, 300, 10),
+        vector::<AesGcm128, KdfShake128, MlKem768>(2, 20, 10),
+        vector::<AesGcm256, KdfShake128, MlKem1024>(3, 300, 260),
+        vector::<ChaCha20Poly1305, KdfShake256, MlKem768>(4, 0, 1),
+        vector::<AesGcm256, KdfShake256, MlKem1024>(5, 300, 260),
+        vector::<ChaCha20Poly1305, KdfShake256, MlKem768>(6, 20, 10),
+    ];
+
+    println!("{}", serde_json::to_string_pretty(&vectors.to_vec()).unwrap());
+}
@@ -2,7 +2,7 @@
 #[cfg(test)]
 pub(in crate::tests) mod indcpa_tests {
     use crate::{
-        indcpa::*, matrix::*, params::*, polynomials::*,
+        indcpa::*, kem::KeyDerivation, matrix::*, params::*, polynomials::*,
         tests::params::params_tests::sec_level_strategy, vectors::*,
     };
     use proptest::prelude::*;
@@ -21,7 +21,7 @@ pub(in crate::tests) mod indcpa_tests {
         fn new_indcpa_keypair()
             (key_seed in prop::array::uniform32(u8::MIN..u8::MAX), sec_level in sec_level_strategy())
             -> (PrivateKey, PublicKey) {
-                generate_indcpa_key_pair(&key_seed, sec_level).unwrap()
+                generate_indcpa_key_pair(&key_seed, sec_level, KeyDerivation::Fips203).unwrap()
             }
     }
     // #[test]
@@ -78,7 +78,6 @@ pub(in crate::tests) mod indcpa_tests {
             assert_eq!(message, plaintext, "security level: {:?}", sec_level);
         }
 
-        #[cfg(feature = "decap_key")]
         #[test]
         fn key_pack_unpack(
             (priv_key, pub_key) in new_indcpa_keypair(),
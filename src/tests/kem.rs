@@ -1,27 +1,63 @@
 #![allow(warnings)]
 #[cfg(test)]
 mod kem_tests {
-    use crate::{kem::*, params::SecurityLevel, tests::params::params_tests::sec_level_strategy};
+    extern crate std;
+
+    use crate::{
+        errors::{Error, Validation},
+        kem::*,
+        params::{SecurityLevel, K},
+        tests::params::params_tests::sec_level_strategy,
+    };
     use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{RngCore, SeedableRng};
+    use std::format;
+    use subtle::ConstantTimeEq;
+    use zeroize::Zeroize;
+
+    prop_compose! {
+        fn new_seed()
+            (d in prop::array::uniform32(u8::MIN..u8::MAX), z in prop::array::uniform32(u8::MIN..u8::MAX))
+            -> [u8; 64] {
+                let mut seed = [0u8; 64];
+                seed[..32].copy_from_slice(&d);
+                seed[32..].copy_from_slice(&z);
+                seed
+            }
+    }
 
     prop_compose! {
         fn new_keypair()
             (sec_level in sec_level_strategy())
             -> (PublicKey, PrivateKey) {
-                generate_key_pair(None, sec_level.k()).unwrap()
+                generate_key_pair(&mut ChaCha20Rng::from_entropy(), sec_level.k()).unwrap()
             }
     }
 
     proptest! {
         #[test]
         fn encapsulate_decapsulate((pk, sk) in new_keypair()) {
-            let (ciphertext, shared_secret) = pk.encapsulate(None, None).unwrap();
+            let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
 
-            let decap_secret = sk.decapsulate(ciphertext.as_bytes()).unwrap();
+            let decap_secret = sk.decapsulate(&ciphertext).unwrap();
 
             assert_eq!(shared_secret, decap_secret);
         }
 
+        #[test]
+        fn encapsulate_rng((pk, sk) in new_keypair(), rng_seed in prop::array::uniform32(u8::MIN..u8::MAX)) {
+            let (ciphertext, shared_secret) = pk.encapsulate(&mut ChaCha20Rng::from_seed(rng_seed)).unwrap();
+
+            let mut m = [0u8; 32];
+            ChaCha20Rng::from_seed(rng_seed).fill_bytes(&mut m);
+            let (deterministic_ciphertext, deterministic_secret) = pk.encapsulate_deterministic(&m).unwrap();
+
+            assert_eq!(ciphertext, deterministic_ciphertext);
+            assert_eq!(shared_secret, deterministic_secret);
+            assert_eq!(sk.decapsulate(&ciphertext).unwrap(), shared_secret);
+        }
+
         #[test]
         fn pack_unpack((pk, sk) in new_keypair()) {
             let mut pk_bytes = [0u8; 1568];
@@ -29,25 +65,336 @@ mod kem_tests {
             let unpacked_pk = PublicKey::unpack(&pk_bytes[..pk.sec_level().public_key_bytes()]).unwrap();
             assert_eq!(pk, unpacked_pk);
 
-            #[cfg(feature = "decap_key")]
-            {
-                let mut sk_bytes = [0u8; 3168];
-                sk.pack(&mut sk_bytes[..pk.sec_level().private_key_bytes()]);
-                let unpacked_sk = PrivateKey::unpack(&sk_bytes[..sk.sec_level().private_key_bytes()]).unwrap();
-                assert_eq!(sk, unpacked_sk);
+            let mut sk_bytes = [0u8; 3168];
+            sk.to_expanded_bytes(&mut sk_bytes[..sk.sec_level().private_key_bytes()]).unwrap();
+            let unpacked_sk = PrivateKey::from_expanded_bytes(&sk_bytes[..sk.sec_level().private_key_bytes()]).unwrap();
+            assert_eq!(sk, unpacked_sk);
+            assert_eq!(unpacked_sk.to_seed(), None);
+
+            let seed = sk.to_seed().unwrap();
+            let unpacked_sk = match pk.sec_level() {
+                SecurityLevel::FiveOneTwo { .. } => PrivateKey::from_seed_512(seed),
+                SecurityLevel::SevenSixEight { .. } => PrivateKey::from_seed_768(seed),
+                SecurityLevel::TenTwoFour { .. } => PrivateKey::from_seed_1024(seed),
+            };
+            assert_eq!(sk, unpacked_sk);
+        }
+
+        #[test]
+        fn seed_and_expanded_formats((pk, sk) in new_keypair()) {
+            let len = sk.sec_level().private_key_bytes();
+            let mut sk_bytes = [0u8; 3168];
+            sk.to_expanded_bytes(&mut sk_bytes[..len]).unwrap();
+
+            // A key loaded from its seed can still produce the expanded form, without expanding
+            let seeded_sk = PrivateKey::from_seed(sk.to_seed().unwrap(), sk.sec_level());
+            let mut seeded_bytes = [0u8; 3168];
+            seeded_sk.to_expanded_bytes(&mut seeded_bytes[..len]).unwrap();
+            assert!(!seeded_sk.is_expanded());
+            assert_eq!(sk_bytes, seeded_bytes);
+
+            let expanded_sk = PrivateKey::from_expanded_bytes(&sk_bytes[..len]).unwrap();
+            assert!(expanded_sk.is_expanded());
+            assert_eq!(expanded_sk.derivation(), None);
+            assert_eq!(expanded_sk, seeded_sk);
+            assert_eq!(expanded_sk.get_public_key(), pk);
+
+            let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+            assert_eq!(expanded_sk.decapsulate(&ciphertext).unwrap(), shared_secret);
+
+            assert!(matches!(
+                sk.to_expanded_bytes(&mut sk_bytes[..len - 1]),
+                Err(Error::InvalidLength { expected, actual }) if expected == len && actual == len - 1
+            ));
+        }
+
+        #[test]
+        fn private_key_inequality((_, sk) in new_keypair(), index in 0..3168usize) {
+            let (_, other_sk) = generate_key_pair(&mut ChaCha20Rng::from_entropy(), sk.sec_level().k()).unwrap();
+            assert_ne!(sk, other_sk);
+
+            // A single changed byte of the expanded key, including z
+            let len = sk.sec_level().private_key_bytes();
+            let mut sk_bytes = [0u8; 3168];
+            sk.to_expanded_bytes(&mut sk_bytes[..len]).unwrap();
+            let index = index % len;
+            let h_pk = len - 64..len - 32;
+            prop_assume!(!h_pk.contains(&index));
+            sk_bytes[index] ^= 1;
+            if let Ok(modified_sk) = PrivateKey::from_expanded_bytes(&sk_bytes[..len]) {
+                assert_ne!(sk, modified_sk);
             }
-            #[cfg(not(feature = "decap_key"))]
-            {
-                let sk_bytes = sk.pack();
-                let unpacked_sk = match pk.sec_level() {
-                    SecurityLevel::FiveOneTwo { .. } => PrivateKey::unpack_512(sk_bytes),
-                    SecurityLevel::SevenSixEight { .. } => PrivateKey::unpack_768(sk_bytes),
-                    SecurityLevel::TenTwoFour { .. } => PrivateKey::unpack_1024(sk_bytes),
-                };
-
-                assert_eq!(sk, unpacked_sk);
+        }
+
+        #[test]
+        fn ciphertext_from_bytes((pk, sk) in new_keypair()) {
+            let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+
+            let parsed = Ciphertext::from_bytes(ciphertext.as_bytes()).unwrap();
+            assert_eq!(parsed, ciphertext);
+            assert_eq!(Ciphertext::try_from(ciphertext.as_ref()).unwrap(), ciphertext);
+            assert_eq!(sk.decapsulate(&parsed).unwrap(), shared_secret);
+        }
+
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn to_vec((pk, sk) in new_keypair()) {
+            let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+            assert_eq!(ciphertext.to_vec(), ciphertext.as_bytes());
+            assert_eq!(PublicKey::unpack(&pk.to_vec()).unwrap(), pk);
+            assert_eq!(PrivateKey::from_expanded_bytes(&sk.to_vec()).unwrap(), sk);
+        }
+
+        #[test]
+        fn shared_secret((pk, sk) in new_keypair()) {
+            let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+            let decap_secret = sk.decapsulate(&ciphertext).unwrap();
+            assert!(bool::from(shared_secret.ct_eq(&decap_secret)));
+            assert_eq!(shared_secret.as_bytes(), decap_secret.as_bytes());
+
+            assert_eq!(format!("{shared_secret:?}"), "SharedSecret([REDACTED])");
+
+            let (_, other_secret) = pk.encapsulate_with_default_rng().unwrap();
+            assert!(!bool::from(shared_secret.ct_eq(&other_secret)));
+
+            let mut zeroised = shared_secret.clone();
+            zeroised.zeroize();
+            assert_eq!(zeroised.as_bytes(), &[0u8; 32]);
+        }
+
+        #[test]
+        fn private_key_debug_redacted((_, sk) in new_keypair()) {
+            assert_eq!(
+                format!("{sk:?}"),
+                format!("PrivateKey {{ sec_level: {:?}, key: \"[REDACTED]\", .. }}", sk.get_public_key().sec_level())
+            );
+        }
+
+        #[test]
+        fn ciphertext_invalid_length(len in 0..2000usize) {
+            prop_assume!(![768, 1088, 1568].contains(&len));
+            let bytes = [0u8; 2000];
+
+            assert_eq!(
+                Ciphertext::from_bytes(&bytes[..len]),
+                Err(Error::UnsupportedLength { actual: len })
+            );
+        }
+
+        #[test]
+        fn unpack_invalid_length(len in 0..2000usize) {
+            prop_assume!(![800, 1184, 1568].contains(&len));
+            let bytes = [0u8; 2000];
+
+            assert_eq!(
+                PublicKey::unpack(&bytes[..len]),
+                Err(Error::UnsupportedLength { actual: len })
+            );
+        }
+
+        #[test]
+        fn expanded_private_key_invalid_length(len in 0..4000usize) {
+            prop_assume!(![1632, 2400, 3168].contains(&len));
+            let bytes = [0u8; 4000];
+
+            assert!(matches!(
+                PrivateKey::from_expanded_bytes(&bytes[..len]),
+                Err(Error::UnsupportedLength { actual }) if actual == len
+            ));
+        }
+
+        #[test]
+        fn unpack_unreduced_coefficient(
+            (pk, _) in new_keypair(),
+            index in 0..1024usize,
+            coeff in 3329..4096u16,
+        ) {
+            let len = pk.sec_level().public_key_bytes();
+            let mut pk_bytes = [0u8; 1568];
+            pk.pack(&mut pk_bytes[..len]).unwrap();
+
+            // Overwrite a single 12-bit coefficient of t with a value >= q
+            let index = index % ((len - 32) / 384 * 256);
+            let offset = 3 * (index / 2);
+            if index % 2 == 0 {
+                pk_bytes[offset] = coeff as u8;
+                pk_bytes[offset + 1] = (pk_bytes[offset + 1] & 0xf0) | (coeff >> 8) as u8;
+            } else {
+                pk_bytes[offset + 1] = (pk_bytes[offset + 1] & 0x0f) | (coeff << 4) as u8;
+                pk_bytes[offset + 2] = (coeff >> 4) as u8;
             }
 
+            assert!(matches!(
+                PublicKey::unpack(&pk_bytes[..len]),
+                Err(Error::InvalidEncoding)
+            ));
+        }
+
+        #[test]
+        fn unpack_corrupted_private_key((pk, sk) in new_keypair(), bit in 0..256usize) {
+            let sec_level = sk.sec_level();
+            let len = sec_level.private_key_bytes();
+            let ek_offset = sec_level.indcpa_private_key_bytes();
+            let h_offset = ek_offset + sec_level.public_key_bytes();
+            let mut sk_bytes = [0u8; 3168];
+            sk.to_expanded_bytes(&mut sk_bytes[..len]).unwrap();
+
+            let mut bad_hash = sk_bytes;
+            bad_hash[h_offset + bit / 8] ^= 1 << (bit % 8);
+            assert!(matches!(
+                PrivateKey::from_expanded_bytes(&bad_hash[..len]),
+                Err(Error::ValidationFailure(Validation::PrivateKeyHash))
+            ));
+
+            // Flip a bit of rho, so that the embedded ek still passes the modulus check
+            let mut bad_ek = sk_bytes;
+            bad_ek[h_offset - 32 + bit / 8] ^= 1 << (bit % 8);
+            assert!(matches!(
+                PrivateKey::from_expanded_bytes(&bad_ek[..len]),
+                Err(Error::ValidationFailure(Validation::PrivateKeyHash))
+            ));
+
+            // First coefficient of the embedded t set to 4095
+            let mut unreduced_ek = sk_bytes;
+            unreduced_ek[ek_offset] = 0xff;
+            unreduced_ek[ek_offset + 1] |= 0x0f;
+            assert!(matches!(
+                PrivateKey::from_expanded_bytes(&unreduced_ek[..len]),
+                Err(Error::InvalidEncoding)
+            ));
+        }
+
+        #[test]
+        fn pairwise_consistency((pk, sk) in new_keypair()) {
+            let sec_level = sk.sec_level();
+            let len = sec_level.private_key_bytes();
+            let mut sk_bytes = [0u8; 3168];
+            sk.to_expanded_bytes(&mut sk_bytes[..len]).unwrap();
+            assert!(PrivateKey::from_expanded_bytes(&sk_bytes[..len]).unwrap().check_pairwise_consistency().is_ok());
+
+            // Replace the secret vector with one from an unrelated key of the same level
+            let (_, other_sk) = generate_key_pair(&mut ChaCha20Rng::from_entropy(), sec_level.k()).unwrap();
+            let mut other_bytes = [0u8; 3168];
+            other_sk.to_expanded_bytes(&mut other_bytes[..len]).unwrap();
+            let secret_len = sec_level.indcpa_private_key_bytes();
+            sk_bytes[..secret_len].copy_from_slice(&other_bytes[..secret_len]);
+
+            let mismatched_sk = PrivateKey::from_expanded_bytes(&sk_bytes[..len]).unwrap();
+            assert!(matches!(
+                mismatched_sk.check_pairwise_consistency(),
+                Err(Error::ValidationFailure(Validation::PairwiseConsistency))
+            ));
+        }
+
+        #[test]
+        fn expand((pk, sk) in new_keypair()) {
+            assert!(sk.is_expanded());
+
+            let seed = sk.to_seed().unwrap();
+            let mut unpacked_sk = match pk.sec_level() {
+                SecurityLevel::FiveOneTwo { .. } => PrivateKey::from_seed_512(seed),
+                SecurityLevel::SevenSixEight { .. } => PrivateKey::from_seed_768(seed),
+                SecurityLevel::TenTwoFour { .. } => PrivateKey::from_seed_1024(seed),
+            };
+            assert!(!unpacked_sk.is_expanded());
+            assert_eq!(unpacked_sk, sk);
+
+            let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+            assert_eq!(unpacked_sk.decapsulate(&ciphertext).unwrap(), shared_secret);
+
+            unpacked_sk.expand().unwrap();
+            assert!(unpacked_sk.is_expanded());
+            assert_eq!(unpacked_sk, sk);
+            assert_eq!(unpacked_sk.get_public_key(), pk);
+            assert_eq!(unpacked_sk.decapsulate(&ciphertext).unwrap(), shared_secret);
+        }
+
+        #[test]
+        fn derivations_differ(seed in new_seed(), sec_level in sec_level_strategy()) {
+            let (pk, _) = key_pair_from_seed(seed, sec_level, KeyDerivation::Fips203).unwrap();
+            let (legacy_pk, _) = key_pair_from_seed(seed, sec_level, KeyDerivation::Fips203Ipd).unwrap();
+
+            assert_ne!(pk, legacy_pk);
+        }
+
+        #[test]
+        fn migrate_current_seed(seed in new_seed(), sec_level in sec_level_strategy()) {
+            let (pk, sk) = key_pair_from_seed(seed, sec_level, KeyDerivation::Fips203).unwrap();
+            let migrated_sk = PrivateKey::migrate_seed(seed, &pk).unwrap();
+
+            assert_eq!(sk, migrated_sk);
+            assert_eq!(migrated_sk.derivation(), Some(KeyDerivation::Fips203));
+        }
+
+        #[test]
+        fn migrate_legacy_seed(seed in new_seed(), sec_level in sec_level_strategy()) {
+            let (legacy_pk, _) = key_pair_from_seed(seed, sec_level, KeyDerivation::Fips203Ipd).unwrap();
+            let sk = PrivateKey::migrate_seed(seed, &legacy_pk).unwrap();
+
+            assert_eq!(sk.derivation(), Some(KeyDerivation::Fips203Ipd));
+            assert_eq!(sk.get_public_key(), legacy_pk);
+
+            let (ciphertext, shared_secret) = legacy_pk.encapsulate_with_default_rng().unwrap();
+            let decap_secret = sk.decapsulate(&ciphertext).unwrap();
+
+            assert_eq!(shared_secret, decap_secret);
+        }
+
+        // A legacy seed would load as a different keypair, so is only exported in expanded form
+        #[test]
+        fn legacy_seed_not_exported(seed in new_seed(), sec_level in sec_level_strategy()) {
+            let (legacy_pk, _) = key_pair_from_seed(seed, sec_level, KeyDerivation::Fips203Ipd).unwrap();
+            let sk = PrivateKey::migrate_seed(seed, &legacy_pk).unwrap();
+            assert_eq!(sk.to_seed(), None);
+
+            let len = sec_level.private_key_bytes();
+            let mut sk_bytes = [0u8; 3168];
+            sk.to_expanded_bytes(&mut sk_bytes[..len]).unwrap();
+            let expanded_sk = PrivateKey::from_expanded_bytes(&sk_bytes[..len]).unwrap();
+            assert_eq!(expanded_sk.get_public_key(), legacy_pk);
+        }
+
+        #[test]
+        fn keypair_from_seed(seed in new_seed(), sec_level in sec_level_strategy()) {
+            let d: [u8; 32] = seed[..32].try_into().unwrap();
+            let z: [u8; 32] = seed[32..].try_into().unwrap();
+            let generate = match sec_level.k() {
+                K::Two => generate_keypair_from_seed_512,
+                K::Three => generate_keypair_from_seed_768,
+                K::Four => generate_keypair_from_seed_1024,
+            };
+            let (pk, sk) = generate(&d, &z).unwrap();
+
+            assert_eq!(pk.sec_level(), sec_level);
+            assert_eq!(sk.to_seed(), Some(seed));
+            assert_eq!(generate(&d, &z).unwrap(), (pk, sk));
+        }
+
+        #[test]
+        fn migrate_unrelated_seed(seed in new_seed(), (pk, _) in new_keypair()) {
+            assert!(PrivateKey::migrate_seed(seed, &pk).is_err());
+        }
+    }
+
+    #[test]
+    fn decapsulate_mismatched_security_level() {
+        let keypairs = [K::Two, K::Three, K::Four]
+            .map(|k| generate_key_pair(&mut ChaCha20Rng::from_entropy(), k).unwrap());
+
+        for (_, sk) in &keypairs {
+            for (pk, _) in &keypairs {
+                let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+                let result = sk.decapsulate(&ciphertext);
+
+                if pk.sec_level() == sk.get_public_key().sec_level() {
+                    assert_eq!(result.unwrap(), shared_secret);
+                } else {
+                    assert!(matches!(
+                        result,
+                        Err(error) if error == Error::parameter_mismatch(sk.sec_level(), pk.sec_level())
+                    ));
+                }
+            }
         }
     }
 }
@@ -0,0 +1,209 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "nist")]
+mod nist_tests {
+    extern crate std;
+
+    use crate::{errors::Error, hybrid::nist::*, kem};
+    use p256::elliptic_curve::{
+        ecdh,
+        sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint},
+        AffinePoint, CurveArithmetic, FieldBytesSize, PublicKey as EcPublicKey, SecretKey,
+    };
+    use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+    use std::{format, vec, vec::Vec};
+
+    fn round_trip<C: Curve, P: ParameterSet>(seed: [u8; 32]) {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let (pk, sk) = NistHybrid::<C, P>::generate_keypair(&mut rng).unwrap();
+        assert_eq!(sk.get_public_key(), pk);
+
+        let (ciphertext, shared_secret) = pk.encapsulate(&mut rng).unwrap();
+        assert_eq!(sk.decapsulate(&ciphertext).unwrap(), shared_secret);
+
+        let mut pk_bytes = vec![0u8; PublicKey::<C, P>::BYTES];
+        pk.pack(&mut pk_bytes).unwrap();
+        assert_eq!(PublicKey::<C, P>::unpack(&pk_bytes).unwrap(), pk);
+
+        let mut sk_bytes = vec![0u8; PrivateKey::<C, P>::BYTES];
+        sk.pack(&mut sk_bytes).unwrap();
+        assert_eq!(PrivateKey::<C, P>::unpack(&sk_bytes).unwrap(), sk);
+
+        let mut ct_bytes = vec![0u8; Ciphertext::<C, P>::BYTES];
+        ciphertext.pack(&mut ct_bytes).unwrap();
+        assert_eq!(
+            Ciphertext::<C, P>::try_from(ct_bytes.as_slice()).unwrap(),
+            ciphertext
+        );
+    }
+
+    // The ECDH shared secret followed by the ML-KEM shared secret, each recomputed from the
+    // component encodings
+    fn concatenation_order<C: Curve, P: ParameterSet, E>(seed: [u8; 32])
+    where
+        E: CurveArithmetic,
+        AffinePoint<E>: FromEncodedPoint<E> + ToEncodedPoint<E>,
+        FieldBytesSize<E>: ModulusSize,
+    {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let (pk, sk) = NistHybrid::<C, P>::generate_keypair(&mut rng).unwrap();
+        let (ciphertext, shared_secret) = pk.encapsulate(&mut rng).unwrap();
+
+        let mut sk_bytes = vec![0u8; PrivateKey::<C, P>::BYTES];
+        sk.pack(&mut sk_bytes).unwrap();
+        let mut pk_bytes = vec![0u8; PublicKey::<C, P>::BYTES];
+        pk.pack(&mut pk_bytes).unwrap();
+        let mut ct_bytes = vec![0u8; Ciphertext::<C, P>::BYTES];
+        ciphertext.pack(&mut ct_bytes).unwrap();
+
+        let (sk_c, sk_m) = sk_bytes.split_at(C::PRIVATE_KEY_BYTES);
+        let (pk_c, pk_m) = pk_bytes.split_at(C::PUBLIC_KEY_BYTES);
+        let (ct_c, ct_m) = ct_bytes.split_at(C::CIPHERTEXT_BYTES);
+
+        // Uncompressed points
+        assert_eq!(pk_c[0], 0x04);
+        assert_eq!(ct_c[0], 0x04);
+
+        let ec_sk = SecretKey::<E>::from_slice(sk_c).unwrap();
+        assert_eq!(
+            EcPublicKey::<E>::from_sec1_bytes(pk_c).unwrap(),
+            ec_sk.public_key()
+        );
+        let ss_c = ecdh::diffie_hellman(
+            ec_sk.to_nonzero_scalar(),
+            EcPublicKey::<E>::from_sec1_bytes(ct_c).unwrap().as_affine(),
+        );
+
+        let kem_sk = kem::PrivateKey::from_expanded_bytes(sk_m).unwrap();
+        let mut kem_pk = vec![0u8; pk_m.len()];
+        kem_sk.get_public_key().pack(&mut kem_pk).unwrap();
+        assert_eq!(kem_pk, pk_m);
+        let ss_m = kem_sk
+            .decapsulate(&kem::Ciphertext::from_bytes(ct_m).unwrap())
+            .unwrap();
+
+        let mut expected = ss_c.raw_secret_bytes().to_vec();
+        expected.extend_from_slice(ss_m.as_bytes());
+        assert_eq!(shared_secret.as_bytes(), expected);
+    }
+
+    proptest! {
+        #[test]
+        fn p256_mlkem768_round_trip(seed in prop::array::uniform32(any::<u8>())) {
+            round_trip::<P256, MlKem768>(seed);
+        }
+
+        #[test]
+        fn p384_mlkem1024_round_trip(seed in prop::array::uniform32(any::<u8>())) {
+            round_trip::<P384, MlKem1024>(seed);
+        }
+
+        #[test]
+        fn p256_mlkem768_concatenation_order(seed in prop::array::uniform32(any::<u8>())) {
+            concatenation_order::<P256, MlKem768, p256::NistP256>(seed);
+        }
+
+        #[test]
+        fn p384_mlkem1024_concatenation_order(seed in prop::array::uniform32(any::<u8>())) {
+            concatenation_order::<P384, MlKem1024, p384::NistP384>(seed);
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(PublicKey::<P256, MlKem768>::BYTES, 1249);
+        assert_eq!(Ciphertext::<P256, MlKem768>::BYTES, 1153);
+        assert_eq!(PrivateKey::<P256, MlKem768>::BYTES, 32 + 2400);
+        assert_eq!(PublicKey::<P384, MlKem1024>::BYTES, 1665);
+        assert_eq!(Ciphertext::<P384, MlKem1024>::BYTES, 1665);
+        assert_eq!(PrivateKey::<P384, MlKem1024>::BYTES, 48 + 3168);
+
+        let (pk, _) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+        let (_, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+        assert_eq!(shared_secret.as_bytes().len(), 64);
+
+        let (pk, _) = SecP384r1MlKem1024::generate_keypair_with_default_rng().unwrap();
+        let (_, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+        assert_eq!(shared_secret.as_bytes().len(), 80);
+    }
+
+    #[test]
+    fn invalid_points() {
+        let (pk, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+        let mut pk_bytes = [0u8; 1249];
+        pk.pack(&mut pk_bytes).unwrap();
+
+        // Compressed point tag
+        let mut compressed = pk_bytes;
+        compressed[0] = 0x02;
+        assert_eq!(
+            PublicKey::<P256, MlKem768>::unpack(&compressed),
+            Err(Error::InvalidEncoding)
+        );
+
+        // Not on the curve
+        let mut off_curve = pk_bytes;
+        off_curve[64] ^= 1;
+        assert_eq!(
+            PublicKey::<P256, MlKem768>::unpack(&off_curve),
+            Err(Error::InvalidEncoding)
+        );
+
+        let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+        let mut ct_bytes = [0u8; 1153];
+        ciphertext.pack(&mut ct_bytes).unwrap();
+        ct_bytes[1] ^= 1;
+        assert_eq!(
+            Ciphertext::<P256, MlKem768>::from_bytes(&ct_bytes),
+            Err(Error::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn invalid_private_scalar() {
+        let (_, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+        let mut sk_bytes = [0u8; 2432];
+        sk.pack(&mut sk_bytes).unwrap();
+
+        // Zero, and the curve order
+        sk_bytes[..32].fill(0);
+        assert_eq!(
+            PrivateKey::<P256, MlKem768>::unpack(&sk_bytes),
+            Err(Error::InvalidEncoding)
+        );
+        sk_bytes[..32].fill(0xff);
+        assert_eq!(
+            PrivateKey::<P256, MlKem768>::unpack(&sk_bytes),
+            Err(Error::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        assert_eq!(
+            PublicKey::<P384, MlKem1024>::unpack(&[0u8; 1568]),
+            Err(Error::InvalidLength {
+                expected: 1665,
+                actual: 1568
+            })
+        );
+        assert_eq!(
+            Ciphertext::<P256, MlKem768>::from_bytes(&[0u8; 1088]),
+            Err(Error::InvalidLength {
+                expected: 1153,
+                actual: 1088
+            })
+        );
+    }
+
+    #[test]
+    fn debug_is_redacted() {
+        let (pk, sk) = SecP256r1MlKem768::generate_keypair_with_default_rng().unwrap();
+        let (_, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+
+        assert_eq!(format!("{sk:?}"), "PrivateKey { .. }");
+        assert_eq!(format!("{shared_secret:?}"), "SharedSecret { .. }");
+    }
+}
@@ -0,0 +1,237 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(all(feature = "pkcs8", feature = "alloc"))]
+mod pkcs8_tests {
+    extern crate std;
+
+    use crate::{
+        kem::*,
+        params::{SecurityLevel, K},
+        tests::params::params_tests::sec_level_strategy,
+    };
+    use ::pkcs8::{
+        der::{
+            asn1::{BitStringRef, OctetStringRef},
+            Encode,
+        },
+        spki::{self, AlgorithmIdentifierRef, SubjectPublicKeyInfoRef},
+        ObjectIdentifier, PrivateKeyInfoRef,
+    };
+    use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+    use std::{vec, vec::Vec};
+
+    const ID_ALG_ML_KEM: [&str; 3] = [
+        "2.16.840.1.101.3.4.4.1",
+        "2.16.840.1.101.3.4.4.2",
+        "2.16.840.1.101.3.4.4.3",
+    ];
+
+    // Reference keys from https://github.com/lamps-wg/kyber-certificates, generated from the seed
+    // 0, 1, ..., 63
+    const LAMPS_KEYS: [(&str, &str); 3] = [
+        (
+            include_str!("lamps/ML-KEM-512.pub"),
+            include_str!("lamps/ML-KEM-512-seed.priv"),
+        ),
+        (
+            include_str!("lamps/ML-KEM-768.pub"),
+            include_str!("lamps/ML-KEM-768-seed.priv"),
+        ),
+        (
+            include_str!("lamps/ML-KEM-1024.pub"),
+            include_str!("lamps/ML-KEM-1024-seed.priv"),
+        ),
+    ];
+
+    fn algorithm(sec_level: SecurityLevel) -> AlgorithmIdentifierRef<'static> {
+        let index = usize::from(sec_level.k()) - 2;
+        AlgorithmIdentifierRef {
+            oid: ObjectIdentifier::new_unwrap(ID_ALG_ML_KEM[index]),
+            parameters: None,
+        }
+    }
+
+    fn expanded_bytes(sk: &PrivateKey) -> Vec<u8> {
+        let mut bytes = vec![0u8; sk.sec_level().private_key_bytes()];
+        sk.to_expanded_bytes(&mut bytes).unwrap();
+        bytes
+    }
+
+    // PKCS#8 document with the given `ML-KEM-PrivateKey` encoding
+    fn private_key_info(sec_level: SecurityLevel, private_key: &[u8]) -> Vec<u8> {
+        PrivateKeyInfoRef::new(
+            algorithm(sec_level),
+            OctetStringRef::new(private_key).unwrap(),
+        )
+        .to_der()
+        .unwrap()
+    }
+
+    fn expanded_form(sk: &PrivateKey) -> Vec<u8> {
+        OctetStringRef::new(&expanded_bytes(sk))
+            .unwrap()
+            .to_der()
+            .unwrap()
+    }
+
+    fn both_form(seed: &[u8; 64], sk: &PrivateKey) -> Vec<u8> {
+        let mut content = OctetStringRef::new(seed).unwrap().to_der().unwrap();
+        content.extend(expanded_form(sk));
+
+        // SEQUENCE header, with a two byte length
+        let len = content.len();
+        let mut both = vec![0x30, 0x82, (len >> 8) as u8, len as u8];
+        both.extend(content);
+        both
+    }
+
+    prop_compose! {
+        fn new_keypair()
+            (sec_level in sec_level_strategy())
+            -> (PublicKey, PrivateKey) {
+                crate::kem::generate_key_pair(&mut ChaCha20Rng::from_entropy(), sec_level.k()).unwrap()
+            }
+    }
+
+    proptest! {
+        #[test]
+        fn public_key_round_trip((pk, _) in new_keypair()) {
+            let der = pk.to_public_key_der().unwrap();
+
+            assert_eq!(PublicKey::from_public_key_der(der.as_bytes()).unwrap(), pk);
+            assert_eq!(der.as_bytes().len(), pk.sec_level().public_key_bytes() + 22);
+        }
+
+        #[test]
+        fn seed_round_trip((pk, sk) in new_keypair()) {
+            let der = sk.to_pkcs8_der().unwrap();
+            // Seed form, with a 22 byte header
+            assert_eq!(der.as_bytes().len(), 64 + 22);
+
+            let parsed = PrivateKey::from_pkcs8_der(der.as_bytes()).unwrap();
+            assert_eq!(parsed.to_seed(), sk.to_seed());
+            assert_eq!(parsed.get_public_key(), pk);
+        }
+
+        #[test]
+        fn expanded_round_trip((pk, sk) in new_keypair()) {
+            let expanded_sk = PrivateKey::from_expanded_bytes(&expanded_bytes(&sk)).unwrap();
+            let der = expanded_sk.to_pkcs8_der().unwrap();
+            assert_eq!(der.as_bytes(), private_key_info(sk.sec_level(), &expanded_form(&sk)));
+
+            let parsed = PrivateKey::from_pkcs8_der(der.as_bytes()).unwrap();
+            assert_eq!(parsed.to_seed(), None);
+            assert_eq!(parsed, sk);
+            assert_eq!(parsed.get_public_key(), pk);
+        }
+
+        #[test]
+        fn both_forms((pk, sk) in new_keypair()) {
+            let seed = sk.to_seed().unwrap();
+            let der = private_key_info(sk.sec_level(), &both_form(&seed, &sk));
+
+            let parsed = PrivateKey::from_pkcs8_der(&der).unwrap();
+            assert!(parsed.is_expanded());
+            assert_eq!(parsed.to_seed(), Some(seed));
+            assert_eq!(parsed.get_public_key(), pk);
+
+            // Expanded key from a different seed
+            let (_, other_sk) = crate::kem::generate_key_pair(&mut ChaCha20Rng::from_entropy(), sk.sec_level().k()).unwrap();
+            let der = private_key_info(sk.sec_level(), &both_form(&seed, &other_sk));
+            assert!(PrivateKey::from_pkcs8_der(&der).is_err());
+        }
+
+        #[test]
+        fn mismatched_parameter_set((pk, sk) in new_keypair()) {
+            let other_level = SecurityLevel::new(match sk.sec_level().k() {
+                K::Two => K::Three,
+                K::Three => K::Four,
+                K::Four => K::Two,
+            });
+
+            let der = private_key_info(other_level, &expanded_form(&sk));
+            assert!(PrivateKey::from_pkcs8_der(&der).is_err());
+
+            let seed = sk.to_seed().unwrap();
+            let der = private_key_info(other_level, &both_form(&seed, &sk));
+            assert!(PrivateKey::from_pkcs8_der(&der).is_err());
+
+            let mut pk_bytes = vec![0u8; pk.sec_level().public_key_bytes()];
+            pk.pack(&mut pk_bytes).unwrap();
+            let der = SubjectPublicKeyInfoRef {
+                algorithm: algorithm(other_level),
+                subject_public_key: BitStringRef::new(0, &pk_bytes).unwrap(),
+            }
+            .to_der()
+            .unwrap();
+            assert!(PublicKey::from_public_key_der(&der).is_err());
+        }
+
+        #[test]
+        fn mismatched_public_key((_, sk) in new_keypair()) {
+            let (other_pk, _) = crate::kem::generate_key_pair(&mut ChaCha20Rng::from_entropy(), sk.sec_level().k()).unwrap();
+            let mut pk_bytes = vec![0u8; other_pk.sec_level().public_key_bytes()];
+            other_pk.pack(&mut pk_bytes).unwrap();
+
+            let expanded = expanded_form(&sk);
+            let mut private_key_info = PrivateKeyInfoRef::new(
+                algorithm(sk.sec_level()),
+                OctetStringRef::new(&expanded).unwrap(),
+            );
+            private_key_info.public_key = Some(BitStringRef::new(0, &pk_bytes).unwrap());
+            assert!(PrivateKey::from_pkcs8_der(&private_key_info.to_der().unwrap()).is_err());
+
+            let mut pk_bytes = vec![0u8; other_pk.sec_level().public_key_bytes()];
+            sk.get_public_key().pack(&mut pk_bytes).unwrap();
+            private_key_info.public_key = Some(BitStringRef::new(0, &pk_bytes).unwrap());
+            assert_eq!(PrivateKey::from_pkcs8_der(&private_key_info.to_der().unwrap()).unwrap(), sk);
+        }
+    }
+
+    #[test]
+    fn unknown_algorithm() {
+        let (pk, sk) = generate_keypair_768_with_default_rng().unwrap();
+        let mut der = sk.to_pkcs8_der().unwrap().as_bytes().to_vec();
+        // Last arc of the OID, giving the unassigned 2.16.840.1.101.3.4.4.4
+        der[17] = 0x04;
+        assert!(matches!(
+            PrivateKey::from_pkcs8_der(&der),
+            Err(::pkcs8::Error::PublicKey(spki::Error::OidUnknown { .. }))
+        ));
+
+        let mut der = pk.to_public_key_der().unwrap().as_bytes().to_vec();
+        der[16] = 0x04;
+        assert!(matches!(
+            PublicKey::from_public_key_der(&der),
+            Err(spki::Error::OidUnknown { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_seed_length() {
+        let mut seed = vec![0x80, 0x20];
+        seed.extend([0u8; 32]);
+
+        let der = private_key_info(SecurityLevel::new(K::Three), &seed);
+        assert!(PrivateKey::from_pkcs8_der(&der).is_err());
+    }
+
+    #[cfg(feature = "pem")]
+    #[test]
+    fn lamps_reference_keys() {
+        let seed: [u8; 64] = core::array::from_fn(|i| i as u8);
+
+        for (pk_pem, sk_pem) in LAMPS_KEYS {
+            let pk = PublicKey::from_public_key_pem(pk_pem).unwrap();
+            let sk = PrivateKey::from_pkcs8_pem(sk_pem).unwrap();
+
+            assert_eq!(sk.to_seed(), Some(seed));
+            assert_eq!(sk.get_public_key(), pk);
+
+            assert_eq!(pk.to_public_key_pem(LineEnding::LF).unwrap(), pk_pem);
+            assert_eq!(sk.to_pkcs8_pem(LineEnding::LF).unwrap().as_str(), sk_pem);
+        }
+    }
+}
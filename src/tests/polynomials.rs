@@ -8,6 +8,7 @@ pub(in crate::tests) mod poly_tests {
     };
     use more_asserts::assert_le;
     use proptest::prelude::*;
+    use zeroize::Zeroize;
 
     const compress_decompress_buf: [u8; 128] = [
         0, 0, 0, 0, 0, 16, 17, 17, 17, 17, 17, 17, 17, 17, 17, 17, 34, 34, 34, 34, 34, 34, 34, 34,
@@ -304,6 +305,13 @@ pub(in crate::tests) mod poly_tests {
             assert_eq!(&a, poly.coeffs());
         }
 
+        #[test]
+        fn zeroize_test(mut poly in new_poly()) {
+            poly.zeroize();
+
+            assert_eq!(poly.coeffs(), &[0i16; N]);
+        }
+
         #[test]
         fn add_test(
             a in new_limited_poly(),
@@ -0,0 +1,81 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "rand_core_09")]
+mod rng_tests {
+    use crate::{errors::Error, kem::*, rng::RandCore09};
+    use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{RngCore, SeedableRng};
+
+    // rand_core 0.9 RNG producing the same stream as the wrapped 0.6 RNG
+    struct ChaCha09(ChaCha20Rng);
+
+    impl rand_core_09::RngCore for ChaCha09 {
+        fn next_u32(&mut self) -> u32 {
+            self.0.next_u32()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0.next_u64()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            self.0.fill_bytes(dest);
+        }
+    }
+
+    impl rand_core_09::CryptoRng for ChaCha09 {}
+
+    struct FailingRng;
+
+    impl rand_core_09::TryRngCore for FailingRng {
+        type Error = core::fmt::Error;
+
+        fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+            Err(core::fmt::Error)
+        }
+
+        fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+            Err(core::fmt::Error)
+        }
+
+        fn try_fill_bytes(&mut self, _dest: &mut [u8]) -> Result<(), Self::Error> {
+            Err(core::fmt::Error)
+        }
+    }
+
+    impl rand_core_09::TryCryptoRng for FailingRng {}
+
+    proptest! {
+        #[test]
+        fn matches_rand_core_06(seed in prop::array::uniform32(u8::MIN..u8::MAX)) {
+            let mut rng = ChaCha20Rng::from_seed(seed);
+            let (pk, sk) = generate_keypair_768(&mut rng).unwrap();
+            let (ciphertext, shared_secret) = pk.encapsulate(&mut rng).unwrap();
+
+            let mut rng_09 = ChaCha09(ChaCha20Rng::from_seed(seed));
+            let mut wrapped = RandCore09(&mut rng_09);
+            let (pk_09, sk_09) = generate_keypair_768(&mut wrapped).unwrap();
+            let (ciphertext_09, shared_secret_09) = pk_09.encapsulate(&mut wrapped).unwrap();
+
+            assert_eq!(pk, pk_09);
+            assert_eq!(sk, sk_09);
+            assert_eq!(ciphertext, ciphertext_09);
+            assert_eq!(shared_secret, shared_secret_09);
+        }
+    }
+
+    #[test]
+    fn rng_failure() {
+        assert_eq!(
+            generate_keypair_512(&mut RandCore09(FailingRng)).unwrap_err(),
+            Error::RngFailure
+        );
+
+        let (pk, _) = generate_keypair_512_with_default_rng().unwrap();
+        assert_eq!(
+            pk.encapsulate(&mut RandCore09(FailingRng)).unwrap_err(),
+            Error::RngFailure
+        );
+    }
+}
@@ -0,0 +1,192 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "rustls")]
+mod rustls_tests {
+    extern crate std;
+
+    use crate::tls::rustls::*;
+    use rcgen::generate_simple_self_signed;
+    use rustls::{
+        crypto::{ring, CryptoProvider, SupportedKxGroup},
+        pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName},
+        version::TLS13,
+        ClientConfig, ClientConnection, Connection, Error, NamedGroup, PeerMisbehaved,
+        ProtocolVersion, RootCertStore, ServerConfig, ServerConnection,
+    };
+    use std::{
+        io::{Read, Write},
+        string::ToString,
+        sync::Arc,
+        vec,
+        vec::Vec,
+    };
+
+    fn provider(kx_groups: Vec<&'static dyn SupportedKxGroup>) -> Arc<CryptoProvider> {
+        Arc::new(CryptoProvider {
+            kx_groups,
+            ..ring::default_provider()
+        })
+    }
+
+    fn configs(
+        client_groups: Vec<&'static dyn SupportedKxGroup>,
+        server_groups: Vec<&'static dyn SupportedKxGroup>,
+    ) -> (Arc<ClientConfig>, Arc<ServerConfig>) {
+        let certified = generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert = CertificateDer::from(certified.cert.der().to_vec());
+        let key = PrivateKeyDer::from(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+
+        let mut roots = RootCertStore::empty();
+        roots.add(cert.clone()).unwrap();
+
+        let client = ClientConfig::builder_with_provider(provider(client_groups))
+            .with_protocol_versions(&[&TLS13])
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let server = ServerConfig::builder_with_provider(provider(server_groups))
+            .with_protocol_versions(&[&TLS13])
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert], key)
+            .unwrap();
+
+        (Arc::new(client), Arc::new(server))
+    }
+
+    // Moves any pending TLS records from one side to the other
+    fn send(from: &mut Connection, to: &mut Connection) -> Result<(), Error> {
+        let mut buf = Vec::new();
+        while from.wants_write() {
+            from.write_tls(&mut buf).unwrap();
+        }
+        let mut records = buf.as_slice();
+        while !records.is_empty() {
+            to.read_tls(&mut records).unwrap();
+            to.process_new_packets()?;
+        }
+
+        Ok(())
+    }
+
+    // Runs the handshake in-process, then exchanges application data in both directions
+    fn handshake(
+        client_config: Arc<ClientConfig>,
+        server_config: Arc<ServerConfig>,
+    ) -> Result<(Connection, Connection), Error> {
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let mut client = Connection::from(ClientConnection::new(client_config, server_name)?);
+        let mut server = Connection::from(ServerConnection::new(server_config)?);
+
+        while client.is_handshaking() || server.is_handshaking() {
+            send(&mut client, &mut server)?;
+            send(&mut server, &mut client)?;
+        }
+
+        client.writer().write_all(b"ping").unwrap();
+        send(&mut client, &mut server)?;
+        let mut received = [0u8; 4];
+        server.reader().read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"ping");
+
+        server.writer().write_all(b"pong").unwrap();
+        send(&mut server, &mut client)?;
+        client.reader().read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"pong");
+
+        Ok((client, server))
+    }
+
+    fn negotiated(connection: &Connection) -> NamedGroup {
+        connection.negotiated_key_exchange_group().unwrap().name()
+    }
+
+    #[test]
+    fn x25519_mlkem768_handshake() {
+        let (client_config, server_config) = configs(vec![X25519MLKEM768], vec![X25519MLKEM768]);
+        let (client, server) = handshake(client_config, server_config).unwrap();
+
+        assert_eq!(negotiated(&client), NamedGroup::X25519MLKEM768);
+        assert_eq!(negotiated(&server), NamedGroup::X25519MLKEM768);
+        assert_eq!(client.protocol_version(), Some(ProtocolVersion::TLSv1_3));
+    }
+
+    #[test]
+    fn mlkem768_handshake() {
+        let (client_config, server_config) = configs(vec![MLKEM768], vec![MLKEM768]);
+        let (client, server) = handshake(client_config, server_config).unwrap();
+
+        assert_eq!(negotiated(&client), NamedGroup::MLKEM768);
+        assert_eq!(negotiated(&server), NamedGroup::MLKEM768);
+    }
+
+    // The client's first share is not supported by the server, which asks for the second with a
+    // HelloRetryRequest
+    #[test]
+    fn hello_retry_request() {
+        let (client_config, server_config) = configs(ALL_KX_GROUPS.to_vec(), vec![MLKEM768]);
+        let (client, server) = handshake(client_config, server_config).unwrap();
+
+        assert_eq!(negotiated(&client), NamedGroup::MLKEM768);
+        assert_eq!(negotiated(&server), NamedGroup::MLKEM768);
+    }
+
+    // Falls back to a classical group when the server does not support ML-KEM
+    #[test]
+    fn classical_fallback() {
+        let (client_config, server_config) = configs(
+            vec![X25519MLKEM768, ring::kx_group::X25519],
+            vec![ring::kx_group::X25519],
+        );
+        let (client, _) = handshake(client_config, server_config).unwrap();
+
+        assert_eq!(negotiated(&client), NamedGroup::X25519);
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(X25519MLKEM768.name(), NamedGroup::X25519MLKEM768);
+        assert_eq!(MLKEM768.name(), NamedGroup::MLKEM768);
+
+        for group in ALL_KX_GROUPS {
+            assert!(group.usable_for_version(ProtocolVersion::TLSv1_3));
+            assert!(!group.usable_for_version(ProtocolVersion::TLSv1_2));
+        }
+    }
+
+    #[test]
+    fn key_shares() {
+        for (group, client_bytes, server_bytes) in
+            [(X25519MLKEM768, 1216, 1120), (MLKEM768, 1184, 1088)]
+        {
+            let client = group.start().unwrap();
+            assert_eq!(client.group(), group.name());
+            assert_eq!(client.pub_key().len(), client_bytes);
+
+            let server = group.start_and_complete(client.pub_key()).unwrap();
+            assert_eq!(server.group, group.name());
+            assert_eq!(server.pub_key.len(), server_bytes);
+
+            let secret = client.complete(&server.pub_key).unwrap();
+            assert_eq!(secret.secret_bytes(), server.secret.secret_bytes());
+        }
+    }
+
+    #[test]
+    fn invalid_key_shares() {
+        for group in ALL_KX_GROUPS {
+            let client = group.start().unwrap();
+            let mut client_share = client.pub_key().to_vec();
+            client_share.pop();
+            assert_eq!(
+                group.start_and_complete(&client_share).err(),
+                Some(PeerMisbehaved::InvalidKeyShare.into())
+            );
+
+            assert_eq!(
+                client.complete(&[0u8; 32]).err(),
+                Some(PeerMisbehaved::InvalidKeyShare.into())
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod serialization_tests {
+    extern crate std;
+
+    use crate::{
+        kem::{self, typed::*, SharedSecret},
+        tests::params::params_tests::sec_level_strategy,
+    };
+    use base64ct::{Base64, Encoding};
+    use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+    use serde::{Deserialize, Serialize};
+    use std::{string::ToString, vec, vec::Vec};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Identity {
+        pk: kem::PublicKey,
+        #[serde(with = "kem::serde_private_key")]
+        sk: kem::PrivateKey,
+        ciphertext: kem::Ciphertext,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TypedIdentity<P: ParameterSet> {
+        #[serde(bound = "")]
+        pk: PublicKey<P>,
+        #[serde(bound = "", with = "kem::serde_private_key")]
+        sk: PrivateKey<P>,
+        #[serde(bound = "")]
+        ciphertext: Ciphertext<P>,
+    }
+
+    fn pk_bytes(pk: &kem::PublicKey) -> Vec<u8> {
+        let mut bytes = vec![0u8; pk.sec_level().public_key_bytes()];
+        pk.pack(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn sk_bytes(sk: &kem::PrivateKey) -> Vec<u8> {
+        let mut bytes = vec![0u8; sk.sec_level().private_key_bytes()];
+        sk.to_expanded_bytes(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn to_cbor<T: Serialize>(value: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes).unwrap();
+        bytes
+    }
+
+    prop_compose! {
+        fn new_identity()
+            (sec_level in sec_level_strategy())
+            -> (Identity, SharedSecret) {
+                let (pk, sk) = kem::generate_key_pair(&mut ChaCha20Rng::from_entropy(), sec_level.k()).unwrap();
+                let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+                (Identity { pk, sk, ciphertext }, shared_secret)
+            }
+    }
+
+    proptest! {
+        #[test]
+        fn json_round_trip((identity, shared_secret) in new_identity()) {
+            let json = serde_json::to_string(&identity).unwrap();
+            let parsed: Identity = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(parsed, identity);
+            assert_eq!(parsed.sk.decapsulate(&parsed.ciphertext).unwrap(), shared_secret);
+        }
+
+        #[test]
+        fn cbor_round_trip((identity, shared_secret) in new_identity()) {
+            let cbor = to_cbor(&identity);
+            let parsed: Identity = ciborium::from_reader(cbor.as_slice()).unwrap();
+
+            assert_eq!(parsed, identity);
+            assert_eq!(parsed.sk.decapsulate(&parsed.ciphertext).unwrap(), shared_secret);
+        }
+
+        #[test]
+        fn encodings((identity, _) in new_identity()) {
+            let pk_bytes = pk_bytes(&identity.pk);
+            let sk_bytes = sk_bytes(&identity.sk);
+
+            let json = serde_json::to_value(&identity).unwrap();
+            assert_eq!(json["pk"], Base64::encode_string(&pk_bytes));
+            assert_eq!(json["sk"], Base64::encode_string(&sk_bytes));
+            assert_eq!(json["ciphertext"], Base64::encode_string(identity.ciphertext.as_bytes()));
+
+            // CBOR byte strings hold the raw encoding, rather than Base64 text
+            assert_eq!(to_cbor(&identity.pk)[3..], pk_bytes[..]);
+        }
+
+        #[test]
+        fn invalid_length((identity, _) in new_identity(), trim in 1..32usize) {
+            let pk_bytes = pk_bytes(&identity.pk);
+            let sk_bytes = sk_bytes(&identity.sk);
+            let ct_bytes = identity.ciphertext.as_bytes();
+
+            let short_pk = serde_json::to_string(&Base64::encode_string(&pk_bytes[trim..])).unwrap();
+            assert!(serde_json::from_str::<kem::PublicKey>(&short_pk).is_err());
+
+            let short_sk = serde_json::to_string(&Base64::encode_string(&sk_bytes[trim..])).unwrap();
+            assert!(serde_json::from_str::<kem::PrivateKey>(&short_sk).is_err());
+
+            let short_ct = to_cbor(&serde_bytes(&ct_bytes[trim..]));
+            assert!(ciborium::from_reader::<kem::Ciphertext, _>(short_ct.as_slice()).is_err());
+        }
+
+        #[test]
+        fn corrupted_private_key((identity, _) in new_identity(), index in 0..32usize) {
+            let mut sk_bytes = sk_bytes(&identity.sk);
+            let len = sk_bytes.len();
+            sk_bytes[len - 64 + index] ^= 1;
+
+            let json = serde_json::to_string(&Base64::encode_string(&sk_bytes)).unwrap();
+            let error = serde_json::from_str::<kem::PrivateKey>(&json).unwrap_err();
+            assert!(error.to_string().contains("private key hash"));
+        }
+    }
+
+    // Serialised as a CBOR byte string, as Vec<u8> would be a sequence
+    struct SerdeBytes<'a>(&'a [u8]);
+
+    impl Serialize for SerdeBytes<'_> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    fn serde_bytes(bytes: &[u8]) -> SerdeBytes<'_> {
+        SerdeBytes(bytes)
+    }
+
+    fn typed_round_trip<P: ParameterSet>() {
+        let (pk, sk) = P::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+        let identity = TypedIdentity { pk, sk, ciphertext };
+
+        let json = serde_json::to_string(&identity).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TypedIdentity<P>>(&json).unwrap(),
+            identity
+        );
+
+        let cbor = to_cbor(&identity);
+        assert_eq!(
+            ciborium::from_reader::<TypedIdentity<P>, _>(cbor.as_slice()).unwrap(),
+            identity
+        );
+    }
+
+    #[test]
+    fn typed_round_trip_all() {
+        typed_round_trip::<MlKem512>();
+        typed_round_trip::<MlKem768>();
+        typed_round_trip::<MlKem1024>();
+    }
+
+    #[test]
+    fn typed_mismatched_parameter_set() {
+        let (pk, sk) = MlKem512::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+        let json = serde_json::to_string(&TypedIdentity { pk, sk, ciphertext }).unwrap();
+
+        let error = serde_json::from_str::<TypedIdentity<MlKem768>>(&json).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Mismatched parameter sets, expected ML-KEM-768, got ML-KEM-512"));
+    }
+
+    #[test]
+    fn invalid_base64() {
+        assert!(serde_json::from_str::<kem::PublicKey>("\"not base64!\"").is_err());
+        assert!(serde_json::from_str::<kem::Ciphertext>("\"\"").is_err());
+    }
+}
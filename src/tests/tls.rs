@@ -0,0 +1,220 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "tls")]
+mod tls_tests {
+    extern crate std;
+
+    use crate::{errors::Error, kem, tls::*};
+    use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+    use std::{format, vec::Vec};
+    use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+
+    const GROUPS: [NamedGroup; 4] = [
+        NamedGroup::X25519MlKem768,
+        NamedGroup::SecP256r1MlKem768,
+        NamedGroup::MlKem768,
+        NamedGroup::MlKem1024,
+    ];
+
+    fn round_trip(group: NamedGroup, seed: [u8; 32]) {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let client = ClientKeyExchange::start(group, &mut rng).unwrap();
+        assert_eq!(client.group(), group);
+        assert_eq!(
+            client.key_share().as_bytes().len(),
+            group.client_share_bytes()
+        );
+
+        let (server_share, server_secret) =
+            server_key_exchange(group, client.key_share().as_bytes(), &mut rng).unwrap();
+        assert_eq!(server_share.as_bytes().len(), group.server_share_bytes());
+        assert_eq!(server_secret.as_bytes().len(), group.shared_secret_bytes());
+
+        let client_secret = client.finish(server_share.as_bytes()).unwrap();
+        assert_eq!(client_secret, server_secret);
+    }
+
+    proptest! {
+        #[test]
+        fn x25519_mlkem768_round_trip(seed: [u8; 32]) {
+            round_trip(NamedGroup::X25519MlKem768, seed);
+        }
+
+        #[test]
+        fn secp256r1_mlkem768_round_trip(seed: [u8; 32]) {
+            round_trip(NamedGroup::SecP256r1MlKem768, seed);
+        }
+
+        #[test]
+        fn mlkem768_round_trip(seed: [u8; 32]) {
+            round_trip(NamedGroup::MlKem768, seed);
+        }
+
+        #[test]
+        fn mlkem1024_round_trip(seed: [u8; 32]) {
+            round_trip(NamedGroup::MlKem1024, seed);
+        }
+
+        // The client's share is the ML-KEM-768 encapsulation key followed by the X25519 public
+        // key, and the shared secret is the ML-KEM shared secret followed by the X25519 one
+        #[test]
+        fn x25519_mlkem768_layout(seed: [u8; 32], x25519_seed: [u8; 32]) {
+            let mut rng = ChaCha20Rng::from_seed(seed);
+            let client = ClientKeyExchange::start(NamedGroup::X25519MlKem768, &mut rng).unwrap();
+            let (pk_m, pk_x) = client.key_share().as_bytes().split_at(1184);
+
+            let (ct_m, ss_m) = kem::PublicKey::unpack(pk_m)
+                .unwrap()
+                .encapsulate(&mut rng)
+                .unwrap();
+            let sk_x = StaticSecret::from(x25519_seed);
+            let pk_x: [u8; 32] = pk_x.try_into().unwrap();
+            let ss_x = sk_x.diffie_hellman(&X25519PublicKey::from(pk_x));
+            prop_assume!(ss_x.was_contributory());
+
+            let server_share = [ct_m.as_bytes(), X25519PublicKey::from(&sk_x).as_bytes()].concat();
+            let shared_secret = client.finish(&server_share).unwrap();
+
+            assert_eq!(
+                shared_secret.as_bytes(),
+                [ss_m.as_bytes().as_slice(), ss_x.as_bytes()].concat()
+            );
+        }
+
+        #[test]
+        fn mlkem768_layout(seed: [u8; 32]) {
+            let mut rng = ChaCha20Rng::from_seed(seed);
+            let client = ClientKeyExchange::start(NamedGroup::MlKem768, &mut rng).unwrap();
+
+            let (ciphertext, ss) = kem::PublicKey::unpack(client.key_share().as_bytes())
+                .unwrap()
+                .encapsulate(&mut rng)
+                .unwrap();
+            let shared_secret = client.finish(ciphertext.as_bytes()).unwrap();
+
+            assert_eq!(shared_secret.as_bytes(), ss.as_bytes());
+        }
+
+        #[test]
+        fn modified_server_share(seed: [u8; 32], index: usize) {
+            let mut rng = ChaCha20Rng::from_seed(seed);
+            for group in GROUPS {
+                let client = ClientKeyExchange::start(group, &mut rng).unwrap();
+                let (server_share, server_secret) =
+                    server_key_exchange(group, client.key_share().as_bytes(), &mut rng).unwrap();
+
+                // Only the ML-KEM ciphertext, as a modified point may be invalid
+                let index = match group {
+                    NamedGroup::X25519MlKem768 => index % 1088,
+                    NamedGroup::SecP256r1MlKem768 => 65 + index % 1088,
+                    _ => index % server_share.as_bytes().len(),
+                };
+                let mut modified = server_share.as_bytes().to_vec();
+                modified[index] ^= 1;
+
+                assert_ne!(client.finish(&modified).unwrap(), server_secret);
+            }
+        }
+    }
+
+    #[test]
+    fn code_points() {
+        for (group, code_point) in GROUPS.into_iter().zip([0x11EC, 0x11EB, 0x0201, 0x0202]) {
+            assert_eq!(group.code_point(), code_point);
+            assert_eq!(u16::from(group), code_point);
+            assert_eq!(NamedGroup::from_code_point(code_point), Some(group));
+        }
+
+        // X25519, secp256r1, and the draft Kyber768 hybrid
+        for code_point in [0x001D, 0x0017, 0x6399] {
+            assert_eq!(NamedGroup::from_code_point(code_point), None);
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        let sizes = [
+            (1216, 1120, 64),
+            (1249, 1153, 64),
+            (1184, 1088, 32),
+            (1568, 1568, 32),
+        ];
+        for (group, (client, server, secret)) in GROUPS.into_iter().zip(sizes) {
+            assert_eq!(group.client_share_bytes(), client);
+            assert_eq!(group.server_share_bytes(), server);
+            assert_eq!(group.shared_secret_bytes(), secret);
+        }
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        for group in GROUPS {
+            let client = ClientKeyExchange::start_with_default_rng(group).unwrap();
+            let mut client_share = client.key_share().as_bytes().to_vec();
+            client_share.push(0);
+            assert_eq!(
+                server_key_exchange_with_default_rng(group, &client_share),
+                Err(Error::InvalidLength {
+                    expected: group.client_share_bytes(),
+                    actual: group.client_share_bytes() + 1,
+                })
+            );
+
+            let server_share = [0u8; 1000];
+            assert_eq!(
+                client.finish(&server_share),
+                Err(Error::InvalidLength {
+                    expected: group.server_share_bytes(),
+                    actual: 1000,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn zero_x25519_share() {
+        let group = NamedGroup::X25519MlKem768;
+        let client = ClientKeyExchange::start_with_default_rng(group).unwrap();
+        let mut client_share = client.key_share().as_bytes().to_vec();
+        client_share[1184..].fill(0);
+        assert_eq!(
+            server_key_exchange_with_default_rng(group, &client_share),
+            Err(Error::InvalidEncoding)
+        );
+
+        let (server_share, _) =
+            server_key_exchange_with_default_rng(group, client.key_share().as_bytes()).unwrap();
+        let mut server_share = server_share.as_bytes().to_vec();
+        server_share[1088..].fill(0);
+        assert_eq!(client.finish(&server_share), Err(Error::InvalidEncoding));
+    }
+
+    #[test]
+    fn invalid_p256_share() {
+        let group = NamedGroup::SecP256r1MlKem768;
+        let client = ClientKeyExchange::start_with_default_rng(group).unwrap();
+        let mut client_share = client.key_share().as_bytes().to_vec();
+        // Compressed point tag
+        client_share[0] = 0x02;
+        assert_eq!(
+            server_key_exchange_with_default_rng(group, &client_share),
+            Err(Error::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn debug() {
+        let client = ClientKeyExchange::start_with_default_rng(NamedGroup::MlKem768).unwrap();
+        let (_, shared_secret) = server_key_exchange_with_default_rng(
+            NamedGroup::MlKem768,
+            client.key_share().as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(format!("{shared_secret:?}"), "SharedSecret { .. }");
+        assert!(format!("{client:?}").starts_with("ClientKeyExchange { group: MlKem768,"));
+        assert!(format!("{client:?}").ends_with(".. }"));
+    }
+}
@@ -0,0 +1,55 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "kem")]
+mod traits_tests {
+    use crate::kem::{self, typed::*, SharedSecret};
+    use ::kem::{Decapsulate, Encapsulate};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    // Round trip using only the kem traits, as generic code would
+    fn round_trip<EK, PK, SK>(pk: &PK, sk: &SK)
+    where
+        PK: Encapsulate<EK, SharedSecret>,
+        SK: Decapsulate<EK, SharedSecret>,
+    {
+        let mut rng = ChaCha20Rng::from_entropy();
+        let (encapsulated_key, shared_secret) = pk.encapsulate(&mut rng).unwrap();
+
+        assert_eq!(sk.decapsulate(&encapsulated_key).unwrap(), shared_secret);
+    }
+
+    fn typed_round_trip<P: ParameterSet>() {
+        let (pk, sk) = P::generate_keypair_with_default_rng().unwrap();
+
+        round_trip(&pk, &sk);
+    }
+
+    #[test]
+    fn typed_round_trip_all() {
+        typed_round_trip::<MlKem512>();
+        typed_round_trip::<MlKem768>();
+        typed_round_trip::<MlKem1024>();
+    }
+
+    #[test]
+    fn dynamic_round_trip_all() {
+        for (pk, sk) in [
+            kem::generate_keypair_512_with_default_rng().unwrap(),
+            kem::generate_keypair_768_with_default_rng().unwrap(),
+            kem::generate_keypair_1024_with_default_rng().unwrap(),
+        ] {
+            round_trip(&pk, &sk);
+        }
+    }
+
+    #[test]
+    fn mismatched_security_level() {
+        let (pk, _) = kem::generate_keypair_512_with_default_rng().unwrap();
+        let (_, sk) = kem::generate_keypair_768_with_default_rng().unwrap();
+        let (ciphertext, _) =
+            Encapsulate::encapsulate(&pk, &mut ChaCha20Rng::from_entropy()).unwrap();
+
+        assert!(Decapsulate::decapsulate(&sk, &ciphertext).is_err());
+    }
+}
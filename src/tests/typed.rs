@@ -0,0 +1,148 @@
+#![allow(warnings)]
+#[cfg(test)]
+mod typed_tests {
+    use crate::{
+        errors::Error,
+        kem::{self, typed::*},
+    };
+
+    fn encapsulate_decapsulate<P: ParameterSet>() {
+        let (pk, sk) = P::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+
+        assert_eq!(sk.decapsulate(&ciphertext).unwrap(), shared_secret);
+
+        let bytes = ciphertext.as_bytes();
+        assert_eq!(
+            sk.decapsulate(&Ciphertext::from_bytes(*bytes)).unwrap(),
+            shared_secret
+        );
+    }
+
+    fn pack_unpack<P: ParameterSet>() {
+        let (pk, sk) = P::generate_keypair_with_default_rng().unwrap();
+
+        let pk_bytes = pk.to_bytes();
+        assert_eq!(PublicKey::<P>::from_bytes(&pk_bytes).unwrap(), pk);
+
+        assert_eq!(PrivateKey::<P>::from_seed(sk.to_seed().unwrap()), sk);
+        assert_eq!(
+            PrivateKey::<P>::from_expanded_bytes(&sk.to_expanded_bytes()).unwrap(),
+            sk
+        );
+    }
+
+    fn keypair_from_seed<P: ParameterSet>() {
+        let (d, z) = ([1u8; 32], [2u8; 32]);
+        let (pk, sk) = P::generate_keypair_from_seed(&d, &z).unwrap();
+        let (dyn_pk, dyn_sk) = kem::generate_key_pair_from_seed(&d, &z, P::SEC_LEVEL.k()).unwrap();
+
+        assert_eq!(kem::PublicKey::from(pk), dyn_pk);
+        assert_eq!(kem::PrivateKey::from(sk), dyn_sk);
+    }
+
+    fn dynamic_round_trip<P: ParameterSet>() {
+        let (pk, sk) = P::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+
+        let dyn_pk = kem::PublicKey::from(pk);
+        let dyn_sk = kem::PrivateKey::from(sk);
+        let dyn_ciphertext = kem::Ciphertext::from(ciphertext);
+        assert_eq!(dyn_sk.decapsulate(&dyn_ciphertext).unwrap(), shared_secret);
+
+        let pk = PublicKey::<P>::try_from(dyn_pk).unwrap();
+        let sk = PrivateKey::<P>::try_from(dyn_sk).unwrap();
+        assert_eq!(sk.get_public_key(), pk);
+    }
+
+    #[test]
+    fn encapsulate_decapsulate_all() {
+        encapsulate_decapsulate::<MlKem512>();
+        encapsulate_decapsulate::<MlKem768>();
+        encapsulate_decapsulate::<MlKem1024>();
+    }
+
+    #[test]
+    fn pack_unpack_all() {
+        pack_unpack::<MlKem512>();
+        pack_unpack::<MlKem768>();
+        pack_unpack::<MlKem1024>();
+    }
+
+    #[test]
+    fn keypair_from_seed_all() {
+        keypair_from_seed::<MlKem512>();
+        keypair_from_seed::<MlKem768>();
+        keypair_from_seed::<MlKem1024>();
+    }
+
+    #[test]
+    fn dynamic_round_trip_all() {
+        dynamic_round_trip::<MlKem512>();
+        dynamic_round_trip::<MlKem768>();
+        dynamic_round_trip::<MlKem1024>();
+    }
+
+    #[test]
+    fn byte_lengths() {
+        let (pk, _) = MlKem512::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+        let _: [u8; 800] = pk.to_bytes();
+        let _: [u8; 768] = *ciphertext.as_bytes();
+
+        let (pk, _) = MlKem768::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+        let _: [u8; 1184] = pk.to_bytes();
+        let _: [u8; 1088] = *ciphertext.as_bytes();
+
+        let (pk, _) = MlKem1024::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+        let _: [u8; 1568] = pk.to_bytes();
+        let _: [u8; 1568] = *ciphertext.as_bytes();
+    }
+
+    #[test]
+    fn try_from_mismatched_security_level() {
+        let (pk, sk) = kem::generate_keypair_512_with_default_rng().unwrap();
+
+        assert_eq!(
+            PublicKey::<MlKem768>::try_from(pk),
+            Err(Error::ParameterMismatch {
+                expected: 768,
+                actual: 512
+            })
+        );
+        assert_eq!(
+            PrivateKey::<MlKem1024>::try_from(sk),
+            Err(Error::ParameterMismatch {
+                expected: 1024,
+                actual: 512
+            })
+        );
+    }
+
+    #[test]
+    fn ciphertext_try_from() {
+        let (pk, _) = MlKem768::generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+
+        assert_eq!(
+            Ciphertext::<MlKem768>::try_from(ciphertext.as_ref()).unwrap(),
+            ciphertext
+        );
+        assert_eq!(
+            Ciphertext::<MlKem1024>::try_from(ciphertext.as_ref()),
+            Err(Error::InvalidLength {
+                expected: 1568,
+                actual: 1088
+            })
+        );
+        assert_eq!(
+            Ciphertext::<MlKem512>::try_from(kem::Ciphertext::from(ciphertext)),
+            Err(Error::ParameterMismatch {
+                expected: 512,
+                actual: 768
+            })
+        );
+    }
+}
@@ -11,6 +11,7 @@ mod vec_tests {
     };
     use proptest::prelude::*;
     use tinyvec::{array_vec, ArrayVec};
+    use zeroize::Zeroize;
 
     prop_compose! {
         pub(in crate::tests) fn new_poly_vec()
@@ -299,6 +300,13 @@ mod vec_tests {
     }
 
     proptest! {
+        #[test]
+        fn zeroize_test(mut poly_vec in new_poly_vec()) {
+            poly_vec.zeroize();
+
+            assert!(poly_vec.polynomials().iter().all(|poly| poly.coeffs() == &[0i16; N]));
+        }
+
         #[test]
         fn sec_level_test(poly_vec in new_poly_vec()) {
             let sec_level = poly_vec.sec_level();
@@ -0,0 +1,151 @@
+#![allow(warnings)]
+#[cfg(test)]
+#[cfg(feature = "hybrid")]
+mod xwing_tests {
+    extern crate std;
+
+    use crate::{errors::Error, hybrid::xwing::*};
+    use proptest::prelude::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+    use serde::Deserialize;
+    use std::{format, vec::Vec};
+
+    // See xwing/README.md for the source of the vectors.
+    const TEST_VECTORS: &str = include_str!("xwing/test-vectors.json");
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        #[serde(with = "hex")]
+        seed: Vec<u8>,
+        #[serde(with = "hex")]
+        eseed: Vec<u8>,
+        #[serde(with = "hex")]
+        ss: Vec<u8>,
+        #[serde(with = "hex")]
+        sk: Vec<u8>,
+        #[serde(with = "hex")]
+        pk: Vec<u8>,
+        #[serde(with = "hex")]
+        ct: Vec<u8>,
+    }
+
+    prop_compose! {
+        fn new_keypair()
+            (seed in prop::array::uniform32(any::<u8>()))
+            -> (PublicKey, PrivateKey) {
+                generate_keypair(&mut ChaCha20Rng::from_seed(seed)).unwrap()
+            }
+    }
+
+    proptest! {
+        #[test]
+        fn encapsulate_decapsulate((pk, sk) in new_keypair()) {
+            let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+
+            assert_eq!(sk.decapsulate(&ciphertext).unwrap(), shared_secret);
+        }
+
+        #[test]
+        fn seed_round_trip((pk, sk) in new_keypair()) {
+            let parsed = PrivateKey::from_seed(sk.as_bytes()).unwrap();
+
+            assert_eq!(parsed, sk);
+            assert_eq!(parsed.get_public_key(), pk);
+        }
+
+        #[test]
+        fn public_key_round_trip((pk, _) in new_keypair()) {
+            assert_eq!(PublicKey::from_bytes(&pk.to_bytes()).unwrap(), pk);
+        }
+
+        #[test]
+        fn ciphertext_round_trip((pk, _) in new_keypair()) {
+            let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+
+            assert_eq!(Ciphertext::try_from(ciphertext.as_ref()).unwrap(), ciphertext);
+            assert_eq!(Ciphertext::from_bytes(*ciphertext.as_bytes()), ciphertext);
+        }
+
+        #[test]
+        fn modified_ciphertext((pk, sk) in new_keypair(), index in 0..CIPHERTEXT_BYTES) {
+            let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+            let mut bytes = *ciphertext.as_bytes();
+            bytes[index] ^= 1;
+
+            // ML-KEM implicitly rejects, and the X25519 ciphertext is bound by the combiner
+            assert_ne!(sk.decapsulate(&Ciphertext::from_bytes(bytes)).unwrap(), shared_secret);
+        }
+
+        #[test]
+        fn wrong_private_key((pk, _) in new_keypair(), (_, other_sk) in new_keypair()) {
+            let (ciphertext, shared_secret) = pk.encapsulate_with_default_rng().unwrap();
+
+            assert_ne!(other_sk.decapsulate(&ciphertext).unwrap(), shared_secret);
+        }
+    }
+
+    #[test]
+    fn test_vectors() {
+        let vectors: Vec<TestVector> = serde_json::from_str(TEST_VECTORS).unwrap();
+
+        for vector in vectors {
+            let sk = PrivateKey::from_seed(&vector.seed.try_into().unwrap()).unwrap();
+            assert_eq!(sk.as_bytes().as_slice(), vector.sk);
+
+            let pk = sk.get_public_key();
+            assert_eq!(pk.to_bytes().as_slice(), vector.pk);
+
+            let (ciphertext, shared_secret) = pk
+                .encapsulate_deterministic(&vector.eseed.try_into().unwrap())
+                .unwrap();
+            assert_eq!(ciphertext.as_ref(), vector.ct);
+            assert_eq!(shared_secret.as_bytes().as_slice(), vector.ss);
+
+            let ciphertext = Ciphertext::try_from(vector.ct.as_slice()).unwrap();
+            assert_eq!(
+                sk.decapsulate(&ciphertext).unwrap().as_bytes().as_slice(),
+                vector.ss
+            );
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        let (pk, sk) = generate_keypair_with_default_rng().unwrap();
+        let (ciphertext, _) = pk.encapsulate_with_default_rng().unwrap();
+
+        assert_eq!(sk.as_bytes().len(), 32);
+        assert_eq!(pk.to_bytes().len(), 1216);
+        assert_eq!(ciphertext.as_bytes().len(), 1120);
+    }
+
+    #[test]
+    fn invalid_ciphertext_length() {
+        assert_eq!(
+            Ciphertext::try_from([0u8; 1088].as_slice()),
+            Err(Error::InvalidLength {
+                expected: CIPHERTEXT_BYTES,
+                actual: 1088
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_public_key() {
+        // Coefficients of the ML-KEM public key must be reduced modulo q
+        let mut bytes = [0u8; PUBLIC_KEY_BYTES];
+        bytes[..2].fill(0xff);
+
+        assert!(PublicKey::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn private_key_debug_is_redacted() {
+        let (_, sk) = generate_keypair_with_default_rng().unwrap();
+        let hex_seed = hex::encode(sk.as_bytes());
+
+        assert!(!format!("{sk:?}").contains(&hex_seed));
+        assert_eq!(format!("{sk:?}"), "PrivateKey { .. }");
+    }
+}
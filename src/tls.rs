@@ -0,0 +1,522 @@
+//! Sans-IO TLS 1.3 `key_share` helpers for the standardised ML-KEM groups.
+//!
+//! These produce and consume the `key_exchange` field of a `KeyShareEntry`, leaving the framing
+//! of the handshake messages to the TLS stack:
+//!
+//! 1. The client calls [`ClientKeyExchange::start`] and sends [`key_share`](ClientKeyExchange::key_share)
+//!    in its `ClientHello`
+//! 2. The server calls [`server_key_exchange`] on the received share, and sends the returned
+//!    [`KeyShare`] in its `ServerHello`
+//! 3. The client calls [`finish`](ClientKeyExchange::finish) on the server's share
+//!
+//! Both sides then hold the same [`SharedSecret`], to be used as the (EC)DHE input to the TLS
+//! 1.3 key schedule.
+//!
+//! With the `rustls` feature, `tls::rustls` provides these groups to a `rustls` `CryptoProvider`.
+//!
+//! | Group                               | Client share                     | Server share                  | Shared secret        |
+//! |-------------------------------------|----------------------------------|-------------------------------|----------------------|
+//! | `X25519MLKEM768` (`0x11EC`)         | ML-KEM-768 key (1184) + X25519 (32) | ML-KEM-768 ct (1088) + X25519 (32) | ML-KEM + X25519 (64) |
+//! | `SecP256r1MLKEM768` (`0x11EB`)      | P-256 point (65) + ML-KEM-768 key (1184) | P-256 point (65) + ML-KEM-768 ct (1088) | P-256 + ML-KEM (64) |
+//! | `MLKEM768` (`0x0201`)               | ML-KEM-768 key (1184)            | ML-KEM-768 ct (1088)          | ML-KEM (32)          |
+//! | `MLKEM1024` (`0x0202`)              | ML-KEM-1024 key (1568)           | ML-KEM-1024 ct (1568)         | ML-KEM (32)          |
+//!
+//! # Example
+//! ```
+//! use enc_rust::tls::*;
+//!
+//! let group = NamedGroup::from_code_point(0x11EC).unwrap();
+//!
+//! // Client
+//! let client = ClientKeyExchange::start_with_default_rng(group)?;
+//! let client_share = client.key_share().as_bytes();
+//!
+//! // Server
+//! let (server_share, server_secret) = server_key_exchange_with_default_rng(group, client_share)?;
+//!
+//! // Client
+//! let client_secret = client.finish(server_share.as_bytes())?;
+//!
+//! assert_eq!(client_secret, server_secret);
+//!
+//! # Ok::<(), enc_rust::errors::Error>(())
+//! ```
+
+#[cfg(feature = "rustls")]
+pub mod rustls;
+
+/// The shared secret of a key exchange, the component shared secrets concatenated in the order of
+/// the group, used as the (EC)DHE input to the TLS 1.3 key schedule
+pub use crate::hybrid::SharedSecret;
+
+use crate::{
+    errors::Error,
+    hybrid::{
+        check_length,
+        classical::{ClassicalKem, X25519},
+        nist,
+    },
+    kem::{
+        typed::{self, MlKem1024, MlKem768, ParameterSet},
+        PublicKey as DynPublicKey,
+    },
+    params::{SecurityLevel, K, SHAREDSECRETBYTES},
+};
+use core::{
+    fmt::{Debug, Formatter},
+    mem::size_of,
+};
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+
+// Largest key share, the ML-KEM-1024 encapsulation key or ciphertext
+const MAX_KEY_SHARE: usize = SecurityLevel::new(K::Four).public_key_bytes();
+
+const X25519_BYTES: usize = X25519::PUBLIC_KEY_BYTES;
+const MLKEM768_PUBLIC_KEY_BYTES: usize = size_of::<<MlKem768 as ParameterSet>::PublicKeyBytes>();
+const MLKEM768_CIPHERTEXT_BYTES: usize = size_of::<<MlKem768 as ParameterSet>::CiphertextBytes>();
+
+/// A TLS 1.3 `NamedGroup` supported by this module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NamedGroup {
+    /// `X25519MLKEM768`, code point `0x11EC`
+    X25519MlKem768,
+    /// `SecP256r1MLKEM768`, code point `0x11EB`
+    SecP256r1MlKem768,
+    /// `MLKEM768`, code point `0x0201`
+    MlKem768,
+    /// `MLKEM1024`, code point `0x0202`
+    MlKem1024,
+}
+
+impl NamedGroup {
+    /// Returns the group for a TLS `NamedGroup` code point, or `None` if it is not supported
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::tls::*;
+    /// assert_eq!(NamedGroup::from_code_point(0x0201), Some(NamedGroup::MlKem768));
+    /// assert_eq!(NamedGroup::from_code_point(0x001D), None);
+    /// ```
+    #[must_use]
+    pub const fn from_code_point(code_point: u16) -> Option<Self> {
+        match code_point {
+            0x11EC => Some(Self::X25519MlKem768),
+            0x11EB => Some(Self::SecP256r1MlKem768),
+            0x0201 => Some(Self::MlKem768),
+            0x0202 => Some(Self::MlKem1024),
+            _ => None,
+        }
+    }
+
+    /// Returns the TLS `NamedGroup` code point of the group
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::tls::*;
+    /// assert_eq!(NamedGroup::X25519MlKem768.code_point(), 0x11EC);
+    /// ```
+    #[must_use]
+    pub const fn code_point(self) -> u16 {
+        match self {
+            Self::X25519MlKem768 => 0x11EC,
+            Self::SecP256r1MlKem768 => 0x11EB,
+            Self::MlKem768 => 0x0201,
+            Self::MlKem1024 => 0x0202,
+        }
+    }
+
+    /// Length of the client's key share in bytes
+    #[must_use]
+    pub const fn client_share_bytes(self) -> usize {
+        match self {
+            Self::X25519MlKem768 => MLKEM768_PUBLIC_KEY_BYTES + X25519_BYTES,
+            Self::SecP256r1MlKem768 => nist::PublicKey::<nist::P256, MlKem768>::BYTES,
+            Self::MlKem768 => MLKEM768_PUBLIC_KEY_BYTES,
+            Self::MlKem1024 => SecurityLevel::new(K::Four).public_key_bytes(),
+        }
+    }
+
+    /// Length of the server's key share in bytes
+    #[must_use]
+    pub const fn server_share_bytes(self) -> usize {
+        match self {
+            Self::X25519MlKem768 => MLKEM768_CIPHERTEXT_BYTES + X25519_BYTES,
+            Self::SecP256r1MlKem768 => nist::Ciphertext::<nist::P256, MlKem768>::BYTES,
+            Self::MlKem768 => MLKEM768_CIPHERTEXT_BYTES,
+            Self::MlKem1024 => SecurityLevel::new(K::Four).ciphertext_bytes(),
+        }
+    }
+
+    /// Length of the shared secret in bytes
+    #[must_use]
+    pub const fn shared_secret_bytes(self) -> usize {
+        match self {
+            Self::X25519MlKem768 | Self::SecP256r1MlKem768 => 2 * SHAREDSECRETBYTES,
+            Self::MlKem768 | Self::MlKem1024 => SHAREDSECRETBYTES,
+        }
+    }
+}
+
+impl From<NamedGroup> for u16 {
+    fn from(group: NamedGroup) -> Self {
+        group.code_point()
+    }
+}
+
+/// The `key_exchange` field of a `KeyShareEntry`, sent by the client or the server.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyShare {
+    bytes: [u8; MAX_KEY_SHARE],
+    len: usize,
+}
+
+impl KeyShare {
+    fn new(len: usize, pack: impl FnOnce(&mut [u8]) -> Result<(), Error>) -> Result<Self, Error> {
+        let mut bytes = [0u8; MAX_KEY_SHARE];
+        pack(&mut bytes[..len])?;
+
+        Ok(Self { bytes, len })
+    }
+
+    fn from_bytes(share: &[u8]) -> Self {
+        let mut bytes = [0u8; MAX_KEY_SHARE];
+        bytes[..share.len()].copy_from_slice(share);
+
+        Self {
+            bytes,
+            len: share.len(),
+        }
+    }
+
+    fn concat(first: &[u8], second: &[u8]) -> Self {
+        let len = first.len() + second.len();
+        let mut bytes = [0u8; MAX_KEY_SHARE];
+        bytes[..first.len()].copy_from_slice(first);
+        bytes[first.len()..len].copy_from_slice(second);
+
+        Self { bytes, len }
+    }
+
+    /// Returns the encoded key share
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl AsRef<[u8]> for KeyShare {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Debug for KeyShare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("bytes", &self.as_bytes())
+            .finish()
+    }
+}
+
+enum ClientState {
+    X25519MlKem768 {
+        sk_m: typed::PrivateKey<MlKem768>,
+        sk_x: <X25519 as ClassicalKem>::PrivateKey,
+    },
+    SecP256r1MlKem768(nist::PrivateKey<nist::P256, MlKem768>),
+    MlKem768(typed::PrivateKey<MlKem768>),
+    MlKem1024(typed::PrivateKey<MlKem1024>),
+}
+
+// Each group is generated separately to keep the stack frame of `ClientKeyExchange::start` small
+impl ClientState {
+    // ML-KEM-768 encapsulation key || X25519 public key
+    fn x25519_mlkem768<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<(Self, KeyShare), Error> {
+        let (pk_m, sk_m) = MlKem768::generate_keypair(rng)?;
+        let (pk_x, sk_x) = X25519::generate_keypair(rng)?;
+        let key_share = KeyShare::concat(pk_m.to_bytes().as_ref(), &pk_x);
+
+        Ok((Self::X25519MlKem768 { sk_m, sk_x }, key_share))
+    }
+
+    // P-256 point || ML-KEM-768 encapsulation key
+    fn secp256r1_mlkem768<R: CryptoRng + RngCore + ?Sized>(
+        rng: &mut R,
+    ) -> Result<(Self, KeyShare), Error> {
+        let (pk, sk) = nist::SecP256r1MlKem768::generate_keypair(rng)?;
+        let key_share = KeyShare::new(
+            NamedGroup::SecP256r1MlKem768.client_share_bytes(),
+            |bytes| pk.pack(bytes),
+        )?;
+
+        Ok((Self::SecP256r1MlKem768(sk), key_share))
+    }
+
+    fn mlkem768<R: CryptoRng + RngCore + ?Sized>(rng: &mut R) -> Result<(Self, KeyShare), Error> {
+        let (pk, sk) = MlKem768::generate_keypair(rng)?;
+
+        Ok((
+            Self::MlKem768(sk),
+            KeyShare::from_bytes(pk.to_bytes().as_ref()),
+        ))
+    }
+
+    fn mlkem1024<R: CryptoRng + RngCore + ?Sized>(rng: &mut R) -> Result<(Self, KeyShare), Error> {
+        let (pk, sk) = MlKem1024::generate_keypair(rng)?;
+
+        Ok((
+            Self::MlKem1024(sk),
+            KeyShare::from_bytes(pk.to_bytes().as_ref()),
+        ))
+    }
+}
+
+/// The client side of a key exchange, holding the ephemeral private keys between sending the
+/// `ClientHello` and receiving the `ServerHello`.
+///
+/// The private keys are zeroised when dropped, and are redacted from the [`Debug`] output.
+pub struct ClientKeyExchange {
+    group: NamedGroup,
+    state: ClientState,
+    key_share: KeyShare,
+}
+
+impl ClientKeyExchange {
+    /// Generates the client's ephemeral keys for `group`
+    ///
+    /// # Inputs
+    /// - `group`: The group to offer
+    /// - `rng`: RNG to be used when generating the ephemeral keys. Must satisfy the
+    ///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+    ///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::tls::*;
+    /// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    ///
+    /// let mut rng = ChaCha20Rng::from_entropy();
+    /// let client = ClientKeyExchange::start(NamedGroup::MlKem768, &mut rng)?;
+    /// assert_eq!(client.key_share().as_bytes().len(), 1184);
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn start<R: CryptoRng + RngCore + ?Sized>(
+        group: NamedGroup,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let (state, key_share) = match group {
+            NamedGroup::X25519MlKem768 => ClientState::x25519_mlkem768(rng)?,
+            NamedGroup::SecP256r1MlKem768 => ClientState::secp256r1_mlkem768(rng)?,
+            NamedGroup::MlKem768 => ClientState::mlkem768(rng)?,
+            NamedGroup::MlKem1024 => ClientState::mlkem1024(rng)?,
+        };
+
+        Ok(Self {
+            group,
+            state,
+            key_share,
+        })
+    }
+
+    /// Generates the client's ephemeral keys for `group`, using
+    /// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+    /// seeded from the system entropy source.
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if RNG fails
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::tls::*;
+    /// let client = ClientKeyExchange::start_with_default_rng(NamedGroup::X25519MlKem768)?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn start_with_default_rng(group: NamedGroup) -> Result<Self, Error> {
+        Self::start(group, &mut ChaCha20Rng::from_entropy())
+    }
+
+    /// Returns the group of the key exchange
+    #[must_use]
+    pub const fn group(&self) -> NamedGroup {
+        self.group
+    }
+
+    /// Returns the client's key share, to be sent in the `ClientHello`
+    #[must_use]
+    pub const fn key_share(&self) -> &KeyShare {
+        &self.key_share
+    }
+
+    /// Completes the key exchange with the server's key share from the `ServerHello`
+    ///
+    /// # Errors
+    /// Will return an [`Error`] if:
+    /// - The server's share is not of length [`server_share_bytes`](NamedGroup::server_share_bytes)
+    /// - The elliptic curve share is invalid, or gives an all zero X25519 shared secret
+    ///
+    /// # Example
+    /// ```
+    /// # use enc_rust::tls::*;
+    /// # let client = ClientKeyExchange::start_with_default_rng(NamedGroup::X25519MlKem768)?;
+    /// # let (server_share, _) =
+    /// #     server_key_exchange_with_default_rng(client.group(), client.key_share().as_bytes())?;
+    /// let shared_secret = client.finish(server_share.as_bytes())?;
+    ///
+    /// # Ok::<(), enc_rust::errors::Error>(())
+    /// ```
+    pub fn finish(self, server_share: &[u8]) -> Result<SharedSecret, Error> {
+        check_length(server_share, self.group.server_share_bytes())?;
+
+        match &self.state {
+            // ML-KEM-768 ciphertext || X25519 public key
+            ClientState::X25519MlKem768 { sk_m, sk_x } => {
+                let (ct_m, ct_x) = server_share.split_at(MLKEM768_CIPHERTEXT_BYTES);
+                let ss_m = sk_m.decapsulate(&ct_m.try_into()?)?;
+                let ss_x = X25519::decapsulate(sk_x, &X25519::ciphertext_from_bytes(ct_x)?)?;
+
+                Ok(SharedSecret::concat(ss_m.as_bytes(), ss_x.as_ref()))
+            }
+            // P-256 point || ML-KEM-768 ciphertext
+            ClientState::SecP256r1MlKem768(sk) => sk.decapsulate(&server_share.try_into()?),
+            ClientState::MlKem768(sk) => {
+                let ss = sk.decapsulate(&server_share.try_into()?)?;
+
+                Ok(SharedSecret::new(ss.as_bytes()))
+            }
+            ClientState::MlKem1024(sk) => {
+                let ss = sk.decapsulate(&server_share.try_into()?)?;
+
+                Ok(SharedSecret::new(ss.as_bytes()))
+            }
+        }
+    }
+}
+
+impl Debug for ClientKeyExchange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ClientKeyExchange")
+            .field("group", &self.group)
+            .field("key_share", &self.key_share)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Completes the server side of the key exchange for `group`, encapsulating to the client's key
+/// share from the `ClientHello`
+///
+/// # Inputs
+/// - `group`: The group selected by the server
+/// - `client_share`: The client's key share for `group`
+/// - `rng`: RNG to be used during encapsulation. Must satisfy the
+///   [`RngCore`](https://docs.rs/rand_core/0.6/rand_core/trait.RngCore.html) and
+///   [`CryptoRng`](https://docs.rs/rand_core/0.6/rand_core/trait.CryptoRng.html) traits.
+///
+/// # Outputs
+/// - [`KeyShare`]: The server's key share, to be sent in the `ServerHello`
+/// - [`SharedSecret`]
+///
+/// # Errors
+/// Will return an [`Error`] if:
+/// - RNG fails
+/// - The client's share is not of length [`client_share_bytes`](NamedGroup::client_share_bytes)
+/// - The ML-KEM encapsulation key fails the FIPS 203 modulus check
+/// - The elliptic curve share is invalid, or gives an all zero X25519 shared secret
+///
+/// # Example
+/// ```
+/// # use enc_rust::tls::*;
+/// use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+///
+/// # let client = ClientKeyExchange::start_with_default_rng(NamedGroup::SecP256r1MlKem768)?;
+/// # let client_share = client.key_share().as_bytes();
+/// let mut rng = ChaCha20Rng::from_entropy();
+/// let (server_share, shared_secret) =
+///     server_key_exchange(NamedGroup::SecP256r1MlKem768, client_share, &mut rng)?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn server_key_exchange<R: CryptoRng + RngCore + ?Sized>(
+    group: NamedGroup,
+    client_share: &[u8],
+    rng: &mut R,
+) -> Result<(KeyShare, SharedSecret), Error> {
+    check_length(client_share, group.client_share_bytes())?;
+
+    match group {
+        NamedGroup::X25519MlKem768 => {
+            let (pk_m, pk_x) = client_share.split_at(MLKEM768_PUBLIC_KEY_BYTES);
+            let pk_m = typed::PublicKey::<MlKem768>::try_from(DynPublicKey::unpack(pk_m)?)?;
+            let pk_x = X25519::public_key_from_bytes(pk_x)?;
+
+            let (ct_m, ss_m) = pk_m.encapsulate(rng)?;
+            let (ct_x, ss_x) = X25519::encapsulate(&pk_x, rng)?;
+
+            Ok((
+                KeyShare::concat(ct_m.as_ref(), &ct_x),
+                SharedSecret::concat(ss_m.as_bytes(), ss_x.as_ref()),
+            ))
+        }
+        NamedGroup::SecP256r1MlKem768 => {
+            let pk = nist::PublicKey::<nist::P256, MlKem768>::unpack(client_share)?;
+            let (ct, ss) = pk.encapsulate(rng)?;
+
+            Ok((
+                KeyShare::new(group.server_share_bytes(), |bytes| ct.pack(bytes))?,
+                ss,
+            ))
+        }
+        NamedGroup::MlKem768 => {
+            let pk = typed::PublicKey::<MlKem768>::try_from(DynPublicKey::unpack(client_share)?)?;
+            let (ct, ss) = pk.encapsulate(rng)?;
+
+            Ok((
+                KeyShare::from_bytes(ct.as_ref()),
+                SharedSecret::new(ss.as_bytes()),
+            ))
+        }
+        NamedGroup::MlKem1024 => {
+            let pk = typed::PublicKey::<MlKem1024>::try_from(DynPublicKey::unpack(client_share)?)?;
+            let (ct, ss) = pk.encapsulate(rng)?;
+
+            Ok((
+                KeyShare::from_bytes(ct.as_ref()),
+                SharedSecret::new(ss.as_bytes()),
+            ))
+        }
+    }
+}
+
+/// Completes the server side of the key exchange for `group`, using
+/// [`ChaCha20`](https://docs.rs/rand_chacha/latest/rand_chacha/struct.ChaCha20Rng.html)
+/// seeded from the system entropy source, see [`server_key_exchange`].
+///
+/// # Errors
+/// Will return an [`Error`] if:
+/// - RNG fails
+/// - The client's share is not valid for `group`
+///
+/// # Example
+/// ```
+/// # use enc_rust::tls::*;
+/// # let client = ClientKeyExchange::start_with_default_rng(NamedGroup::MlKem1024)?;
+/// # let client_share = client.key_share().as_bytes();
+/// let (server_share, shared_secret) =
+///     server_key_exchange_with_default_rng(NamedGroup::MlKem1024, client_share)?;
+///
+/// # Ok::<(), enc_rust::errors::Error>(())
+/// ```
+pub fn server_key_exchange_with_default_rng(
+    group: NamedGroup,
+    client_share: &[u8],
+) -> Result<(KeyShare, SharedSecret), Error> {
+    server_key_exchange(group, client_share, &mut ChaCha20Rng::from_entropy())
+}
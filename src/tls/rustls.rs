@@ -0,0 +1,96 @@
+//! [`rustls`](https://docs.rs/rustls/0.23) key exchange groups for `X25519MLKEM768` and
+//! `MLKEM768`, backed by the [`key_share`](super) helpers.
+//!
+//! The groups can be added to any
+//! [`CryptoProvider`](https://docs.rs/rustls/0.23/rustls/crypto/struct.CryptoProvider.html),
+//! keeping the rest of its algorithms. They are only offered for TLS 1.3.
+//!
+//! # Example
+//! ```
+//! use enc_rust::tls::rustls::{MLKEM768, X25519MLKEM768};
+//!
+//! let mut provider = rustls::crypto::ring::default_provider();
+//! provider.kx_groups.splice(0..0, [X25519MLKEM768, MLKEM768]);
+//!
+//! let config = rustls::ClientConfig::builder_with_provider(provider.into())
+//!     .with_protocol_versions(&[&rustls::version::TLS13])
+//!     .unwrap();
+//! ```
+
+use super::{server_key_exchange_with_default_rng, ClientKeyExchange, NamedGroup};
+use crate::errors::Error;
+use ::rustls::{
+    crypto::{ActiveKeyExchange, CompletedKeyExchange, SharedSecret, SupportedKxGroup},
+    Error as RustlsError, NamedGroup as RustlsNamedGroup, PeerMisbehaved, ProtocolVersion,
+};
+use alloc::{boxed::Box, format};
+
+/// `X25519MLKEM768`, ML-KEM-768 combined with X25519
+pub static X25519MLKEM768: &dyn SupportedKxGroup = &KxGroup(NamedGroup::X25519MlKem768);
+
+/// `MLKEM768`, ML-KEM-768 on its own
+pub static MLKEM768: &dyn SupportedKxGroup = &KxGroup(NamedGroup::MlKem768);
+
+/// All of the key exchange groups, in order of preference
+pub static ALL_KX_GROUPS: &[&dyn SupportedKxGroup] = &[X25519MLKEM768, MLKEM768];
+
+#[derive(Debug)]
+struct KxGroup(NamedGroup);
+
+impl SupportedKxGroup for KxGroup {
+    // Client: generate the ephemeral keys for the `ClientHello`
+    fn start(&self) -> Result<Box<dyn ActiveKeyExchange>, RustlsError> {
+        let kx = ClientKeyExchange::start_with_default_rng(self.0).map_err(to_rustls_error)?;
+
+        Ok(Box::new(ActiveKx(kx)))
+    }
+
+    // Server: encapsulate to the client's key share, as a KEM has no ephemeral server key
+    fn start_and_complete(&self, peer_pub_key: &[u8]) -> Result<CompletedKeyExchange, RustlsError> {
+        let (key_share, shared_secret) =
+            server_key_exchange_with_default_rng(self.0, peer_pub_key).map_err(to_rustls_error)?;
+
+        Ok(CompletedKeyExchange {
+            group: self.name(),
+            pub_key: key_share.as_bytes().to_vec(),
+            secret: SharedSecret::from(shared_secret.as_bytes()),
+        })
+    }
+
+    fn name(&self) -> RustlsNamedGroup {
+        RustlsNamedGroup::from(self.0.code_point())
+    }
+
+    fn usable_for_version(&self, version: ProtocolVersion) -> bool {
+        version == ProtocolVersion::TLSv1_3
+    }
+}
+
+struct ActiveKx(ClientKeyExchange);
+
+impl ActiveKeyExchange for ActiveKx {
+    fn complete(self: Box<Self>, peer_pub_key: &[u8]) -> Result<SharedSecret, RustlsError> {
+        let shared_secret = self.0.finish(peer_pub_key).map_err(to_rustls_error)?;
+
+        Ok(SharedSecret::from(shared_secret.as_bytes()))
+    }
+
+    fn pub_key(&self) -> &[u8] {
+        self.0.key_share().as_bytes()
+    }
+
+    fn group(&self) -> RustlsNamedGroup {
+        RustlsNamedGroup::from(self.0.group().code_point())
+    }
+}
+
+fn to_rustls_error(err: Error) -> RustlsError {
+    match err {
+        Error::RngFailure => RustlsError::FailedToGetRandomBytes,
+        Error::InvalidLength { .. }
+        | Error::UnsupportedLength { .. }
+        | Error::InvalidEncoding
+        | Error::ValidationFailure(_) => PeerMisbehaved::InvalidKeyShare.into(),
+        err => RustlsError::General(format!("key exchange failed: {err}")),
+    }
+}
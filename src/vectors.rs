@@ -1,9 +1,14 @@
 use crate::{
-    errors::{CrystalsError, PackingError},
-    params::{Eta, SecurityLevel, K, N, POLYBYTES, Q_DIV_VEC, Q_I16, Q_U32},
+    errors::Error,
+    params::{
+        Eta, SecurityLevel, K, N, POLYBYTES, Q_DIV_VEC, Q_DIV_VEC_11, Q_HALF_CEIL, Q_HALF_FLOOR,
+        Q_I16, Q_U32,
+    },
     polynomials::{Barrett, Montgomery, Normalised, Poly, Reduced, State, Unnormalised, Unreduced},
 };
+use subtle::{Choice, ConstantTimeEq};
 use tinyvec::ArrayVec;
+use zeroize::Zeroize;
 
 #[derive(Copy, Clone, Default, PartialEq, Debug, Eq)]
 pub struct PolyVec<S: State> {
@@ -11,6 +16,23 @@ pub struct PolyVec<S: State> {
     sec_level: K,
 }
 
+impl<S: State> Zeroize for PolyVec<S> {
+    fn zeroize(&mut self) {
+        self.polynomials.iter_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+// The security level is public, so only the polynomials are compared in constant time
+impl<S: State> ConstantTimeEq for PolyVec<S> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        if self.sec_level != other.sec_level {
+            return Choice::from(0);
+        }
+
+        self.polynomials().ct_eq(other.polynomials())
+    }
+}
+
 impl<S: State> PolyVec<S> {
     // Gets the security level of the given polyvec.
     pub(crate) const fn sec_level(&self) -> SecurityLevel {
@@ -23,9 +45,9 @@ impl<S: State> PolyVec<S> {
         &self.polynomials.as_slice()[..self.sec_level.into()]
     }
 
-    pub(crate) fn from(polynomials: ArrayVec<[Poly<S>; 4]>) -> Result<Self, CrystalsError> {
+    pub(crate) fn from(polynomials: ArrayVec<[Poly<S>; 4]>) -> Result<Self, Error> {
         K::try_from(polynomials.len()).map_or_else(
-            |_| Err(CrystalsError::InternalError()),
+            |_| Err(Error::Internal),
             |sec_level| {
                 Ok(Self {
                     polynomials,
@@ -37,10 +59,7 @@ impl<S: State> PolyVec<S> {
 
     // Add two polyvecs pointwise.
     // They must be the same security level.
-    pub(crate) fn add<T: State>(
-        &self,
-        addend: &PolyVec<T>,
-    ) -> Result<PolyVec<Unreduced>, CrystalsError> {
+    pub(crate) fn add<T: State>(&self, addend: &PolyVec<T>) -> Result<PolyVec<Unreduced>, Error> {
         if self.sec_level == addend.sec_level {
             let mut polynomials = ArrayVec::<[Poly<Unreduced>; 4]>::new();
             for (augend_poly, addend_poly) in self.polynomials.iter().zip(addend.polynomials.iter())
@@ -53,7 +72,7 @@ impl<S: State> PolyVec<S> {
                 sec_level: self.sec_level,
             })
         } else {
-            Err(CrystalsError::MismatchedSecurityLevels(
+            Err(Error::parameter_mismatch(
                 self.sec_level(),
                 addend.sec_level(),
             ))
@@ -122,14 +141,13 @@ impl<S: State + Reduced + Copy> PolyVec<S> {
 impl PolyVec<Normalised> {
     // buf should be of length k * POLYBYTES
     // packs the polyvec poly-wise into the buffer
-    pub(crate) fn pack(&self, buf: &mut [u8]) -> Result<(), PackingError> {
+    pub(crate) fn pack(&self, buf: &mut [u8]) -> Result<(), Error> {
         if buf.len() != self.polynomials.len() * POLYBYTES {
             let buffer_sec_level = SecurityLevel::new(K::try_from(buf.len() / POLYBYTES)?);
-            return Err(CrystalsError::MismatchedSecurityLevels(
-                buffer_sec_level,
+            return Err(Error::parameter_mismatch(
                 self.sec_level(),
-            )
-            .into());
+                buffer_sec_level,
+            ));
         }
 
         for (k, poly) in self.polynomials.iter().enumerate() {
@@ -141,13 +159,12 @@ impl PolyVec<Normalised> {
 
     // buf should be of length poly_vec_compressed_bytes
     // compresses the polyvec poly-wise into the buffer
-    pub(crate) fn compress(&self, buf: &mut [u8]) -> Result<(), PackingError> {
+    pub(crate) fn compress(&self, buf: &mut [u8]) -> Result<(), Error> {
         if buf.len() != self.sec_level().poly_vec_compressed_bytes() {
-            return Err(CrystalsError::IncorrectBufferLength(
-                buf.len(),
-                self.sec_level().poly_vec_compressed_bytes(),
-            )
-            .into());
+            return Err(Error::InvalidLength {
+                expected: self.sec_level().poly_vec_compressed_bytes(),
+                actual: buf.len(),
+            });
         }
 
         match self.sec_level() {
@@ -168,8 +185,8 @@ impl PolyVec<Normalised> {
                             *t_elem = *coeff as u16;
                             *t_elem =
                                 t_elem.wrapping_add((((*t_elem as i16) >> 15) & Q_I16) as u16);
-                            *t_elem = (((((u64::from(*t_elem) << 10) + u64::from(Q_U32 / 2))
-                                * Q_DIV_VEC)
+                            // round(2^10 / Q * x)
+                            *t_elem = (((((u64::from(*t_elem) << 10) + Q_HALF_CEIL) * Q_DIV_VEC)
                                 >> 32)
                                 & 0x3ff) as u16;
                         }
@@ -211,8 +228,9 @@ impl PolyVec<Normalised> {
                             *t_elem = *coeff as u16;
                             *t_elem =
                                 t_elem.wrapping_add((((*t_elem as i16) >> 15) & Q_I16) as u16);
-                            *t_elem = (((((u64::from(*t_elem) << 11) + u64::from(Q_U32 / 2))
-                                * (Q_DIV_VEC / 2))
+                            // round(2^11 / Q * x)
+                            *t_elem = (((((u64::from(*t_elem) << 11) + Q_HALF_FLOOR)
+                                * Q_DIV_VEC_11)
                                 >> 31)
                                 & 0x7ff) as u16;
                         }
@@ -242,7 +260,7 @@ impl PolyVec<Normalised> {
     // The buffer should be of length k * POLYBYTES.
     // If the length of the buffer is incorrect, the operation can still succeed provided it is a valid
     // multiple of POLYBYTES, and will result in a polyvec of incorrect security level.
-    pub fn unpack(buf: &[u8]) -> Result<PolyVec<Unreduced>, PackingError> {
+    pub fn unpack(buf: &[u8]) -> Result<PolyVec<Unreduced>, Error> {
         let sec_level = K::try_from(buf.len() / POLYBYTES)?; // If this fails then we know the
                                                              // buffer is not of the right size and
                                                              // so no further checks are needed.;
@@ -250,7 +268,7 @@ impl PolyVec<Normalised> {
         let polyvec_result = buf
             .chunks(POLYBYTES)
             .map(Poly::unpack)
-            .collect::<Result<ArrayVec<[Poly<Unreduced>; 4]>, PackingError>>();
+            .collect::<Result<ArrayVec<[Poly<Unreduced>; 4]>, Error>>();
 
         match polyvec_result {
             Ok(polynomials) => Ok(PolyVec {
@@ -265,14 +283,12 @@ impl PolyVec<Normalised> {
     // The buffer should be of length poly_vec_compressed_bytes.
     // If the length of the buffer is incorrect, the operation can still succeed provided it is a valid
     // poly_vec_compressed_bytes, and will result in a polyvec of incorrect security level.
-    pub(crate) fn decompress(buf: &[u8]) -> Result<Self, PackingError> {
+    pub(crate) fn decompress(buf: &[u8]) -> Result<Self, Error> {
         let sec_level = match buf.len() {
             640 => Ok(SecurityLevel::new(K::Two)),
             960 => Ok(SecurityLevel::new(K::Three)),
             1408 => Ok(SecurityLevel::new(K::Four)),
-            _ => Err(PackingError::Crystals(
-                CrystalsError::IncorrectBufferLength(buf.len(), 0),
-            )),
+            _ => Err(Error::UnsupportedLength { actual: buf.len() }),
         }?;
 
         let polynomials = match sec_level {